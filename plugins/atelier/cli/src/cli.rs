@@ -3,13 +3,14 @@
 //! subsystem's own clap surface. This keeps the subsystems' argument grammars
 //! independent and unchanged from their standalone CLIs.
 
+use crate::shared::trace;
 use clap::Parser;
 
 #[derive(Parser)]
 #[command(
     name = "atelier",
     version,
-    about = "Unified development workflow CLI (drift, git, session)"
+    about = "Unified development workflow CLI (drift, git, session, state, hook-dispatch, doctor)"
 )]
 pub struct AtelierCli {
     #[command(subcommand)]
@@ -39,13 +40,50 @@ pub enum AtelierCommand {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Inspect and sweep atelier's on-disk state (info / gc)
+    #[command(disable_help_flag = true)]
+    State {
+        /// Arguments forwarded verbatim to the state subsystem
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// One hook entry point routing any event to every enabled handler
+    #[command(name = "hook-dispatch", disable_help_flag = true)]
+    HookDispatch {
+        /// Arguments forwarded verbatim to the dispatch subsystem
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Check the environment atelier depends on (tools, auth, hooks, drift, state)
+    #[command(disable_help_flag = true)]
+    Doctor {
+        /// Arguments forwarded verbatim to the doctor subsystem
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
 }
 
 /// Parses argv and dispatches to the selected subsystem, returning a process
 /// exit code. Each subsystem re-parses its own args so its grammar (and
 /// `--help`/`--version`) behaves exactly as the standalone CLI did.
+///
+/// The whole command runs inside one structured-log span named after the
+/// subsystem and its first argument (`git guard`, `session baseline`, …).
 pub fn run() -> i32 {
     let cli = AtelierCli::parse();
+    let (subsystem, args) = match &cli.command {
+        AtelierCommand::Drift { args } => ("drift", args),
+        AtelierCommand::Git { args } => ("git", args),
+        AtelierCommand::Session { args } => ("session", args),
+        AtelierCommand::State { args } => ("state", args),
+        AtelierCommand::HookDispatch { args } => ("hook-dispatch", args),
+        AtelierCommand::Doctor { args } => ("doctor", args),
+    };
+    let name = match args.first().filter(|a| !a.starts_with('-')) {
+        Some(first) => format!("{subsystem} {first}"),
+        None => subsystem.to_string(),
+    };
+    let _span = trace::span(name);
     match cli.command {
         AtelierCommand::Drift { args } => {
             let argv = std::iter::once("drift".to_string()).chain(args);
@@ -59,5 +97,17 @@ pub fn run() -> i32 {
             let argv = std::iter::once("session".to_string()).chain(args);
             crate::session::run_from(argv)
         }
+        AtelierCommand::State { args } => {
+            let argv = std::iter::once("state".to_string()).chain(args);
+            crate::state::run_from(argv)
+        }
+        AtelierCommand::HookDispatch { args } => {
+            let argv = std::iter::once("hook-dispatch".to_string()).chain(args);
+            crate::dispatch::run_from(argv)
+        }
+        AtelierCommand::Doctor { args } => {
+            let argv = std::iter::once("doctor".to_string()).chain(args);
+            crate::doctor::run_from(argv)
+        }
     }
 }
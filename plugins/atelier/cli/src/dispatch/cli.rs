@@ -0,0 +1,60 @@
+//! The dispatch clap surface: reads the hook payload off stdin and prints the
+//! merged decision.
+
+use crate::dispatch::route::DispatchEvent;
+use crate::git::commands::guard::{GuardConfig, GuardTargetKind};
+use crate::shared::process::{default_project_dir, read_stdin_raw};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(
+    name = "hook-dispatch",
+    version,
+    about = "Route one Claude Code hook payload to every enabled atelier handler"
+)]
+pub struct Cli {
+    /// Project the handlers are anchored to; the payload cwd if absent
+    #[arg(long = "project-dir")]
+    pub project_dir: Option<String>,
+    /// Forwarded to the branch guards (see `atelier git guard`)
+    #[arg(long = "create-branch-script")]
+    pub create_branch_script: Option<String>,
+    #[arg(long = "default-branch")]
+    pub default_branch: Option<String>,
+    #[arg(long = "protected-branches")]
+    pub protected_branches: Option<String>,
+}
+
+/// Parses `argv` (including the leading program name) and dispatches the
+/// payload on stdin. Always returns 0.
+pub fn run_from<I, T>(argv: I) -> i32
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    run(Cli::parse_from(argv))
+}
+
+pub fn run(cli: Cli) -> i32 {
+    let event = DispatchEvent::parse(&read_stdin_raw());
+    let project_dir = default_project_dir(
+        cli.project_dir
+            .filter(|d| !d.is_empty())
+            .or_else(|| event.session.cwd.clone().filter(|d| !d.is_empty())),
+    );
+    let guard = GuardConfig {
+        create_branch_script: cli.create_branch_script.unwrap_or_default(),
+        default_branch: cli.default_branch,
+        protected_branches: cli.protected_branches.map(|raw| {
+            raw.split(',')
+                .map(|b| b.trim().to_string())
+                .filter(|b| !b.is_empty())
+                .collect()
+        }),
+        ..GuardConfig::new(GuardTargetKind::Write, project_dir)
+    };
+    if let Some(json) = super::evaluate(&event, &guard).render() {
+        println!("{json}");
+    }
+    0
+}
@@ -0,0 +1,56 @@
+//! The production `HandlerRunner`: each handler is the same command layer its
+//! standalone hook runs, bound to the real git, GitHub and baseline store.
+
+use crate::dispatch::route::HandlerRunner;
+use crate::git::commands::guard::{
+    self, GuardCommandDeps, GuardConfig, GuardTargetKind, HookPayload,
+};
+use crate::git::types::GuardDecision;
+use crate::session::commands::advise::{self, Advice};
+use crate::session::commands::baseline;
+use crate::session::commands::payload::SessionPayload;
+use crate::session::core::transcript::create_transcript_source;
+use crate::session::with_project_deps;
+use crate::shared::config::SessionConfig;
+use crate::shared::trace;
+
+pub struct RealHandlers<'a> {
+    /// Branch-guard settings; `target` is replaced per handler, and
+    /// `project_dir` anchors the session handlers too.
+    pub guard: &'a GuardConfig,
+    /// The git and GitHub services every guard target of one event shares,
+    /// so a Bash call checked for both commit and PR detects things once.
+    pub guard_deps: &'a GuardCommandDeps<'a>,
+    pub session: &'a SessionConfig,
+}
+
+impl HandlerRunner for RealHandlers<'_> {
+    fn guard(&self, target: GuardTargetKind, payload: &HookPayload) -> GuardDecision {
+        let _span = trace::span(format!("guard-{}", target.name()));
+        let config = GuardConfig {
+            target,
+            ..self.guard.clone()
+        };
+        guard::run(self.guard_deps, &config.input(payload.clone()))
+    }
+
+    fn baseline(&self, payload: &SessionPayload) {
+        let _span = trace::span("baseline");
+        with_project_deps(&self.guard.project_dir, |deps| {
+            baseline::run(deps, payload.session_id.as_deref().unwrap_or_default())
+        })
+    }
+
+    fn advise(&self, payload: &SessionPayload) -> Vec<Advice> {
+        let _span = trace::span("advise");
+        let transcript = create_transcript_source(payload.transcript_path.clone());
+        with_project_deps(&self.guard.project_dir, |deps| {
+            advise::run(
+                deps,
+                &transcript,
+                self.session,
+                payload.session_id.as_deref().unwrap_or_default(),
+            )
+        })
+    }
+}
@@ -0,0 +1,75 @@
+//! Dispatch subsystem — one hook entry point for every Claude Code event
+//! atelier handles, so a single registration per event replaces one process
+//! per handler (each re-reading git on its own).
+//!
+//! ```text
+//! atelier hook-dispatch [--project-dir <dir>] [--default-branch <b>] [--protected-branches <a,b>] [--create-branch-script <s>]
+//! ```
+//!
+//! The payload's `hook_event_name` and `tool_name` pick the handlers
+//! (`route::Handler`): the write guard for `Write`/`Edit`/`MultiEdit`, the
+//! commit and PR guards for `Bash`, the baseline on SessionStart and the
+//! advisors on Stop. Each can be switched off per project under
+//! `dispatch.handlers` in `.claude/atelier.json`.
+//!
+//! Output contract: always exit 0, with Claude Code's JSON decision on stdout
+//! (`shared::hook_output`) — any guard block wins as
+//! `permissionDecision: "deny"`, otherwise a guard that wants confirmation
+//! makes it `"ask"`, and advisor banners become `systemMessage` — and nothing
+//! at all when every handler passed.
+
+#[cfg(feature = "cli")]
+mod cli;
+pub mod handlers;
+pub mod route;
+
+#[cfg(feature = "cli")]
+pub use cli::{run, run_from, Cli};
+
+use crate::dispatch::handlers::RealHandlers;
+use crate::dispatch::route::DispatchEvent;
+use crate::git::commands::guard::{self, GuardConfig};
+use crate::shared::config::ProjectConfig;
+use crate::shared::hook_output::HookOutput;
+use crate::shared::trace;
+
+/// Library entry for `hook-dispatch`: runs the handlers `event` reaches in
+/// `guard.project_dir` (whose `target` is ignored) and merges their answers.
+/// The commit-message policy comes from that project's config file.
+pub fn evaluate(event: &DispatchEvent, guard: &GuardConfig) -> HookOutput {
+    let config = ProjectConfig::load(&guard.project_dir);
+    let handlers = route::handlers_for(event, &config.dispatch);
+    let guard = GuardConfig {
+        commit_message: config.git.commit_message.clone(),
+        ..guard.clone()
+    };
+    trace::info(
+        "dispatch_route",
+        serde_json::json!({
+            "hook_event_name": event.hook_event_name,
+            "tool_name": event.tool_name,
+            "handlers": handlers.iter().map(|h| h.id()).collect::<Vec<_>>(),
+        }),
+    );
+    // Building the guard services runs nothing: git and gh are only called
+    // once a guard target asks, so session-only events pay nothing for them.
+    let output = guard::with_real_deps(&guard.project_dir, |guard_deps| {
+        let runner = RealHandlers {
+            guard: &guard,
+            guard_deps,
+            session: &config.session,
+        };
+        route::dispatch(event, &handlers, &runner)
+    });
+    trace::info(
+        "dispatch_decision",
+        serde_json::json!({
+            "permission_decision": output
+                .hook_specific_output
+                .as_ref()
+                .and_then(|o| o.permission_decision),
+            "system_message": output.system_message.is_some(),
+        }),
+    );
+    output
+}
@@ -0,0 +1,173 @@
+//! Routing and merging for `hook-dispatch`: which handlers an event reaches,
+//! and how their answers fold into one Claude Code decision. Pure — the
+//! handlers themselves are behind `HandlerRunner`, so the whole table is
+//! exercised in memory.
+
+use crate::git::commands::guard::{GuardTargetKind, HookPayload};
+use crate::git::types::GuardDecision;
+use crate::session::commands::advise::Advice;
+use crate::session::commands::payload::SessionPayload;
+use crate::shared::config::DispatchConfig;
+use crate::shared::hook_output::{HookOutput, HookSpecificOutput, PermissionDecision};
+
+/// Hook events with at least one handler.
+pub const PRE_TOOL_USE: &str = "PreToolUse";
+pub const SESSION_START: &str = "SessionStart";
+pub const STOP: &str = "Stop";
+
+/// Tools whose `tool_input.file_path` the write guard checks.
+const WRITE_TOOLS: [&str; 3] = ["Write", "Edit", "MultiEdit"];
+
+/// One hook payload, parsed once for every handler. `parse` is swallow-all
+/// like the payloads it wraps: an unreadable stdin routes to no handler.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchEvent {
+    pub hook_event_name: Option<String>,
+    pub tool_name: Option<String>,
+    pub guard: HookPayload,
+    pub session: SessionPayload,
+}
+
+impl DispatchEvent {
+    pub fn parse(raw: &str) -> DispatchEvent {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(raw) else {
+            return DispatchEvent::default();
+        };
+        DispatchEvent {
+            hook_event_name: value["hook_event_name"].as_str().map(|s| s.to_string()),
+            tool_name: value["tool_name"].as_str().map(|s| s.to_string()),
+            guard: HookPayload::parse(raw),
+            session: SessionPayload::parse(raw),
+        }
+    }
+}
+
+/// An atelier hook handler the dispatcher can route to. `id` is the switch
+/// name under `dispatch.handlers` in `.claude/atelier.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handler {
+    GuardWrite,
+    GuardCommit,
+    GuardPr,
+    Baseline,
+    Advise,
+}
+
+impl Handler {
+    pub const ALL: [Handler; 5] = [
+        Handler::GuardWrite,
+        Handler::GuardCommit,
+        Handler::GuardPr,
+        Handler::Baseline,
+        Handler::Advise,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Handler::GuardWrite => "guard-write",
+            Handler::GuardCommit => "guard-commit",
+            Handler::GuardPr => "guard-pr",
+            Handler::Baseline => "baseline",
+            Handler::Advise => "advise",
+        }
+    }
+
+    /// Whether this handler wants `event` — the matcher each one would have
+    /// been registered with on its own.
+    fn accepts(self, event: &DispatchEvent) -> bool {
+        let name = event.hook_event_name.as_deref();
+        let tool = event.tool_name.as_deref().unwrap_or_default();
+        match self {
+            Handler::GuardWrite => name == Some(PRE_TOOL_USE) && WRITE_TOOLS.contains(&tool),
+            Handler::GuardCommit | Handler::GuardPr => name == Some(PRE_TOOL_USE) && tool == "Bash",
+            Handler::Baseline => name == Some(SESSION_START),
+            Handler::Advise => name == Some(STOP),
+        }
+    }
+}
+
+/// The enabled handlers that accept `event`, in `Handler::ALL` order.
+pub fn handlers_for(event: &DispatchEvent, config: &DispatchConfig) -> Vec<Handler> {
+    Handler::ALL
+        .into_iter()
+        .filter(|h| h.accepts(event) && config.handler_enabled(h.id()))
+        .collect()
+}
+
+/// The handlers' side of the world. One implementation binds the real
+/// services; tests substitute their own.
+pub trait HandlerRunner {
+    fn guard(&self, target: GuardTargetKind, payload: &HookPayload) -> GuardDecision;
+    fn baseline(&self, payload: &SessionPayload);
+    fn advise(&self, payload: &SessionPayload) -> Vec<Advice>;
+}
+
+/// Runs every handler and merges their answers: any block wins and carries
+/// every block reason and context, then any ask does the same; advice
+/// becomes the user-facing message. Handlers that pass contribute nothing,
+/// so an all-clear event yields an empty output.
+pub fn dispatch(
+    event: &DispatchEvent,
+    handlers: &[Handler],
+    runner: &dyn HandlerRunner,
+) -> HookOutput {
+    let mut denials: Vec<GuardDecision> = Vec::new();
+    let mut asks: Vec<GuardDecision> = Vec::new();
+    let mut messages: Vec<String> = Vec::new();
+    for &handler in handlers {
+        let target = match handler {
+            Handler::GuardWrite => GuardTargetKind::Write,
+            Handler::GuardCommit => GuardTargetKind::Commit,
+            Handler::GuardPr => GuardTargetKind::Pr,
+            Handler::Baseline => {
+                runner.baseline(&event.session);
+                continue;
+            }
+            Handler::Advise => {
+                messages.extend(runner.advise(&event.session).into_iter().map(|a| a.message));
+                continue;
+            }
+        };
+        let mut decision = runner.guard(target, &event.guard);
+        let verdicts = match decision.permission() {
+            Some(PermissionDecision::Deny) => &mut denials,
+            Some(PermissionDecision::Ask) => &mut asks,
+            None => continue,
+        };
+        decision
+            .reason
+            .get_or_insert_with(|| format!("flagged by atelier {}", handler.id()));
+        verdicts.push(decision);
+    }
+
+    // A block outranks a question: asking about a call another guard refuses
+    // would offer the user an approval that cannot take effect.
+    let verdict = if !denials.is_empty() {
+        Some((PermissionDecision::Deny, denials))
+    } else if !asks.is_empty() {
+        Some((PermissionDecision::Ask, asks))
+    } else {
+        None
+    };
+    let hook_specific_output = verdict.map(|(decision, verdicts)| {
+        let reasons: Vec<String> = verdicts.iter().filter_map(|v| v.reason.clone()).collect();
+        // Two guards on one event see the same branch: say it once.
+        let mut contexts: Vec<String> = Vec::new();
+        for context in verdicts.into_iter().filter_map(|v| v.context) {
+            if !contexts.contains(&context) {
+                contexts.push(context);
+            }
+        }
+        HookSpecificOutput {
+            hook_event_name: event.hook_event_name.clone().unwrap_or_default(),
+            permission_decision: Some(decision),
+            permission_decision_reason: Some(reasons.join("\n\n")),
+            additional_context: Some(contexts.join("\n")).filter(|c| !c.is_empty()),
+        }
+    });
+    let message = messages.concat();
+    HookOutput {
+        hook_specific_output,
+        system_message: Some(message.trim_end().to_string()).filter(|m| !m.is_empty()),
+    }
+}
@@ -0,0 +1,325 @@
+//! The diagnostics themselves: each check reads the environment through the
+//! same service trait its subsystem uses at runtime, so a mock world drives
+//! every verdict in tests, and the report only renders.
+//!
+//! A `fail` is something that breaks atelier outright (no git, a settings
+//! file Claude Code cannot parse, stale guards still firing, an unwritable
+//! state dir); a `warn` degrades it (no gh, no origin/HEAD, drift).
+
+use crate::doctor::probe::{probe_writable, ToolProbe};
+use crate::drift::commands::{check as drift_check, DriftDeps};
+use crate::drift::core::types::{ArtifactStatus, DriftPaths};
+use crate::git::commands::guard_setup::{guard_generation, GuardGeneration};
+use crate::git::commands::hook::{settings_path, HookCommand};
+use crate::git::core::git::{GitService, OriginHeadWarmer};
+use crate::git::core::github::GhAuth;
+use crate::git::types::{CmdResult, HookListInput};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+fn check(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Check {
+    Check {
+        name,
+        status,
+        detail: detail.into(),
+    }
+}
+
+/// Output format for `atelier doctor`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum DoctorFormat {
+    /// Aligned check/status/detail table
+    #[default]
+    Table,
+    /// `{"checks": [...]}` for scripts
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// 1 when any check failed; warnings alone leave atelier usable.
+    pub fn exit_code(&self) -> i32 {
+        if self.count(CheckStatus::Fail) > 0 {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn render(&self, format: DoctorFormat) -> String {
+        match format {
+            DoctorFormat::Json => {
+                let mut json =
+                    serde_json::to_string_pretty(self).unwrap_or_else(|_| "null".to_string());
+                json.push('\n');
+                json
+            }
+            DoctorFormat::Table => self.render_table(),
+        }
+    }
+
+    fn render_table(&self) -> String {
+        let width = self
+            .checks
+            .iter()
+            .map(|c| c.name.len())
+            .chain(["CHECK".len()])
+            .max()
+            .unwrap_or_default();
+        let mut out = format!("{:<width$}  STATUS  DETAIL\n", "CHECK");
+        for c in &self.checks {
+            out.push_str(&format!(
+                "{:<width$}  {:<6}  {}\n",
+                c.name,
+                c.status.as_str(),
+                c.detail
+            ));
+        }
+        out.push_str(&format!(
+            "→ {} passed, {} warned, {} failed\n",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        ));
+        out
+    }
+}
+
+pub struct DoctorDeps<'a> {
+    pub tools: &'a dyn ToolProbe,
+    pub gh_auth: &'a dyn GhAuth,
+    pub git: &'a dyn GitService,
+    pub warmer: &'a dyn OriginHeadWarmer,
+    pub hook: &'a HookCommand<'a>,
+    pub drift: &'a DriftDeps<'a>,
+}
+
+pub struct DoctorInput {
+    pub project_dir: String,
+    /// `$HOME`, whose `.claude/settings.json` is the user scope; `None`
+    /// checks the project scope only.
+    pub home: Option<String>,
+    /// `None` when no plugin root is known — drift is then skipped.
+    pub drift: Option<DriftPaths>,
+    pub state_dir: PathBuf,
+}
+
+pub fn run(deps: &DoctorDeps, input: &DoctorInput) -> DoctorReport {
+    let git = tool(deps, "git", CheckStatus::Fail);
+    let gh = tool(deps, "gh", CheckStatus::Warn);
+    let gh_auth = if gh.status == CheckStatus::Pass {
+        gh_auth(deps)
+    } else {
+        check("gh-auth", CheckStatus::Warn, "skipped: gh is not on PATH")
+    };
+    let origin_head = if git.status == CheckStatus::Pass {
+        origin_head(deps)
+    } else {
+        check(
+            "origin-head",
+            CheckStatus::Warn,
+            "skipped: git is not on PATH",
+        )
+    };
+    DoctorReport {
+        checks: vec![
+            git,
+            gh,
+            gh_auth,
+            origin_head,
+            guard_hooks(deps, input),
+            drift(deps, input.drift.as_ref()),
+            state_dir(input),
+        ],
+    }
+}
+
+fn tool(deps: &DoctorDeps, name: &'static str, missing: CheckStatus) -> Check {
+    match deps.tools.version(name) {
+        Some(version) => check(name, CheckStatus::Pass, version),
+        None => check(name, missing, format!("`{name}` is not on PATH")),
+    }
+}
+
+fn gh_auth(deps: &DoctorDeps) -> Check {
+    let status = deps.gh_auth.auth_status();
+    let host = status
+        .host
+        .unwrap_or_else(|| "gh default host (no GH_HOST in ~/.git-workflow-env)".to_string());
+    if status.authenticated {
+        check("gh-auth", CheckStatus::Pass, host)
+    } else {
+        check(
+            "gh-auth",
+            CheckStatus::Warn,
+            format!("not authenticated against {host}: {}", status.detail),
+        )
+    }
+}
+
+fn origin_head(deps: &DoctorDeps) -> Check {
+    if !deps.git.is_inside_work_tree() {
+        return check("origin-head", CheckStatus::Warn, "not a git repository");
+    }
+    match deps.warmer.origin_head() {
+        Some(branch) => check("origin-head", CheckStatus::Pass, format!("origin/{branch}")),
+        None => check(
+            "origin-head",
+            CheckStatus::Warn,
+            "not warmed — run `atelier git setup guard` or `git remote set-head origin --auto`",
+        ),
+    }
+}
+
+/// Every hook command in one settings.json, in file order.
+fn hook_commands(hooks: &Value) -> Vec<String> {
+    let Some(types) = hooks.as_object() else {
+        return Vec::new();
+    };
+    types
+        .values()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|entry| entry["hooks"].as_array())
+        .flatten()
+        .filter_map(|hook| hook["command"].as_str().map(str::to_string))
+        .collect()
+}
+
+fn guard_hooks(deps: &DoctorDeps, input: &DoctorInput) -> Check {
+    const NAME: &str = "guard-hooks";
+    // Run from $HOME, the project scope *is* the user scope: read it once.
+    let user = input.home.as_deref().filter(|h| *h != input.project_dir);
+    let scopes = [Some(input.project_dir.as_str()), user];
+    let mut dispatched = false;
+    let mut stale: Vec<String> = Vec::new();
+    for dir in scopes.into_iter().flatten() {
+        let listed = deps.hook.list(&HookListInput {
+            hook_type: None,
+            project_dir: Some(dir.to_string()),
+        });
+        let hooks = match listed {
+            Ok(CmdResult::Ok(hooks)) => hooks,
+            Ok(CmdResult::Err(e)) | Err(e) => {
+                return check(
+                    NAME,
+                    CheckStatus::Fail,
+                    format!("cannot read {}: {e}", settings_path(dir)),
+                )
+            }
+        };
+        for command in hook_commands(&hooks) {
+            match guard_generation(&command) {
+                // `hook-dispatch` runs every guard in one process
+                Some(GuardGeneration::Current) => dispatched = true,
+                Some(GuardGeneration::Stale) => stale.push(command),
+                None => {}
+            }
+        }
+    }
+
+    if !stale.is_empty() {
+        return check(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "stale guard registration — re-run /atelier:setup: {}",
+                stale.join("; ")
+            ),
+        );
+    }
+    if dispatched {
+        check(
+            NAME,
+            CheckStatus::Pass,
+            "write and commit guards registered",
+        )
+    } else {
+        check(
+            NAME,
+            CheckStatus::Warn,
+            "no guard registered — run `atelier git setup guard`",
+        )
+    }
+}
+
+fn drift(deps: &DoctorDeps, paths: Option<&DriftPaths>) -> Check {
+    const NAME: &str = "drift";
+    let Some(paths) = paths else {
+        return check(
+            NAME,
+            CheckStatus::Warn,
+            "skipped: no plugin root (pass --plugin-root or set CLAUDE_PLUGIN_ROOT)",
+        );
+    };
+    match drift_check::run(deps.drift, paths) {
+        Err(e) => check(NAME, CheckStatus::Warn, e),
+        Ok(report) => {
+            let drifted: Vec<&str> = report
+                .findings
+                .iter()
+                .filter(|f| f.status == ArtifactStatus::Drifted)
+                .map(|f| f.name.as_str())
+                .collect();
+            if drifted.is_empty() {
+                check(
+                    NAME,
+                    CheckStatus::Pass,
+                    "installed artifacts match the plugin",
+                )
+            } else {
+                check(
+                    NAME,
+                    CheckStatus::Warn,
+                    format!("{} drifted — run /atelier:update", drifted.join(", ")),
+                )
+            }
+        }
+    }
+}
+
+fn state_dir(input: &DoctorInput) -> Check {
+    match probe_writable(&input.state_dir) {
+        Ok(()) => check(
+            "state-dir",
+            CheckStatus::Pass,
+            input.state_dir.display().to_string(),
+        ),
+        Err(e) => check("state-dir", CheckStatus::Fail, e),
+    }
+}
@@ -0,0 +1,64 @@
+//! The doctor clap surface: resolves path defaults from the environment,
+//! prints the report and returns its exit code.
+
+use crate::doctor::checks::{DoctorFormat, DoctorInput};
+use crate::drift::core::types::DriftPaths;
+use crate::shared::process::default_project_dir;
+use crate::shared::state::state_root;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(
+    name = "doctor",
+    version,
+    about = "Check the environment atelier depends on"
+)]
+pub struct Cli {
+    /// Project whose repository and settings.json are checked (default: cwd)
+    #[arg(long = "project-dir")]
+    pub project_dir: Option<String>,
+    /// Plugin root for the drift check (default: $CLAUDE_PLUGIN_ROOT)
+    #[arg(long = "plugin-root")]
+    pub plugin_root: Option<String>,
+    /// User CLAUDE.md for the drift check (default: $HOME/.claude/CLAUDE.md)
+    #[arg(long = "claude-md")]
+    pub claude_md: Option<String>,
+    #[arg(long = "format", value_enum, default_value_t = DoctorFormat::Table)]
+    pub format: DoctorFormat,
+}
+
+/// Parses `argv` (including the leading program name) and runs every check.
+pub fn run_from<I, T>(argv: I) -> i32
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    run(Cli::parse_from(argv))
+}
+
+pub fn run(cli: Cli) -> i32 {
+    let non_empty = |v: Option<String>| v.filter(|v| !v.is_empty());
+    let home = non_empty(std::env::var("HOME").ok());
+    let project_dir = default_project_dir(cli.project_dir);
+    let claude_md = cli
+        .claude_md
+        .or_else(|| home.as_ref().map(|h| format!("{h}/.claude/CLAUDE.md")));
+    let drift = non_empty(
+        cli.plugin_root
+            .or_else(|| std::env::var("CLAUDE_PLUGIN_ROOT").ok()),
+    )
+    .zip(claude_md)
+    .map(|(plugin_root, claude_md)| DriftPaths {
+        plugin_root,
+        claude_md,
+        project_dir: project_dir.clone(),
+    });
+    let report = super::diagnose(&DoctorInput {
+        project_dir,
+        home,
+        drift,
+        state_dir: state_root(),
+    });
+    print!("{}", report.render(cli.format));
+    report.exit_code()
+}
@@ -0,0 +1,52 @@
+//! Doctor subsystem — the checks we used to run by hand when the plugin
+//! misbehaves, as one command:
+//!
+//! ```text
+//! atelier doctor [--project-dir <dir>] [--plugin-root <dir>] [--claude-md <path>] [--format table|json]
+//! ```
+//!
+//! `git` and `gh` on PATH, gh authenticated against the `GH_HOST` from
+//! `~/.git-workflow-env`, `origin/HEAD` warmed, settings.json holding
+//! current-generation guard registrations (project and user scope), drift
+//! clean, and the state dir writable. Each check goes through the service
+//! trait its subsystem already uses (`checks::DoctorDeps`).
+//!
+//! Output: a pass/warn/fail table, or `--format json`; exit 1 when any check
+//! failed, 0 otherwise.
+
+pub mod checks;
+#[cfg(feature = "cli")]
+mod cli;
+pub mod probe;
+
+#[cfg(feature = "cli")]
+pub use cli::{run, run_from, Cli};
+
+use crate::doctor::checks::{DoctorDeps, DoctorInput, DoctorReport};
+use crate::doctor::probe::create_tool_probe;
+use crate::git::commands::hook::{create_hook_command, RealHookFs};
+use crate::git::core::git::create_git_service;
+use crate::git::core::github::create_github_service;
+
+/// Library entry for `doctor`: runs every check against the real
+/// environment, with git and gh pinned to `input.project_dir`.
+pub fn diagnose(input: &DoctorInput) -> DoctorReport {
+    let tools = create_tool_probe();
+    let git = create_git_service(Some(input.project_dir.clone()));
+    let github = create_github_service(Some(input.project_dir.clone()));
+    let fs = RealHookFs;
+    let hook = create_hook_command(&fs);
+    crate::drift::with_real_deps(|drift| {
+        checks::run(
+            &DoctorDeps {
+                tools: &tools,
+                gh_auth: &github,
+                git: &git,
+                warmer: &git,
+                hook: &hook,
+                drift,
+            },
+            input,
+        )
+    })
+}
@@ -0,0 +1,53 @@
+//! The environment reads `doctor` needs that no other subsystem makes: is a
+//! tool on PATH, and can the state directory take a write.
+
+use crate::shared::shell::{exec, ExecOptions};
+use std::path::Path;
+use std::time::Duration;
+
+/// Deadline for a `<tool> --version` call.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub trait ToolProbe {
+    /// First line of `<tool> --version`, `None` when the tool cannot be run.
+    fn version(&self, tool: &str) -> Option<String>;
+}
+
+pub struct RealToolProbe;
+
+pub fn create_tool_probe() -> RealToolProbe {
+    RealToolProbe
+}
+
+impl ToolProbe for RealToolProbe {
+    fn version(&self, tool: &str) -> Option<String> {
+        let r = exec(
+            &[tool, "--version"],
+            Some(&ExecOptions {
+                timeout: Some(VERSION_TIMEOUT),
+                ..ExecOptions::default()
+            }),
+        );
+        if r.exit_code != 0 {
+            return None;
+        }
+        Some(
+            r.stdout
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        )
+    }
+}
+
+/// Creates `dir` if needed and round-trips a scratch file through it — the
+/// same operations the baseline store and the log perform.
+pub fn probe_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+    let scratch = dir.join(format!(".doctor.{}.tmp", std::process::id()));
+    std::fs::write(&scratch, b"ok").map_err(|e| format!("cannot write {}: {e}", dir.display()))?;
+    let _ = std::fs::remove_file(&scratch);
+    Ok(())
+}
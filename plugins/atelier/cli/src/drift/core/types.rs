@@ -74,7 +74,8 @@ pub enum ArtifactContent {
 /// Which installed copy a `drift sync` run updates. A closed enum rather than
 /// a string so an unknown target dies at the clap boundary (exit 2), never
 /// deep inside the command.
-#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum SyncTarget {
     /// The `[coding-style]` marker range inside the user CLAUDE.md.
     ClaudeMd,
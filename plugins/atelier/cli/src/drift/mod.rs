@@ -24,136 +24,35 @@
 //! branches on the `<check>=<STATUS>` line format and relays the `synced:`
 //! line (backup path included) verbatim to the user.
 
+#[cfg(feature = "cli")]
+mod cli;
 pub mod commands;
 pub mod core;
 
+#[cfg(feature = "cli")]
+pub use cli::{run, run_from, Cli, Commands};
+
 use crate::drift::commands::DriftDeps;
 use crate::drift::core::artifact::{create_artifact_fs, create_backup_clock};
-use crate::drift::core::types::{DriftPaths, SyncTarget};
-use crate::shared::process::default_project_dir;
-use clap::{Args, Parser, Subcommand};
-
-#[derive(Parser)]
-#[command(
-    name = "drift",
-    version,
-    about = "Judge and re-sync setup-copied artifacts against the plugin sources"
-)]
-pub struct Cli {
-    #[command(subcommand)]
-    pub command: Option<Commands>,
-}
-
-#[derive(Subcommand)]
-pub enum Commands {
-    /// Report per-artifact drift status (read-only)
-    Check {
-        #[command(flatten)]
-        args: PathArgs,
-    },
-    /// Update one installed copy from its plugin source (never installs)
-    Sync {
-        /// Which installed copy to update
-        #[arg(long = "target", value_enum)]
-        target: SyncTarget,
-        #[command(flatten)]
-        args: PathArgs,
-    },
-}
-
-/// The flag trio every drift command shares.
-#[derive(Args)]
-pub struct PathArgs {
-    /// Plugin root holding the source template and rules files
-    #[arg(long = "plugin-root")]
-    plugin_root: String,
-    /// User CLAUDE.md path (default: $HOME/.claude/CLAUDE.md)
-    #[arg(long = "claude-md")]
-    claude_md: Option<String>,
-    /// Project root the rules copy lives under (default: the process cwd)
-    #[arg(long = "project-dir")]
-    project_dir: Option<String>,
-}
-
-impl PathArgs {
-    /// Resolves the flags into concrete paths — the only place defaults (and
-    /// therefore the environment) are consulted; the commands take resolved
-    /// paths.
-    fn resolve(self) -> Result<DriftPaths, String> {
-        let claude_md = match self.claude_md {
-            Some(path) => path,
-            None => {
-                let home = std::env::var("HOME")
-                    .map_err(|_| "HOME is not set — pass --claude-md explicitly".to_string())?;
-                format!("{home}/.claude/CLAUDE.md")
-            }
-        };
-        Ok(DriftPaths {
-            plugin_root: self.plugin_root,
-            claude_md,
-            project_dir: default_project_dir(self.project_dir),
-        })
-    }
-}
-
-/// The error edge: every failure is `Error: <message>` on stderr with exit 2,
-/// the shell scripts' usage/refusal contract.
-fn fail(message: &str) -> i32 {
-    eprintln!("Error: {message}");
-    2
-}
-
-/// Parses `argv` (including the leading program name) with the drift clap
-/// surface and runs the selected command, returning a process exit code.
-pub fn run_from<I, T>(argv: I) -> i32
-where
-    I: IntoIterator<Item = T>,
-    T: Into<std::ffi::OsString> + Clone,
-{
-    run(Cli::parse_from(argv))
-}
-
-/// Runs a parsed drift CLI, returning a process exit code. The subsystem's
-/// only stdout render site — commands return values, never print.
-pub fn run(cli: Cli) -> i32 {
-    let command = match cli.command {
-        Some(c) => c,
-        None => {
-            use clap::CommandFactory;
-            let _ = Cli::command().print_help();
-            println!();
-            return 0;
-        }
-    };
+use crate::drift::core::types::{CheckReport, DriftPaths, SyncReport, SyncTarget};
 
+/// Runs `command` against the real filesystem and clock.
+pub(crate) fn with_real_deps<T>(command: impl FnOnce(&DriftDeps) -> T) -> T {
     let fs = create_artifact_fs();
     let clock = create_backup_clock();
-    let deps = DriftDeps {
+    command(&DriftDeps {
         fs: &fs,
         clock: &clock,
-    };
-    // Both arms share the resolve → run → render pipeline; only the rendered
-    // text and the exit code differ (check's 0/1 split lives on the report).
-    let (rendered, code) = match command {
-        Commands::Check { args } => {
-            match args
-                .resolve()
-                .and_then(|paths| commands::check::run(&deps, &paths))
-            {
-                Ok(report) => (report.render(), report.exit_code()),
-                Err(e) => return fail(&e),
-            }
-        }
-        Commands::Sync { target, args } => {
-            match args
-                .resolve()
-                .and_then(|paths| commands::sync::run(&deps, &paths, target))
-            {
-                Ok(report) => (report.render(), 0),
-                Err(e) => return fail(&e),
-            }
-        }
-    };
-    print!("{rendered}");
-    code
+    })
+}
+
+/// Library entry for `drift check`: judges every artifact against the real
+/// filesystem. `Err` is a plugin-source problem (the CLI's exit 2).
+pub fn check(paths: &DriftPaths) -> Result<CheckReport, String> {
+    with_real_deps(|deps| commands::check::run(deps, paths))
+}
+
+/// Library entry for `drift sync`: backs up and rewrites one installed copy.
+pub fn sync(paths: &DriftPaths, target: SyncTarget) -> Result<SyncReport, String> {
+    with_real_deps(|deps| commands::sync::run(deps, paths, target))
 }
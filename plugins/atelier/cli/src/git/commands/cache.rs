@@ -0,0 +1,14 @@
+//! `cache` command — maintenance for the default-branch detection cache the
+//! guards read (`core::branch_cache`). Entries invalidate themselves when the
+//! repository's refs move; `clear` is for everything else (a remote renamed
+//! its default without a fetch, a suspect answer while debugging a guard).
+
+use crate::git::core::branch_cache::BranchCache;
+use crate::git::types::{CacheClearOutput, CmdResult};
+
+pub fn clear(cache: &dyn BranchCache) -> CmdResult<CacheClearOutput> {
+    match cache.clear() {
+        Ok(cleared) => CmdResult::Ok(CacheClearOutput { cleared }),
+        Err(e) => CmdResult::Err(e),
+    }
+}
@@ -2,10 +2,18 @@
 //! Branch targets (write/commit) route to `core::guard::GuardService`, the
 //! `pr` target routes to `core::pr_guard::PrGuardService`. Both collapse into
 //! a `GuardDecision` so the CLI layer only maps allow/block to exit codes.
+//!
+//! `evaluate` is the whole hook as a library call: real services, typed
+//! payload and configuration in, decision out.
 
-use crate::git::core::guard::GuardService;
-use crate::git::core::pr_guard::PrGuardService;
+use crate::git::core::branch_cache::{create_cached_git_service, SqliteBranchCache};
+use crate::git::core::git::create_git_service;
+use crate::git::core::github::create_github_service;
+use crate::git::core::guard::{create_guard_service, GuardService};
+use crate::git::core::pr_guard::{create_pr_guard_service, PrGuardService};
 use crate::git::types::{GuardCommandTarget, GuardDecision, GuardInput, GuardTarget, PrGuardInput};
+use crate::shared::config::CommitMessageConfig;
+use crate::shared::trace;
 
 /// PreToolUse hook payload fields the guard targets consume. `parse` is
 /// swallow-all — any read/JSON failure yields all-`None`, preserving the TS
@@ -49,6 +57,14 @@ impl GuardTargetKind {
         }
     }
 
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Write => "write",
+            Self::Commit => "commit",
+            Self::Pr => "pr",
+        }
+    }
+
     /// Binds exactly the payload field this target's check consumes.
     pub fn into_target(self, payload: HookPayload) -> GuardCommandTarget {
         match self {
@@ -78,10 +94,33 @@ pub struct GuardCommandInput {
     pub create_branch_script: String,
     pub default_branch: Option<String>,
     pub protected_branches: Option<Vec<String>>,
+    pub commit_message: CommitMessageConfig,
 }
 
 /// Routes the target to its guard service and returns the unified decision.
 pub fn run(deps: &GuardCommandDeps, input: &GuardCommandInput) -> GuardDecision {
+    let decision = decide(deps, input);
+    trace::info(
+        "guard_decision",
+        serde_json::json!({
+            "target": target_name(&input.target),
+            "allowed": decision.allowed,
+            "ask": decision.ask,
+            "reason": decision.reason,
+        }),
+    );
+    decision
+}
+
+fn target_name(target: &GuardCommandTarget) -> &'static str {
+    match target {
+        GuardCommandTarget::Pr { .. } => "pr",
+        GuardCommandTarget::Branch(GuardTarget::Write { .. }) => "write",
+        GuardCommandTarget::Branch(GuardTarget::Commit { .. }) => "commit",
+    }
+}
+
+fn decide(deps: &GuardCommandDeps, input: &GuardCommandInput) -> GuardDecision {
     match &input.target {
         GuardCommandTarget::Pr { command } => check_pr(deps.pr_guard, command.clone()),
         GuardCommandTarget::Branch(target) => deps
@@ -92,6 +131,7 @@ pub fn run(deps: &GuardCommandDeps, input: &GuardCommandInput) -> GuardDecision
                 create_branch_script: input.create_branch_script.clone(),
                 default_branch: input.default_branch.clone(),
                 protected_branches: input.protected_branches.clone(),
+                commit_message: input.commit_message.clone(),
             })
             .into(),
     }
@@ -106,3 +146,73 @@ pub fn check_pr(pr_guard: &dyn PrGuardService, command: Option<String>) -> Guard
         })
         .into()
 }
+
+/// What `atelier git guard` takes as flags: the target to check and the
+/// branch-guard configuration (ignored by the `pr` target).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardConfig {
+    pub target: GuardTargetKind,
+    pub project_dir: String,
+    /// Branch-creation command the block message suggests; empty for the
+    /// guard core's default.
+    pub create_branch_script: String,
+    /// Overrides default-branch detection.
+    pub default_branch: Option<String>,
+    /// Overrides the protected branch list.
+    pub protected_branches: Option<Vec<String>>,
+    /// The project's `git.commit_message` policy, for the commit target.
+    pub commit_message: CommitMessageConfig,
+}
+
+impl GuardConfig {
+    /// A `target` check for `project_dir` with every default left in place.
+    pub fn new(target: GuardTargetKind, project_dir: impl Into<String>) -> Self {
+        GuardConfig {
+            target,
+            project_dir: project_dir.into(),
+            create_branch_script: String::new(),
+            default_branch: None,
+            protected_branches: None,
+            commit_message: CommitMessageConfig::default(),
+        }
+    }
+
+    /// Binds `payload` to this configuration's target.
+    pub fn input(&self, payload: HookPayload) -> GuardCommandInput {
+        GuardCommandInput {
+            target: self.target.into_target(payload),
+            project_dir: self.project_dir.clone(),
+            create_branch_script: self.create_branch_script.clone(),
+            default_branch: self.default_branch.clone(),
+            protected_branches: self.protected_branches.clone(),
+            commit_message: self.commit_message.clone(),
+        }
+    }
+}
+
+/// Runs `command` against the real guard services for `project_dir`, so a
+/// caller checking several targets for one tool call (`hook-dispatch`)
+/// builds them once.
+pub fn with_real_deps<T>(project_dir: &str, command: impl FnOnce(&GuardCommandDeps) -> T) -> T {
+    // Pin the git service to project_dir so special-state / default-branch
+    // detection reflect the project, not the hook's process cwd (worktree /
+    // subagent contexts) — see #780.
+    let git = create_git_service(Some(project_dir.to_string()));
+    let cache = SqliteBranchCache::open_default();
+    let cached = create_cached_git_service(&git, &cache, project_dir);
+    let branch_guard = create_guard_service(&cached);
+    let github = create_github_service(None);
+    let pr_guard = create_pr_guard_service(&github);
+    command(&GuardCommandDeps {
+        branch_guard: &branch_guard,
+        pr_guard: &pr_guard,
+    })
+}
+
+/// Evaluates one PreToolUse payload against the real git and GitHub
+/// services. Never fails: every error path the hook has resolves to a decision.
+pub fn evaluate(payload: HookPayload, config: &GuardConfig) -> GuardDecision {
+    with_real_deps(&config.project_dir, |deps| {
+        run(deps, &config.input(payload))
+    })
+}
@@ -13,7 +13,8 @@
 //!    detection), absorbing every failure into "no pin",
 //! 3. resolve the scope's settings file,
 //! 4. retire stale guard registrations by command prefix,
-//! 5. register both guards in a single write.
+//! 5. register one `hook-dispatch` entry covering every guard, in a single
+//!    write.
 
 use crate::git::commands::hook::{settings_path, HookCommand};
 use crate::git::core::git::{GitService, OriginHeadWarmer};
@@ -27,19 +28,26 @@ use crate::git::types::{
 /// every future invocation.
 const PROJECT_DIR_ARG: &str = r#"--project-dir "${CLAUDE_PROJECT_DIR:-.}""#;
 
-/// Hook type both guards register under.
+/// Hook type the guards register under.
 const HOOK_TYPE: &str = "PreToolUse";
 
-/// The branch-guard targets setup installs, each with the tool matcher it
-/// gates. `guard pr` is absent on purpose — it carries no project-specific
-/// value, so the hook-management flow registers it separately.
-const GUARD_TARGETS: [(&str, &str); 2] = [("write", "Write|Edit"), ("commit", "Bash")];
+/// Every tool a guard gates: the write guard's tools and the commit / PR
+/// guards' `Bash`. `hook-dispatch` routes each call to the right guards.
+const GUARD_MATCHER: &str = "Write|Edit|MultiEdit|Bash";
 
-/// The only place a guard command string is produced. A `None` branch does not
-/// push the flag at all — a bare `--default-branch` makes the hook exit 2 on
-/// clap's usage error, which Claude Code reads as "deny every edit".
-fn guard_command(target: &str, branch: Option<&DetectedBranch>) -> String {
-    let mut command = format!("atelier git guard {target} {PROJECT_DIR_ARG}");
+/// One process per tool call runs every guard (`atelier hook-dispatch`).
+const DISPATCH_COMMAND: &str = "atelier hook-dispatch";
+
+/// Commands of the per-guard generation: one registration (and one process)
+/// per guard, including the PR guard and its `pr-guard` alias. `hook-dispatch`
+/// runs all of them, so left in place each would run twice.
+const PER_GUARD_PREFIXES: [&str; 2] = ["atelier git guard ", "atelier git pr-guard"];
+
+/// The only place the guard command string is produced. A `None` branch does
+/// not push the flag at all — a bare `--default-branch` makes the hook exit 2
+/// on clap's usage error, which Claude Code reads as "deny every edit".
+fn guard_command(branch: Option<&DetectedBranch>) -> String {
+    let mut command = format!("{DISPATCH_COMMAND} {PROJECT_DIR_ARG}");
     if let Some(branch) = branch {
         command.push_str(" --default-branch ");
         command.push_str(branch.as_str());
@@ -47,12 +55,45 @@ fn guard_command(target: &str, branch: Option<&DetectedBranch>) -> String {
     command
 }
 
-/// Prefix identifying *any* generation of a registered branch guard, pinned or
+/// Prefixes identifying *any* generation of a registered guard, pinned or
 /// not. Exact-command replacement cannot retire an entry whose trailing
-/// `--default-branch <b>` differs from the command being registered, so without
-/// this purge both would survive and the guard would run twice.
-fn guard_command_prefix(target: &str) -> String {
-    format!("atelier git guard {target} ")
+/// `--default-branch <b>` differs from the command being registered, so
+/// without this purge both would survive and the guards would run twice.
+fn guard_command_prefixes() -> Vec<String> {
+    std::iter::once(DISPATCH_COMMAND)
+        .chain(PER_GUARD_PREFIXES)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Where a settings.json command stands relative to what setup writes today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardGeneration {
+    /// A `hook-dispatch` registration, pinned or not — it runs every guard.
+    Current,
+    /// An earlier generation: a per-guard `atelier git guard <target>` (or
+    /// `pr-guard`) command, or one of the `default-branch-guard*.sh` scripts
+    /// `/atelier:setup` unregisters.
+    Stale,
+}
+
+/// Classifies `command`; `None` when it is not a branch guard at all.
+pub fn guard_generation(command: &str) -> Option<GuardGeneration> {
+    // A bare `atelier hook-dispatch` anchors on the payload cwd, which is the
+    // session's project — unlike a bare per-guard command it is not stale.
+    if format!("{command} ").starts_with(&format!("{DISPATCH_COMMAND} ")) {
+        return Some(GuardGeneration::Current);
+    }
+    // A bare `atelier git guard commit` is as stale as one with flags.
+    if PER_GUARD_PREFIXES
+        .iter()
+        .any(|prefix| format!("{command} ").starts_with(prefix))
+    {
+        return Some(GuardGeneration::Stale);
+    }
+    command
+        .contains("default-branch-guard")
+        .then_some(GuardGeneration::Stale)
 }
 
 pub struct GuardSetupDeps<'a> {
@@ -104,27 +145,20 @@ pub fn run(deps: &GuardSetupDeps, input: &GuardSetupInput) -> CmdResult<GuardSet
         Err(e) => return CmdResult::Err(e),
     };
 
-    // 4 + 5. Purge stale generations and register both guards in one write, so
-    //        the install can never land half-migrated or half-registered.
-    let commands: Vec<String> = GUARD_TARGETS
-        .iter()
-        .map(|(target, _)| guard_command(target, branch.as_ref()))
-        .collect();
+    // 4 + 5. Purge stale generations and register the dispatcher in one write,
+    //        so the install can never land half-migrated.
+    let commands = vec![guard_command(branch.as_ref())];
     let batch = HookRegisterManyInput {
-        hooks: GUARD_TARGETS
+        hooks: commands
             .iter()
-            .zip(&commands)
-            .map(|((_, matcher), command)| HookRegistration {
+            .map(|command| HookRegistration {
                 hook_type: HOOK_TYPE.to_string(),
-                matcher: matcher.to_string(),
+                matcher: GUARD_MATCHER.to_string(),
                 command: command.clone(),
                 timeout: None,
             })
             .collect(),
-        remove_command_prefixes: GUARD_TARGETS
-            .iter()
-            .map(|(target, _)| guard_command_prefix(target))
-            .collect(),
+        remove_command_prefixes: guard_command_prefixes(),
         project_dir: Some(settings_dir.clone()),
         dry_run: input.dry_run,
     };
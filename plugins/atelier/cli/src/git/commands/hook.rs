@@ -27,6 +27,24 @@ pub trait HookFs {
     fn mkdir(&self, path: &str) -> Result<(), String>;
 }
 
+/// Real filesystem for the hook command.
+pub struct RealHookFs;
+
+impl HookFs for RealHookFs {
+    fn read_file(&self, path: &str) -> Result<String, String> {
+        std::fs::read_to_string(path).map_err(|e| e.to_string())
+    }
+    fn write_file(&self, path: &str, content: &str) -> Result<(), String> {
+        std::fs::write(path, content).map_err(|e| e.to_string())
+    }
+    fn exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).exists()
+    }
+    fn mkdir(&self, path: &str) -> Result<(), String> {
+        std::fs::create_dir_all(path).map_err(|e| e.to_string())
+    }
+}
+
 pub struct HookCommand<'a> {
     fs: &'a dyn HookFs,
 }
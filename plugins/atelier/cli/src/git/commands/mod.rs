@@ -3,6 +3,7 @@
 //! typed input and returns a `CmdResult`, keeping business logic out of the CLI
 //! layer.
 
+pub mod cache;
 pub mod guard;
 pub mod guard_setup;
 pub mod hook;
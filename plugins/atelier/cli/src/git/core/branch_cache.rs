@@ -0,0 +1,212 @@
+//! Default-branch detection cache. The write and commit guards run on every
+//! Edit and every Bash call, and `detect_default_branch` costs up to four git
+//! subprocesses each time — for an answer that changes about never.
+//!
+//! Entries live in the shared database (`shared::db`), one row per repository,
+//! and are trusted only while the repository's fingerprint is unchanged and
+//! the TTL has not run out. The fingerprint covers what detection reads:
+//! `HEAD`, `packed-refs`, the `refs/remotes/origin` directory and the content
+//! of `refs/remotes/origin/HEAD` — so a fetch, a `remote set-head` or a
+//! repacked ref invalidates the entry without any hook having to notice.
+//!
+//! `CachedGitService` wraps any `GitService`; every failure along the cache
+//! path (no repository found, database unavailable) falls through to plain
+//! detection, and failed detections are never cached.
+
+use crate::git::core::git::GitService;
+use crate::git::types::GitSpecialState;
+use crate::shared::db;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long an entry is trusted even when nothing in the fingerprint moved.
+pub const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Identifies a repository and the state its default branch was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoFingerprint {
+    /// Canonical common git dir — shared by every worktree of a repository,
+    /// as their remote refs are.
+    pub repo: String,
+    pub stamp: String,
+}
+
+/// The git dir `dir` belongs to: the nearest `.git` directory, or the target
+/// of a `.git` file (`gitdir: <path>`, as worktrees and submodules have).
+fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    let start = std::fs::canonicalize(dir).ok()?;
+    for candidate in start.ancestors() {
+        let dot_git = candidate.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        if let Ok(raw) = std::fs::read_to_string(&dot_git) {
+            let target = raw.strip_prefix("gitdir:")?.trim();
+            return Some(candidate.join(target));
+        }
+    }
+    None
+}
+
+/// A worktree's git dir names the shared one in its `commondir` file.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(raw) => git_dir.join(raw.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn mtime_ns(path: &Path) -> String {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos().to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+impl RepoFingerprint {
+    /// Fingerprints the repository containing `dir`; `None` outside one.
+    pub fn read(dir: &Path) -> Option<RepoFingerprint> {
+        let common = std::fs::canonicalize(common_dir(&find_git_dir(dir)?)).ok()?;
+        let origin_head = std::fs::read_to_string(common.join("refs/remotes/origin/HEAD"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let stamp = [
+            mtime_ns(&common.join("HEAD")),
+            mtime_ns(&common.join("packed-refs")),
+            mtime_ns(&common.join("refs/remotes/origin")),
+            origin_head,
+        ]
+        .join("|");
+        Some(RepoFingerprint {
+            repo: common.to_string_lossy().to_string(),
+            stamp,
+        })
+    }
+}
+
+pub trait BranchCache {
+    /// The cached branch for `key`, if its fingerprint matches and it is fresh.
+    fn get(&self, key: &RepoFingerprint) -> Option<String>;
+    /// Records `branch` for `key`. Best effort.
+    fn put(&self, key: &RepoFingerprint, branch: &str);
+    /// Drops every entry, returning how many there were.
+    fn clear(&self) -> Result<usize, String>;
+}
+
+/// `BranchCache` over the shared database, opened on first use so a guard
+/// that never needs detection never touches the file.
+pub struct SqliteBranchCache {
+    path: PathBuf,
+    ttl: Duration,
+    conn: OnceCell<Option<Connection>>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl SqliteBranchCache {
+    pub fn new(path: PathBuf, ttl: Duration) -> Self {
+        SqliteBranchCache {
+            path,
+            ttl,
+            conn: OnceCell::new(),
+        }
+    }
+
+    /// The per-user cache every guard shares.
+    pub fn open_default() -> Self {
+        SqliteBranchCache::new(db::db_path(), CACHE_TTL)
+    }
+
+    fn conn(&self) -> Result<&Connection, String> {
+        self.conn
+            .get_or_init(|| db::open(&self.path).ok())
+            .as_ref()
+            .ok_or_else(|| format!("cannot open {}", self.path.display()))
+    }
+}
+
+impl BranchCache for SqliteBranchCache {
+    fn get(&self, key: &RepoFingerprint) -> Option<String> {
+        let cutoff = now_ms().saturating_sub(self.ttl.as_millis() as i64);
+        self.conn()
+            .ok()?
+            .query_row(
+                "SELECT branch FROM default_branch_cache
+                 WHERE repo = ?1 AND fingerprint = ?2 AND cached_at > ?3",
+                params![key.repo, key.stamp, cutoff],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()?
+    }
+
+    fn put(&self, key: &RepoFingerprint, branch: &str) {
+        if let Ok(conn) = self.conn() {
+            let _ = conn.execute(
+                "INSERT INTO default_branch_cache (repo, fingerprint, branch, cached_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(repo) DO UPDATE SET fingerprint = excluded.fingerprint,
+                     branch = excluded.branch, cached_at = excluded.cached_at",
+                params![key.repo, key.stamp, branch, now_ms()],
+            );
+        }
+    }
+
+    fn clear(&self) -> Result<usize, String> {
+        self.conn()?
+            .execute("DELETE FROM default_branch_cache", [])
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// `GitService` whose `detect_default_branch` answers from `cache` while the
+/// repository at `project_dir` is unchanged. The other reads pass through:
+/// they describe the working tree, which moves on every command.
+pub struct CachedGitService<'a> {
+    inner: &'a dyn GitService,
+    cache: &'a dyn BranchCache,
+    project_dir: PathBuf,
+}
+
+pub fn create_cached_git_service<'a>(
+    inner: &'a dyn GitService,
+    cache: &'a dyn BranchCache,
+    project_dir: impl Into<PathBuf>,
+) -> CachedGitService<'a> {
+    CachedGitService {
+        inner,
+        cache,
+        project_dir: project_dir.into(),
+    }
+}
+
+impl GitService for CachedGitService<'_> {
+    fn detect_default_branch(&self) -> Result<String, String> {
+        let Some(key) = RepoFingerprint::read(&self.project_dir) else {
+            return self.inner.detect_default_branch();
+        };
+        if let Some(branch) = self.cache.get(&key) {
+            return Ok(branch);
+        }
+        let branch = self.inner.detect_default_branch()?;
+        self.cache.put(&key, &branch);
+        Ok(branch)
+    }
+
+    fn is_inside_work_tree(&self) -> bool {
+        self.inner.is_inside_work_tree()
+    }
+
+    fn get_special_state(&self) -> GitSpecialState {
+        self.inner.get_special_state()
+    }
+}
@@ -0,0 +1,218 @@
+//! Shell command-line reading shared by the commit guard (`core::guard`) and
+//! the commit-message policy (`core::commit_message`): one lexer, so the two
+//! agree on what is quoted, and one reader of `git [global options] <cmd>`.
+//!
+//! The lexer knows backslash escapes, literal single quotes and double quotes
+//! with escapes. A `$( … )` inside double quotes is kept whole, since a
+//! heredoc body may hold quotes of its own. Nothing is expanded or run.
+
+use std::path::PathBuf;
+
+/// One lexical piece of a command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    /// An unquoted character, escaped or not.
+    Char(char),
+    /// The contents of one quoted span, escapes resolved.
+    Quoted(String),
+    /// `;`, `&`, `|` or a newline: the end of one simple command.
+    Separator(char),
+    /// Unquoted whitespace between words.
+    Blank(char),
+}
+
+fn lex(command: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // An escaped char never opens/closes a quote or ends a word.
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    pieces.push(Piece::Char(next));
+                }
+            }
+            '\'' => {
+                let mut quoted = String::new();
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    quoted.push(q);
+                }
+                pieces.push(Piece::Quoted(quoted));
+            }
+            '"' => {
+                let mut quoted = String::new();
+                while let Some(q) = chars.next() {
+                    match q {
+                        '\\' => {
+                            if let Some(next) = chars.next() {
+                                quoted.push(next);
+                            }
+                        }
+                        '"' => break,
+                        '$' if chars.peek() == Some(&'(') => {
+                            quoted.push('$');
+                            let mut depth = 0usize;
+                            for r in chars.by_ref() {
+                                quoted.push(r);
+                                match r {
+                                    '(' => depth += 1,
+                                    ')' => {
+                                        depth -= 1;
+                                        if depth == 0 {
+                                            break;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        _ => quoted.push(q),
+                    }
+                }
+                pieces.push(Piece::Quoted(quoted));
+            }
+            ';' | '&' | '|' | '\n' => pieces.push(Piece::Separator(c)),
+            c if c.is_whitespace() => pieces.push(Piece::Blank(c)),
+            c => pieces.push(Piece::Char(c)),
+        }
+    }
+    pieces
+}
+
+/// `command` with every quoted span replaced by a space, so quoted text
+/// arguments can't false-positive a matcher run over the result.
+pub fn strip_quoted(command: &str) -> String {
+    lex(command)
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Char(c) | Piece::Separator(c) | Piece::Blank(c) => c,
+            Piece::Quoted(_) => ' ',
+        })
+        .collect()
+}
+
+/// The words of each simple command in `command`, quotes removed.
+pub fn simple_commands(command: &str) -> Vec<Vec<String>> {
+    let mut commands = vec![Vec::new()];
+    let mut word: Option<String> = None;
+    for piece in lex(command) {
+        match piece {
+            Piece::Char(c) => word.get_or_insert_with(String::new).push(c),
+            Piece::Quoted(q) => word.get_or_insert_with(String::new).push_str(&q),
+            Piece::Separator(_) | Piece::Blank(_) => {
+                let current = commands.last_mut().expect("never empty");
+                current.extend(word.take());
+                if matches!(piece, Piece::Separator(_)) && !current.is_empty() {
+                    commands.push(Vec::new());
+                }
+            }
+        }
+    }
+    commands.last_mut().expect("never empty").extend(word);
+    commands.retain(|words| !words.is_empty());
+    commands
+}
+
+/// Global options of `git` that take the next word as their value.
+const GIT_VALUE_OPTIONS: [&str; 6] = [
+    "-C",
+    "-c",
+    "--git-dir",
+    "--work-tree",
+    "--namespace",
+    "--config-env",
+];
+
+/// One `git [global options] <subcommand> …` simple command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInvocation<'a> {
+    /// Where `-C` moves git before it runs, relative to the shell's cwd —
+    /// several `-C`s compose like git composes them. `None` without `-C`.
+    pub dir: Option<PathBuf>,
+    pub subcommand: &'a str,
+    pub args: &'a [String],
+}
+
+/// Reads `words` as a git invocation, or `None` for any other command.
+pub fn git_invocation(words: &[String]) -> Option<GitInvocation<'_>> {
+    let (first, rest) = words.split_first()?;
+    if first != "git" && !first.ends_with("/git") {
+        return None;
+    }
+    let mut dir: Option<PathBuf> = None;
+    let mut i = 0;
+    while let Some(word) = rest.get(i) {
+        match word.as_str() {
+            option if GIT_VALUE_OPTIONS.contains(&option) => {
+                if option == "-C" {
+                    let value = rest.get(i + 1)?;
+                    dir = Some(dir.unwrap_or_default().join(value));
+                }
+                i += 2;
+            }
+            w if w.starts_with('-') => i += 1,
+            subcommand => {
+                return Some(GitInvocation {
+                    dir,
+                    subcommand,
+                    args: &rest[i + 1..],
+                })
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_separators_stay_inside_words() {
+        assert_eq!(
+            simple_commands(r#"git commit -m "a; b" && echo 'c|d'"#),
+            vec![vec!["git", "commit", "-m", "a; b"], vec!["echo", "c|d"]]
+        );
+        assert_eq!(
+            strip_quoted(r#"gh issue create --body "git commit" && ls"#),
+            "gh issue create --body   && ls"
+        );
+    }
+
+    #[test]
+    fn heredoc_substitution_stays_one_word() {
+        let command = "git commit -m \"$(cat <<'EOF'\nfix: \"x\"; y\nEOF\n)\" && ls";
+        let commands = simple_commands(command);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0][3], "$(cat <<'EOF'\nfix: \"x\"; y\nEOF\n)");
+    }
+
+    #[test]
+    fn git_global_options_are_skipped_and_dirs_composed() {
+        let words: Vec<String> = [
+            "git",
+            "-C",
+            "a",
+            "-c",
+            "x=y",
+            "--no-pager",
+            "-C",
+            "b",
+            "commit",
+            "-m",
+            "x",
+        ]
+        .map(String::from)
+        .to_vec();
+        let git = git_invocation(&words).unwrap();
+        assert_eq!(git.dir, Some(PathBuf::from("a/b")));
+        assert_eq!(git.subcommand, "commit");
+        assert_eq!(git.args, ["-m", "x"]);
+
+        let words: Vec<String> = ["echo", "git", "commit"].map(String::from).to_vec();
+        assert_eq!(git_invocation(&words), None);
+    }
+}
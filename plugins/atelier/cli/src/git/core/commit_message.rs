@@ -0,0 +1,341 @@
+//! Commit-message policy for the commit guard: pulls the message out of a
+//! `git commit -m …` / `-F <file>` command and checks it against the
+//! project's `git.commit_message` rules (`shared::config`).
+//!
+//! Extraction reads the command with `core::command_line`, the lexer the
+//! branch guard's commit matcher uses, so the two agree on what is a quoted
+//! argument. Anything the guard
+//! cannot see statically — an editor session, `-F -`, a command substitution
+//! other than the `"$(cat <<'EOF' … EOF)"` heredoc idiom, `--fixup` — yields
+//! no message, and no message means no verdict: the policy only ever blocks
+//! what it has actually read.
+
+use crate::git::core::command_line::{git_invocation, simple_commands};
+use crate::shared::config::CommitMessageConfig;
+use crate::shared::trace;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Types `conventional` accepts when the config names none.
+pub const DEFAULT_TYPES: [&str; 11] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+static CONVENTIONAL_SUBJECT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-z]+)(\([^()]+\))?!?: \S").unwrap());
+
+static WIP_SUBJECT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bwip\b").unwrap());
+
+static AI_TRAILER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?im)^\s*(?:co-authored-by:.*\b(?:claude|anthropic|copilot|chatgpt|openai|gemini|cursor)\b|.*\bgenerated (?:with|by)\b.*\b(?:claude|copilot|chatgpt|gemini|ai)\b).*$",
+    )
+    .unwrap()
+});
+
+/// `$(cat <<'EOF' … EOF)` — how Claude Code passes multi-line messages.
+static HEREDOC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)^\$\(\s*cat\s+<<-?\s*['"]?(\w+)['"]?\s*\n(.*?)\n\s*(\w+)\s*\)$"#).unwrap()
+});
+
+/// Subjects git writes itself; they follow git's grammar, not the project's.
+const GENERATED_SUBJECTS: [&str; 5] = ["fixup! ", "squash! ", "amend! ", "Merge ", "Revert \""];
+
+/// What one `-m` / `-F` contributes.
+enum Part {
+    Text(String),
+    File(String),
+}
+
+/// The message one commit's arguments spell out, or `None` when it is not
+/// statically known. Relative `-F` paths are read against `dir`.
+fn message_of(args: &[String], dir: &Path) -> Option<String> {
+    let mut parts: Vec<Part> = Vec::new();
+    let mut trailers: Vec<String> = Vec::new();
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        i += 1;
+        let mut next = || {
+            i += 1;
+            args.get(i - 1).cloned()
+        };
+        match arg.as_str() {
+            "--" => break,
+            "--message" => parts.push(Part::Text(next()?)),
+            "--file" => parts.push(Part::File(next()?)),
+            "--trailer" => trailers.push(next()?),
+            // git writes these messages itself.
+            a if a.starts_with("--fixup") || a.starts_with("--squash") => return None,
+            "--author"
+            | "--date"
+            | "--cleanup"
+            | "--template"
+            | "--reuse-message"
+            | "--reedit-message"
+            | "--pathspec-from-file" => {
+                next();
+            }
+            a if a.starts_with("--") => {
+                if let Some(v) = a.strip_prefix("--message=") {
+                    parts.push(Part::Text(v.to_string()));
+                } else if let Some(v) = a.strip_prefix("--file=") {
+                    parts.push(Part::File(v.to_string()));
+                } else if let Some(v) = a.strip_prefix("--trailer=") {
+                    trailers.push(v.to_string());
+                }
+            }
+            a if a.starts_with('-') && a.len() > 1 => {
+                // A short-option cluster: `-am msg`, `-m msg`, `-mmsg`, `-F f`.
+                let cluster = &a[1..];
+                for (at, flag) in cluster.char_indices() {
+                    let attached = &cluster[at + flag.len_utf8()..];
+                    let value = || {
+                        if attached.is_empty() {
+                            None
+                        } else {
+                            Some(attached.to_string())
+                        }
+                    };
+                    match flag {
+                        'm' => {
+                            parts.push(Part::Text(value().or_else(&mut next)?));
+                            break;
+                        }
+                        'F' => {
+                            parts.push(Part::File(value().or_else(&mut next)?));
+                            break;
+                        }
+                        'c' | 'C' | 't' => {
+                            if attached.is_empty() {
+                                next();
+                            }
+                            break;
+                        }
+                        // Optional values, attached only: `-Skeyid`, `-uno`.
+                        'S' | 'u' => break,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+
+    let mut paragraphs = Vec::new();
+    for part in parts {
+        paragraphs.push(match part {
+            Part::Text(text) => literal(&text)?,
+            Part::File(path) if path == "-" => return None,
+            Part::File(path) => std::fs::read_to_string(dir.join(path)).ok()?,
+        });
+    }
+    let mut message = paragraphs.join("\n\n");
+    if !trailers.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&trailers.join("\n"));
+    }
+    Some(message)
+}
+
+/// `text` as the shell will pass it, when that is knowable without running
+/// anything: plain text, or the body of the heredoc idiom.
+fn literal(text: &str) -> Option<String> {
+    if let Some(caps) = HEREDOC.captures(text.trim()) {
+        return (caps[1] == caps[3]).then(|| caps[2].to_string());
+    }
+    if text.contains("$(") || text.contains('`') {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+/// Every commit message `command` states, in order. A command with no
+/// readable message (or no commit at all) yields none. Relative `-F` paths
+/// are read against `project_dir`, moved by the commit's `git -C <dir>`.
+pub fn extract_messages(command: &str, project_dir: &Path) -> Vec<String> {
+    simple_commands(command)
+        .iter()
+        .filter_map(|words| {
+            let git = git_invocation(words).filter(|g| g.subcommand == "commit")?;
+            let dir = match &git.dir {
+                Some(dir) => project_dir.join(dir),
+                None => project_dir.to_path_buf(),
+            };
+            message_of(git.args, &dir)
+        })
+        .collect()
+}
+
+/// The first non-blank line — what git shows as the subject.
+fn subject(message: &str) -> &str {
+    message
+        .lines()
+        .map(str::trim_end)
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or_default()
+}
+
+/// What is wrong with `message` under `policy`, one line per broken rule.
+/// `protected_branch` is the branch the commit lands on when it is protected.
+pub fn violations(
+    policy: &CommitMessageConfig,
+    message: &str,
+    protected_branch: Option<&str>,
+) -> Vec<String> {
+    let subject = subject(message);
+    let generated = GENERATED_SUBJECTS.iter().any(|p| subject.starts_with(p));
+    let mut out = Vec::new();
+
+    if policy.conventional && !generated {
+        let types: Vec<&str> = if policy.types.is_empty() {
+            DEFAULT_TYPES.to_vec()
+        } else {
+            policy.types.iter().map(String::as_str).collect()
+        };
+        let valid = CONVENTIONAL_SUBJECT
+            .captures(subject)
+            .is_some_and(|caps| types.contains(&&caps[1]));
+        if !valid {
+            out.push(format!(
+                "제목이 Conventional Commits 형식(type(scope): 설명)이 아닙니다. 허용 type: {}",
+                types.join(", ")
+            ));
+        }
+    }
+
+    if let Some(pattern) = &policy.issue_pattern {
+        match Regex::new(pattern) {
+            Ok(re) if !re.is_match(message) => {
+                out.push(format!("이슈 참조가 없습니다 (패턴: {pattern})."));
+            }
+            Ok(_) => {}
+            // A broken pattern is a config typo, not the author's fault.
+            Err(e) => trace::info(
+                "commit_message_bad_pattern",
+                serde_json::json!({ "pattern": pattern, "error": e.to_string() }),
+            ),
+        }
+    }
+
+    if let Some(max) = policy.max_subject_length {
+        let len = subject.chars().count();
+        if len > max {
+            out.push(format!("제목이 {max}자를 넘습니다 ({len}자)."));
+        }
+    }
+
+    if let Some(branch) = protected_branch {
+        if policy.no_wip_on_protected && WIP_SUBJECT.is_match(subject) {
+            out.push(format!(
+                "보호 브랜치({branch})에 WIP 커밋을 남길 수 없습니다."
+            ));
+        }
+    }
+
+    if policy.no_ai_trailers {
+        for m in AI_TRAILER.find_iter(message) {
+            out.push(format!(
+                "AI 작성 표기를 제거해주세요: {}",
+                m.as_str().trim()
+            ));
+        }
+    }
+    out
+}
+
+/// The block message, in the shape of the branch guard's.
+pub fn render_reason(violations: &[String]) -> String {
+    let mut lines =
+        vec!["[Commit Message Guard] 커밋 메시지가 프로젝트 규칙에 맞지 않습니다.".to_string()];
+    lines.extend(violations.iter().map(|v| format!("  - {v}")));
+    lines.push("메시지를 고친 뒤 다시 커밋해주세요.".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(command: &str) -> Vec<String> {
+        extract_messages(command, Path::new("/nonexistent"))
+    }
+
+    #[test]
+    fn message_flag_spellings() {
+        assert_eq!(messages("git commit -m 'feat: a'"), ["feat: a"]);
+        assert_eq!(messages("git commit -am 'feat: a'"), ["feat: a"]);
+        assert_eq!(messages("git commit -mfeat:a"), ["feat:a"]);
+        assert_eq!(messages("git commit --message='feat: a'"), ["feat: a"]);
+        assert_eq!(
+            messages("git -C repo commit -m subject -m body"),
+            ["subject\n\nbody"]
+        );
+        assert_eq!(
+            messages("git commit -m x --trailer 'Co-authored-by: A <a@b>'"),
+            ["x\n\nCo-authored-by: A <a@b>"]
+        );
+    }
+
+    #[test]
+    fn attached_option_values_end_the_cluster() {
+        // The `m` in `-Smykey` is the key id, not `-m`.
+        assert_eq!(messages(r#"git commit -Smykey -m "fix: x""#), ["fix: x"]);
+        assert_eq!(messages("git commit -S -m 'fix: x'"), ["fix: x"]);
+        assert_eq!(messages("git commit -uno -m 'fix: x'"), ["fix: x"]);
+        assert_eq!(messages("git commit -tmpl -m 'fix: x'"), ["fix: x"]);
+    }
+
+    #[test]
+    fn heredoc_idiom_yields_its_body() {
+        let command =
+            "git commit -m \"$(cat <<'EOF'\nfeat: add x\n\nIt's \"quoted\" (really).\nEOF\n)\"";
+        assert_eq!(
+            messages(command),
+            ["feat: add x\n\nIt's \"quoted\" (really)."]
+        );
+    }
+
+    #[test]
+    fn unknowable_messages_are_skipped() {
+        assert!(messages("git commit").is_empty());
+        assert!(messages("git commit -F -").is_empty());
+        assert!(messages("git commit -m \"$(make-message)\"").is_empty());
+        assert!(messages("git commit --fixup HEAD~1").is_empty());
+        assert!(messages("git log -m").is_empty());
+        assert!(messages("echo git commit -m x").is_empty());
+    }
+
+    #[test]
+    fn file_message_is_read_relative_to_the_project() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("MSG"), "fix: from file\n").unwrap();
+        assert_eq!(
+            extract_messages("git add . && git commit -F MSG", tmp.path()),
+            ["fix: from file\n"]
+        );
+    }
+
+    #[test]
+    fn file_message_follows_git_dash_c() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(tmp.path().join("sub")).unwrap();
+        std::fs::write(tmp.path().join("MSG"), "fix: wrong file\n").unwrap();
+        std::fs::write(tmp.path().join("sub/MSG"), "fix: from sub\n").unwrap();
+        assert_eq!(
+            extract_messages("git -C sub commit -F MSG", tmp.path()),
+            ["fix: from sub\n"]
+        );
+        let absolute = tmp.path().join("sub");
+        assert_eq!(
+            extract_messages(
+                &format!("git -C {} commit -F MSG", absolute.display()),
+                Path::new("/nonexistent")
+            ),
+            ["fix: from sub\n"]
+        );
+    }
+}
@@ -6,7 +6,16 @@
 //! run as plain git/gh under the `git` skill's conventions, not through here.
 
 use crate::git::types::GitSpecialState;
-use crate::shared::shell::{exec, ExecOptions};
+use crate::shared::shell::{exec, Exec, ExecOptions};
+use std::time::Duration;
+
+/// Deadline for the local reads the guard makes on every tool call. A repo
+/// read this slow means a stuck lock or filesystem, and the guard's answer
+/// to a failed read is already "pass" (safe mode).
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Deadline for `warm_origin_head`, which talks to the remote.
+const WARM_TIMEOUT: Duration = Duration::from_secs(15);
 
 /// The one repo mutation the git subsystem performs, kept off `GitService` on
 /// purpose: that trait's contract forbids mutation because the guard calls it
@@ -19,6 +28,10 @@ pub trait OriginHeadWarmer {
     /// whether it succeeded; failure (offline, no remote, no auth) is reported,
     /// not fatal — setup continues without it.
     fn warm_origin_head(&self) -> bool;
+    /// The branch the cached `origin/HEAD` names, `None` until a warm-up (or
+    /// a clone) has filled it. A read, so `doctor` can report the warm-up
+    /// without redoing it.
+    fn origin_head(&self) -> Option<String>;
 }
 
 pub trait GitService {
@@ -36,29 +49,36 @@ pub trait GitService {
 /// Real `GitService` bound to an optional working directory.
 pub struct RealGitService {
     cwd: Option<String>,
+    exec: Exec,
 }
 
 /// Constructs the real git service, optionally pinned to `cwd`.
 pub fn create_git_service(cwd: Option<String>) -> RealGitService {
-    RealGitService { cwd }
+    RealGitService { cwd, exec }
 }
 
 const NO_DEFAULT_BRANCH: &str =
     "Could not detect default branch. Make sure you have a remote configured.";
 
 impl RealGitService {
-    fn opts(&self) -> Option<ExecOptions> {
-        self.cwd.as_ref().map(|cwd| ExecOptions {
-            cwd: Some(cwd.clone()),
+    fn opts(&self, timeout: Duration) -> ExecOptions {
+        ExecOptions {
+            cwd: self.cwd.clone(),
             env: None,
-        })
+            timeout: Some(timeout),
+        }
     }
 
-    /// `exec(['git', ...args])` returning (stdout, exit_code).
+    /// `exec(['git', ...args])` returning (stdout, exit_code). A timeout is
+    /// exit `-1`, which every caller already treats as a failed read.
     fn git_safe(&self, args: &[&str]) -> (String, i32) {
+        self.git_within(args, READ_TIMEOUT)
+    }
+
+    fn git_within(&self, args: &[&str], timeout: Duration) -> (String, i32) {
         let mut full = vec!["git"];
         full.extend_from_slice(args);
-        let r = exec(&full, self.opts().as_ref());
+        let r = (self.exec)(&full, Some(&self.opts(timeout)));
         (r.stdout, r.exit_code)
     }
 
@@ -104,9 +124,14 @@ impl OriginHeadWarmer for RealGitService {
     fn warm_origin_head(&self) -> bool {
         // Runs in the service's pinned cwd, so setup warms the project repo
         // rather than whatever directory the session happens to sit in (#780).
-        let (_, exit) = self.git_safe(&["remote", "set-head", "origin", "--auto"]);
+        // A stalled remote reads as a failed warm-up, which setup reports.
+        let (_, exit) = self.git_within(&["remote", "set-head", "origin", "--auto"], WARM_TIMEOUT);
         exit == 0
     }
+
+    fn origin_head(&self) -> Option<String> {
+        self.read_origin_head()
+    }
 }
 
 impl GitService for RealGitService {
@@ -149,7 +174,34 @@ impl GitService for RealGitService {
 
 #[cfg(test)]
 mod tests {
+    use super::{GitService, RealGitService, NO_DEFAULT_BRANCH};
     use crate::git::types::DetectedBranch;
+    use crate::shared::shell::{ExecOptions, ExecResult};
+
+    /// Every git call hangs past its deadline; what arrived before the kill
+    /// looks like a valid `origin/HEAD`.
+    fn timed_out(_: &[&str], _: Option<&ExecOptions>) -> ExecResult {
+        ExecResult {
+            stdout: "refs/remotes/origin/main".to_string(),
+            stderr: String::new(),
+            exit_code: -1,
+            timed_out: true,
+        }
+    }
+
+    #[test]
+    fn timed_out_reads_detect_no_default_branch() {
+        // Partial output of a killed read is not a detection — the guard must
+        // fall back to "undetected" (and pass) rather than trust it.
+        let git = RealGitService {
+            cwd: None,
+            exec: timed_out,
+        };
+        assert_eq!(
+            git.detect_default_branch(),
+            Err(NO_DEFAULT_BRANCH.to_string())
+        );
+    }
 
     /// A branch name is what `detect_default_branch` / `gh` hand back, so the
     /// constructor guarding this type is what stops a blank detection from
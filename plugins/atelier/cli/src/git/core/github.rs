@@ -4,10 +4,15 @@
 //! parses the review-threads GraphQL response into the same shapes as the TS.
 
 use crate::git::types::{DetectedBranch, ReviewComment, ReviewThread};
-use crate::shared::shell::{exec, exec_or_throw, ExecOptions};
+use crate::shared::shell::{exec, Exec, ExecOptions, ExecResult};
 use regex::Regex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Deadline for every `gh` call. gh can stall indefinitely on an auth prompt
+/// or a dead network, and its callers all have a fallback for a failed call.
+const GH_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct ReviewThreadsResult {
     pub pr_title: String,
@@ -30,6 +35,21 @@ pub trait RepoDefaultBranch {
     fn default_branch(&self) -> Option<DetectedBranch>;
 }
 
+/// Whether `gh` holds a usable token for the host the services talk to.
+/// Split out for `doctor`, the one caller that asks (ISP).
+pub trait GhAuth {
+    fn auth_status(&self) -> GhAuthStatus;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhAuthStatus {
+    /// `GH_HOST` from `~/.git-workflow-env`; `None` means gh's own default.
+    pub host: Option<String>,
+    pub authenticated: bool,
+    /// gh's first line of explanation when it is not authenticated.
+    pub detail: String,
+}
+
 const REVIEW_THREADS_QUERY: &str = r#"
 query($owner: String!, $repo: String!, $number: Int!) {
   repository(owner: $owner, name: $repo) {
@@ -79,6 +99,7 @@ pub struct RealGitHubService {
     // constructing the service (e.g. for a guard target that never consults
     // gh) costs no I/O. OnceLock (not cell::OnceCell) keeps the service Sync.
     gh_host: std::sync::OnceLock<Option<String>>,
+    exec: Exec,
 }
 
 /// Constructs the real GitHub service bound to an optional working directory.
@@ -86,6 +107,7 @@ pub fn create_github_service(cwd: Option<String>) -> RealGitHubService {
     RealGitHubService {
         cwd,
         gh_host: std::sync::OnceLock::new(),
+        exec,
     }
 }
 
@@ -93,37 +115,35 @@ impl RealGitHubService {
     fn gh_host(&self) -> &Option<String> {
         self.gh_host.get_or_init(load_gh_host)
     }
-    fn opts(&self) -> Option<ExecOptions> {
-        let gh_host = self.gh_host();
-        if self.cwd.is_none() && gh_host.is_none() {
-            return None;
-        }
-        let env = gh_host
+    fn opts(&self) -> ExecOptions {
+        let env = self
+            .gh_host()
             .as_ref()
             .map(|host| HashMap::from([("GH_HOST".to_string(), host.clone())]));
-        Some(ExecOptions {
+        ExecOptions {
             cwd: self.cwd.clone(),
             env,
-        })
+            timeout: Some(GH_TIMEOUT),
+        }
     }
 
     fn gh(&self, args: &[&str]) -> Result<String, String> {
         let mut full = vec!["gh"];
         full.extend_from_slice(args);
-        exec_or_throw(&full, self.opts().as_ref())
+        (self.exec)(&full, Some(&self.opts())).into_stdout(&full)
     }
 
-    fn gh_safe(&self, args: &[&str]) -> (String, i32) {
+    fn gh_safe(&self, args: &[&str]) -> ExecResult {
         let mut full = vec!["gh"];
         full.extend_from_slice(args);
-        let r = exec(&full, self.opts().as_ref());
-        (r.stdout, r.exit_code)
+        (self.exec)(&full, Some(&self.opts()))
     }
 }
 
 impl RepoDefaultBranch for RealGitHubService {
     fn default_branch(&self) -> Option<DetectedBranch> {
-        let (stdout, exit) = self.gh_safe(&[
+        // A timeout is a failed lookup: setup falls back to git detection.
+        let r = self.gh_safe(&[
             "repo",
             "view",
             "--json",
@@ -131,12 +151,36 @@ impl RepoDefaultBranch for RealGitHubService {
             "-q",
             ".defaultBranchRef.name",
         ]);
-        if exit != 0 {
+        if r.exit_code != 0 {
             return None;
         }
         // `gh` resolves the repository from the cwd's remote, so the service
         // must be constructed pinned to the project directory.
-        DetectedBranch::new(&stdout)
+        DetectedBranch::new(&r.stdout)
+    }
+}
+
+impl GhAuth for RealGitHubService {
+    fn auth_status(&self) -> GhAuthStatus {
+        let host = self.gh_host().clone();
+        let mut args = vec!["auth", "status"];
+        if let Some(host) = &host {
+            args.extend(["--hostname", host.as_str()]);
+        }
+        let r = self.gh_safe(&args);
+        // gh explains itself on stderr or stdout depending on its version.
+        let detail = [&r.stderr, &r.stdout]
+            .into_iter()
+            .flat_map(|out| out.lines())
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string();
+        GhAuthStatus {
+            host,
+            authenticated: r.exit_code == 0,
+            detail,
+        }
     }
 }
 
@@ -203,11 +247,16 @@ impl GitHubService for RealGitHubService {
     }
 
     fn detect_current_pr_number(&self) -> Result<Option<i64>, String> {
-        let (stdout, exit) = self.gh_safe(&["pr", "view", "--json", "number,state"]);
-        if exit != 0 {
+        let r = self.gh_safe(&["pr", "view", "--json", "number,state"]);
+        // "No PR" and "could not ask" differ: only the latter is the PR
+        // guard's safe mode, and a hung `gh` is exactly that.
+        if r.timed_out {
+            return Err(r.stderr);
+        }
+        if r.exit_code != 0 {
             return Ok(None);
         }
-        let parsed: serde_json::Value = match serde_json::from_str(&stdout) {
+        let parsed: serde_json::Value = match serde_json::from_str(&r.stdout) {
             Ok(v) => v,
             Err(_) => return Ok(None),
         };
@@ -220,7 +269,46 @@ impl GitHubService for RealGitHubService {
 
 #[cfg(test)]
 mod tests {
-    use super::GH_HOST_PATTERN;
+    use super::{GitHubService, RealGitHubService, GH_HOST_PATTERN};
+    use crate::git::core::pr_guard::{create_pr_guard_service, PrGuardService};
+    use crate::git::types::PrGuardInput;
+    use crate::shared::shell::{ExecOptions, ExecResult};
+
+    /// `gh` hangs (e.g. on an auth prompt) past its deadline and is killed.
+    fn timed_out(_: &[&str], _: Option<&ExecOptions>) -> ExecResult {
+        ExecResult {
+            stdout: String::new(),
+            stderr: "timed out".to_string(),
+            exit_code: -1,
+            timed_out: true,
+        }
+    }
+
+    fn hung_gh() -> RealGitHubService {
+        RealGitHubService {
+            cwd: None,
+            gh_host: std::sync::OnceLock::from(None),
+            exec: timed_out,
+        }
+    }
+
+    #[test]
+    fn timed_out_pr_lookup_is_an_error_not_no_pr() {
+        assert!(hung_gh().detect_current_pr_number().is_err());
+    }
+
+    #[test]
+    fn timed_out_pr_lookup_passes_the_pr_guard_in_safe_mode() {
+        let gh = hung_gh();
+        let out = create_pr_guard_service(&gh).check(&PrGuardInput {
+            tool_command: Some("gh pr create --title x".to_string()),
+        });
+        assert!(out.allowed);
+        assert_eq!(
+            out.reason.as_deref(),
+            Some("could not check existing PR (safe mode)")
+        );
+    }
 
     /// Extracts the captured GH_HOST value, mirroring `load_gh_host`'s parse.
     fn parse(content: &str) -> Option<&str> {
@@ -1,7 +1,13 @@
 //! Default-branch guard — port of `git-utils/src/core/guard.ts`. Decides
 //! whether a write/commit on a protected branch is allowed. `GuardService`
 //! takes a `GitService` by injection so it is unit-testable with a mock git.
+//!
+//! A commit also answers to the project's commit-message policy
+//! (`core::commit_message`) when one is configured: its violations block the
+//! commit on any branch, and join the branch-guard reason on a protected one.
 
+use crate::git::core::command_line::strip_quoted;
+use crate::git::core::commit_message;
 use crate::git::core::git::GitService;
 use crate::git::types::{GuardInput, GuardOutput, GuardTarget};
 use regex::Regex;
@@ -16,48 +22,6 @@ static GIT_COMMIT_PATTERN: LazyLock<Regex> =
 /// renders it), not the CLI router that merely forwards the flag.
 pub const DEFAULT_CREATE_BRANCH_SCRIPT: &str = "git switch -c";
 
-/// Replaces single-/double-quoted segments with a space so quoted text
-/// arguments can't false-positive the commit matcher — on a protected branch,
-/// `gh issue create --body "... git commit ..."` must not be treated as a
-/// commit (#754). Trade-off: a commit nested entirely inside quotes
-/// (`bash -c "git commit"`) is no longer matched; the guard is a guard-rail,
-/// not an escape-proof sandbox.
-fn strip_quoted(command: &str) -> String {
-    let mut out = String::with_capacity(command.len());
-    let mut chars = command.chars();
-    while let Some(c) = chars.next() {
-        match c {
-            // An escaped char never opens/closes a quote; keep it verbatim so
-            // `echo \"git commit\"` stays conservative (still matches).
-            '\\' => {
-                if let Some(next) = chars.next() {
-                    out.push(next);
-                }
-            }
-            '\'' => {
-                for q in chars.by_ref() {
-                    if q == '\'' {
-                        break;
-                    }
-                }
-                out.push(' ');
-            }
-            '"' => {
-                while let Some(q) = chars.next() {
-                    if q == '\\' {
-                        chars.next();
-                    } else if q == '"' {
-                        break;
-                    }
-                }
-                out.push(' ');
-            }
-            _ => out.push(c),
-        }
-    }
-    out
-}
-
 /// Lexically collapses `.`/`..` in `path` (relative to `base`) without touching
 /// the filesystem. A relative `path` is anchored at `base` rather than the
 /// process cwd — the guard runs as a PreToolUse hook whose cwd may differ from
@@ -153,13 +117,73 @@ pub fn create_guard_service(git: &dyn GitService) -> RealGuardService<'_> {
     RealGuardService { git }
 }
 
+/// Default + develop + extras, in that order and without duplicates.
+fn protected_set(default_branch: &str, extras: Option<&Vec<String>>) -> Vec<String> {
+    let mut protected: Vec<String> = vec![default_branch.to_string(), "develop".to_string()];
+    for b in extras.into_iter().flatten() {
+        if !protected.contains(b) {
+            protected.push(b.clone());
+        }
+    }
+    protected
+}
+
 impl GuardService for RealGuardService<'_> {
     fn check(&self, input: &GuardInput) -> GuardOutput {
+        let output = self.check_branch(input);
+        let GuardTarget::Commit {
+            command: Some(command),
+        } = &input.target
+        else {
+            return output;
+        };
+        if !input.commit_message.is_enabled() {
+            return output;
+        }
+
+        // WIP is only refused where it would land on a protected branch; the
+        // branch check already resolved both names when it got that far.
+        let on_protected = match (&output.current_branch, &output.default_branch) {
+            (Some(current), Some(default)) => {
+                protected_set(default, input.protected_branches.as_ref())
+                    .contains(current)
+                    .then_some(current.as_str())
+            }
+            _ => None,
+        };
+        let project = resolve_project_dir(&input.project_dir);
+        let violations: Vec<String> = commit_message::extract_messages(command, &project)
+            .iter()
+            .flat_map(|m| commit_message::violations(&input.commit_message, m, on_protected))
+            .collect();
+        if violations.is_empty() {
+            return output;
+        }
+
+        let policy = commit_message::render_reason(&violations);
+        let reason = match output.reason.as_deref() {
+            Some(branch) if !output.allowed => format!("{branch}\n\n{policy}"),
+            _ => policy,
+        };
+        GuardOutput {
+            allowed: false,
+            ask: false,
+            reason: Some(reason),
+            ..output
+        }
+    }
+}
+
+impl RealGuardService<'_> {
+    /// The branch guard proper: may this write/commit happen on this branch.
+    fn check_branch(&self, input: &GuardInput) -> GuardOutput {
         let pass = |reason: Option<&str>| GuardOutput {
             allowed: true,
+            ask: false,
             reason: reason.map(|s| s.to_string()),
             current_branch: None,
             default_branch: None,
+            in_progress: None,
         };
 
         // Target-specific prefilters; the payload lives on the variant (#777).
@@ -179,7 +203,11 @@ impl GuardService for RealGuardService<'_> {
             }
             // commit guard: not a git commit command → pass. Quoted segments
             // are stripped so text arguments mentioning "git commit" don't
-            // match (#754).
+            // match — on a protected branch, `gh issue create --body "... git
+            // commit ..."` must not be treated as a commit (#754). Trade-off:
+            // a commit nested entirely inside quotes (`bash -c "git commit"`)
+            // is not matched; the guard is a guard-rail, not an escape-proof
+            // sandbox.
             GuardTarget::Commit { command } => {
                 let is_commit = command
                     .as_ref()
@@ -211,21 +239,32 @@ impl GuardService for RealGuardService<'_> {
             },
         };
 
-        // Protected set: default + develop + extras.
-        let mut protected: Vec<String> = vec![default_branch.clone(), "develop".to_string()];
-        if let Some(extras) = &input.protected_branches {
-            for b in extras {
-                if !protected.contains(b) {
-                    protected.push(b.clone());
-                }
-            }
-        }
+        let protected = protected_set(&default_branch, input.protected_branches.as_ref());
 
         // Guard 2: special state (rebase/merge) → pass. The snapshot also
         // carries the current branch, so guards 2–3 and the branch check cost
         // one `get_special_state` round-trip, not a second subprocess (#778).
         let state = self.git.get_special_state();
         if state.rebase || state.merge {
+            // Edits are conflict resolution and always pass. A commit is the
+            // borderline case: it may land on a protected branch — a merge
+            // into it, or a rebase whose detached HEAD hides the branch — so
+            // it passes under the exit-code contract but asks under JSON.
+            let commit = matches!(input.target, GuardTarget::Commit { .. });
+            if commit && (state.detached() || protected.contains(&state.current_branch)) {
+                let operation = if state.rebase { "rebase" } else { "merge" };
+                return GuardOutput {
+                    allowed: true,
+                    ask: true,
+                    reason: Some(format!(
+                        "[Branch Guard] {operation} 진행 중 커밋입니다. 보호 브랜치({})에 기록될 수 있으니 확인해주세요.",
+                        protected.join(", ")
+                    )),
+                    current_branch: Some(state.current_branch).filter(|b| !b.is_empty()),
+                    default_branch: Some(default_branch),
+                    in_progress: Some(operation),
+                };
+            }
             return pass(Some("special git state (rebase/merge)"));
         }
 
@@ -239,9 +278,11 @@ impl GuardService for RealGuardService<'_> {
         if !protected.contains(&current_branch) {
             return GuardOutput {
                 allowed: true,
+                ask: false,
                 reason: None,
                 current_branch: Some(current_branch),
                 default_branch: Some(default_branch),
+                in_progress: None,
             };
         }
 
@@ -266,9 +307,11 @@ impl GuardService for RealGuardService<'_> {
 
         GuardOutput {
             allowed: false,
+            ask: false,
             reason: Some(reason),
             current_branch: Some(current_branch),
             default_branch: Some(default_branch),
+            in_progress: None,
         }
     }
 }
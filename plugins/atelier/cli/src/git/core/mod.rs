@@ -2,6 +2,9 @@
 //! Each module declares a `*Service` trait plus a real shell-backed
 //! implementation so commands depend on abstractions, not the git/gh CLIs.
 
+pub mod branch_cache;
+pub mod command_line;
+pub mod commit_message;
 pub mod git;
 pub mod github;
 pub mod guard;
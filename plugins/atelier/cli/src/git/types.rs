@@ -3,6 +3,8 @@
 //! Input/Output structs preserve the original JSON shapes so `atelier git`
 //! emits byte-identical output to the legacy `git-utils` CLI.
 
+use crate::shared::config::CommitMessageConfig;
+use crate::shared::hook_output::{HookOutput, HookSpecificOutput, PermissionDecision};
 use serde::Serialize;
 
 /// Mirror of the TS `Result<T> = {ok:true,data} | {ok:false,error}`. Commands
@@ -87,14 +89,21 @@ pub struct GuardInput {
     pub create_branch_script: String,
     pub default_branch: Option<String>,
     pub protected_branches: Option<Vec<String>>,
+    /// Message rules for the commit target; the default checks nothing.
+    pub commit_message: CommitMessageConfig,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GuardOutput {
     pub allowed: bool,
+    /// Allowed, but borderline enough to confirm with the user — see
+    /// `GuardDecision::ask`.
+    pub ask: bool,
     pub reason: Option<String>,
     pub current_branch: Option<String>,
     pub default_branch: Option<String>,
+    /// `"rebase"` or `"merge"` when one in progress made the call borderline.
+    pub in_progress: Option<&'static str>,
 }
 
 /// Unified allow/block decision returned by the guard command after routing
@@ -102,7 +111,15 @@ pub struct GuardOutput {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GuardDecision {
     pub allowed: bool,
+    /// An allowed call the user should confirm (e.g. a commit mid-rebase).
+    /// Only the JSON output can say so; the exit-code contract has no third
+    /// outcome and lets it through, as it always has.
+    pub ask: bool,
     pub reason: Option<String>,
+    /// The git facts behind a block or ask, for the model rather than the
+    /// user: sent as `additionalContext`, so it can act on them (branch off
+    /// the right base, finish the rebase) instead of re-reading git.
+    pub context: Option<String>,
 }
 
 impl GuardDecision {
@@ -115,13 +132,59 @@ impl GuardDecision {
             2
         }
     }
+
+    /// `permissionDecision` for the JSON contract; `None` for a plain pass,
+    /// which leaves Claude Code's normal permission flow in charge.
+    pub fn permission(&self) -> Option<PermissionDecision> {
+        if !self.allowed {
+            Some(PermissionDecision::Deny)
+        } else if self.ask {
+            Some(PermissionDecision::Ask)
+        } else {
+            None
+        }
+    }
+
+    /// The decision as Claude Code's PreToolUse JSON output.
+    pub fn hook_output(&self) -> HookOutput {
+        HookOutput {
+            hook_specific_output: self.permission().map(|decision| HookSpecificOutput {
+                hook_event_name: "PreToolUse".to_string(),
+                permission_decision: Some(decision),
+                permission_decision_reason: self.reason.clone(),
+                additional_context: self.context.clone(),
+            }),
+            system_message: None,
+        }
+    }
+}
+
+/// How `git guard` reports its decision: the 0/2 exit-code contract with the
+/// reason on stderr, or Claude Code's JSON on stdout (always exit 0).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum GuardFormat {
+    #[default]
+    ExitCode,
+    Json,
 }
 
 impl From<GuardOutput> for GuardDecision {
     fn from(out: GuardOutput) -> Self {
+        let facts = [
+            out.current_branch.map(|b| format!("current branch: {b}")),
+            out.default_branch.map(|b| format!("default branch: {b}")),
+            out.in_progress.map(|op| format!("{op} in progress")),
+        ];
+        let context = (!out.allowed || out.ask)
+            .then(|| facts.into_iter().flatten().collect::<Vec<_>>().join(", "))
+            .filter(|c| !c.is_empty())
+            .map(|c| format!("[atelier guard] {c}"));
         GuardDecision {
             allowed: out.allowed,
+            ask: out.ask,
             reason: out.reason,
+            context,
         }
     }
 }
@@ -130,7 +193,11 @@ impl From<PrGuardOutput> for GuardDecision {
     fn from(out: PrGuardOutput) -> Self {
         GuardDecision {
             allowed: out.allowed,
+            ask: false,
             reason: out.reason,
+            context: out
+                .pr_number
+                .map(|n| format!("[atelier guard] open PR for this branch: #{n}")),
         }
     }
 }
@@ -233,7 +300,8 @@ impl DetectedBranch {
 }
 
 /// Which settings.json a hook registration targets.
-#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename_all = "lowercase")]
 pub enum HookScope {
     User,
@@ -318,3 +386,13 @@ impl GitSpecialState {
         self.current_branch.is_empty()
     }
 }
+
+// ---------------------------------------------------------------------------
+// Cache
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CacheClearOutput {
+    /// Cached default-branch entries removed.
+    pub cleared: usize,
+}
@@ -1,5 +1,15 @@
+//! atelier as a library: each subsystem's command layer is callable directly
+//! (`drift::check`, `git::guard::evaluate`, `session::simplify::evaluate`,
+//! `doctor::diagnose`).
+//! The clap surfaces and the `atelier` binary sit behind the default `cli`
+//! feature; `default-features = false` builds without clap.
+
+#[cfg(feature = "cli")]
 pub mod cli;
+pub mod dispatch;
+pub mod doctor;
 pub mod drift;
 pub mod git;
 pub mod session;
 pub mod shared;
+pub mod state;
@@ -0,0 +1,203 @@
+//! The session clap surface: parses the hook flags, reads the payload off
+//! stdin, and prints whatever the commands decide.
+
+use crate::session::commands::advise::Advice;
+use crate::session::commands::payload::SessionPayload;
+use crate::session::commands::report::{ReportFormat, SessionReport};
+use crate::session::commands::simplify::{render_banner, SimplifyDecision};
+use crate::session::commands::{self, SessionDeps};
+use crate::session::core::transcript::create_transcript_source;
+use crate::session::with_project_deps;
+use crate::shared::config::ProjectConfig;
+use crate::shared::process::{default_project_dir, read_stdin_raw};
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(
+    name = "session",
+    version,
+    about = "Session-scoped hook helpers (baseline / advise / simplify-check / report)"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// SessionStart: record the repository state this session starts from
+    Baseline {
+        /// Project the git reads are anchored to (hook cwd may differ — #780)
+        #[arg(long = "project-dir")]
+        project_dir: Option<String>,
+    },
+    /// Stop: run the enabled advisors (simplify, tests, diff size, lockfile)
+    Advise {
+        /// Project the git reads are anchored to (hook cwd may differ — #780)
+        #[arg(long = "project-dir")]
+        project_dir: Option<String>,
+    },
+    /// Stop: suggest `/simplify` when this session changed code
+    #[command(name = "simplify-check")]
+    SimplifyCheck {
+        /// Project the git reads are anchored to (hook cwd may differ — #780)
+        #[arg(long = "project-dir")]
+        project_dir: Option<String>,
+    },
+    /// Report what this session did relative to its baseline
+    Report {
+        /// Project the git reads are anchored to (hook cwd may differ — #780)
+        #[arg(long = "project-dir")]
+        project_dir: Option<String>,
+        /// Session to report on; read from the hook payload on stdin if absent
+        #[arg(long = "session-id")]
+        session_id: Option<String>,
+        /// Session transcript (JSONL); read from the hook payload if absent
+        #[arg(long = "transcript")]
+        transcript: Option<String>,
+        #[arg(long = "format", value_enum, default_value_t = ReportFormat::Json)]
+        format: ReportFormat,
+    },
+}
+
+/// Resolves the project anchor: the explicit flag first, then the payload cwd,
+/// then the process cwd. Never guesses beyond those documented fallbacks.
+fn resolve_project_dir(flag: Option<String>, payload: &SessionPayload) -> String {
+    default_project_dir(
+        flag.filter(|d| !d.is_empty())
+            .or_else(|| payload.cwd.clone().filter(|d| !d.is_empty())),
+    )
+}
+
+/// The subsystem's only stdout write. #725 (moving hook output to
+/// `hookSpecificOutput.additionalContext`) has exactly this one site to change.
+fn emit(decision: &SimplifyDecision) {
+    if let SimplifyDecision::Notify { files, total } = decision {
+        print!("{}", render_banner(files, *total));
+    }
+}
+
+/// Prints every advisor banner, in advisor order.
+fn emit_advice(advice: &[Advice]) {
+    for a in advice {
+        print!("{}", a.message);
+    }
+}
+
+/// Renders a report (or its error) — the one output site of `report`.
+fn emit_report(report: Result<SessionReport, String>, format: ReportFormat) -> i32 {
+    match report {
+        Ok(report) => {
+            match format {
+                ReportFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).unwrap_or_else(|_| "null".to_string())
+                ),
+                ReportFormat::Markdown => print!("{}", report.render_markdown()),
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+/// Parses `argv` (including the leading program name) with the session clap
+/// surface and runs the selected command. Always returns 0.
+pub fn run_from<I, T>(argv: I) -> i32
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    run(Cli::parse_from(argv))
+}
+
+/// Binds the real store and repository reader to the resolved project, then
+/// hands the command its dependencies and the payload's session id.
+fn with_deps(
+    project_dir: Option<String>,
+    payload: &SessionPayload,
+    command: impl FnOnce(&SessionDeps, &str),
+) {
+    let project_dir = resolve_project_dir(project_dir, payload);
+    with_project_deps(&project_dir, |deps| {
+        command(deps, payload.session_id.as_deref().unwrap_or_default())
+    });
+}
+
+/// Runs a parsed session CLI. The hooks always return 0 — they are
+/// non-blocking; only `report` can fail, with 1.
+pub fn run(cli: Cli) -> i32 {
+    let command = match cli.command {
+        Some(c) => c,
+        None => {
+            use clap::CommandFactory;
+            let _ = Cli::command().print_help();
+            println!();
+            return 0;
+        }
+    };
+
+    // An explicit session id means an interactive caller: reading stdin there
+    // would block on a terminal instead of on a hook's closed pipe.
+    let explicit_id = match &command {
+        Commands::Report { session_id, .. } => session_id.clone(),
+        _ => None,
+    };
+    let payload = match explicit_id {
+        Some(id) => SessionPayload {
+            session_id: Some(id),
+            ..SessionPayload::default()
+        },
+        None => SessionPayload::parse(&read_stdin_raw()),
+    };
+    match command {
+        Commands::Baseline { project_dir } => with_deps(project_dir, &payload, |deps, id| {
+            commands::baseline::run(deps, id);
+        }),
+        Commands::Advise { project_dir } => {
+            // Resolved once here: the config is read from the same project the
+            // git reads are anchored to.
+            let project_dir = resolve_project_dir(project_dir, &payload);
+            let config = ProjectConfig::load(&project_dir);
+            let transcript = create_transcript_source(payload.transcript_path.clone());
+            with_deps(Some(project_dir), &payload, |deps, id| {
+                emit_advice(&commands::advise::run(
+                    deps,
+                    &transcript,
+                    &config.session,
+                    id,
+                ));
+            })
+        }
+        Commands::SimplifyCheck { project_dir } => {
+            let project_dir = resolve_project_dir(project_dir, &payload);
+            let config = ProjectConfig::load(&project_dir);
+            with_deps(Some(project_dir), &payload, |deps, id| {
+                emit(&commands::simplify::run(deps, &config.session.simplify, id));
+            })
+        }
+        Commands::Report {
+            project_dir,
+            format,
+            transcript,
+            ..
+        } => {
+            let project_dir = resolve_project_dir(project_dir, &payload);
+            let config = ProjectConfig::load(&project_dir);
+            let transcript =
+                create_transcript_source(transcript.or_else(|| payload.transcript_path.clone()));
+            let mut code = 0;
+            with_deps(Some(project_dir), &payload, |deps, id| {
+                code = emit_report(
+                    commands::report::run(deps, &transcript, &config.session.simplify, id),
+                    format,
+                );
+            });
+            return code;
+        }
+    }
+    0
+}
@@ -0,0 +1,100 @@
+//! `session advise` command — the Stop hook. Runs every enabled advisor that
+//! has not spoken yet this session over one shared input, records the ones
+//! that spoke, and returns their banners in advisor order.
+//!
+//! Reads are gated the same way `simplify-check` gates them: nothing is read
+//! once every enabled advisor has spoken, and the diff and transcript are read
+//! only when the session changed something.
+
+use crate::session::commands::advisors::{
+    builtin_advisors, Advisor, AdvisorDecision, AdvisorInput,
+};
+use crate::session::commands::baseline as baseline_command;
+use crate::session::commands::simplify::{
+    gather_input, session_changes, session_line_stats, SimplifyRules,
+};
+use crate::session::commands::SessionDeps;
+use crate::session::core::baseline::is_valid_session_id;
+use crate::session::core::transcript::TranscriptSource;
+use crate::shared::config::SessionConfig;
+use crate::shared::trace;
+
+/// One advisor's banner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advice {
+    pub advisor: &'static str,
+    pub message: String,
+}
+
+/// Runs `advisors` for the session. Every way this can decline — rejected id,
+/// no baseline, nothing pending, nothing changed — yields no advice.
+pub fn run_with(
+    deps: &SessionDeps,
+    transcript: &dyn TranscriptSource,
+    config: &SessionConfig,
+    session_id: &str,
+    advisors: &[&dyn Advisor],
+) -> Vec<Advice> {
+    if !is_valid_session_id(session_id) {
+        return Vec::new();
+    }
+    let Some(mut baseline) = deps.store.load(session_id) else {
+        // Same self-healing as `simplify-check`: anchor now, speak next turn.
+        baseline_command::run(deps, session_id);
+        return Vec::new();
+    };
+    let pending: Vec<&dyn Advisor> = advisors
+        .iter()
+        .copied()
+        .filter(|a| config.advisor_enabled(a.id()) && !baseline.is_advised(a.id()))
+        .collect();
+    if pending.is_empty() {
+        return Vec::new();
+    }
+
+    let attribution = gather_input(deps, baseline.clone());
+    let changes = session_changes(&baseline, &attribution);
+    if changes.is_empty() {
+        return Vec::new();
+    }
+    let input = AdvisorInput {
+        rules: SimplifyRules::resolve(deps.repo, &config.simplify, &changes),
+        line_stats: session_line_stats(deps, &baseline, &changes),
+        changes,
+        transcript: transcript.transcript(),
+        root: deps.repo.root(),
+    };
+
+    let advice: Vec<Advice> = pending
+        .into_iter()
+        .filter_map(|advisor| match advisor.decide(&input) {
+            AdvisorDecision::Notify(message) => Some(Advice {
+                advisor: advisor.id(),
+                message,
+            }),
+            AdvisorDecision::Silent => None,
+        })
+        .collect();
+    if !advice.is_empty() {
+        for a in &advice {
+            baseline.mark_advised(a.advisor);
+        }
+        // A failed write only costs a repeated banner — never a blocked Stop.
+        let _ = deps.store.save(session_id, &baseline);
+    }
+    trace::info(
+        "advise_decision",
+        serde_json::json!({ "advisors": advice.iter().map(|a| a.advisor).collect::<Vec<_>>() }),
+    );
+    advice
+}
+
+/// Runs the built-in advisors.
+pub fn run(
+    deps: &SessionDeps,
+    transcript: &dyn TranscriptSource,
+    config: &SessionConfig,
+    session_id: &str,
+) -> Vec<Advice> {
+    run_with(deps, transcript, config, session_id, &builtin_advisors())
+}
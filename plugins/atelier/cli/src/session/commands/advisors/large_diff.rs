@@ -0,0 +1,38 @@
+//! "Large diff, consider splitting": the session's changes are past a size a
+//! reviewer can take in as one unit.
+
+use crate::session::commands::advisors::{render_advice, Advisor, AdvisorDecision, AdvisorInput};
+
+/// Lines added plus removed across the session's files.
+pub const LARGE_DIFF_LINES: u64 = 500;
+
+/// Files the session changed, whatever their size.
+pub const LARGE_DIFF_FILES: usize = 25;
+
+pub struct LargeDiffAdvisor;
+
+impl Advisor for LargeDiffAdvisor {
+    fn id(&self) -> &'static str {
+        "large-diff"
+    }
+
+    fn decide(&self, input: &AdvisorInput) -> AdvisorDecision {
+        let lines: u64 = input
+            .changes
+            .iter()
+            .filter_map(|p| input.line_stats.get(p).copied().flatten())
+            .map(|s| s.added + s.removed)
+            .sum();
+        let files = input.changes.len();
+        if lines < LARGE_DIFF_LINES && files < LARGE_DIFF_FILES {
+            return AdvisorDecision::Silent;
+        }
+        AdvisorDecision::Notify(render_advice(
+            "[atelier] 변경 규모 검토",
+            &[
+                format!("이번 세션의 변경이 {files}개 파일, {lines}줄입니다."),
+                "리뷰하기 쉽도록 독립적인 단위로 나눠 커밋·PR 하는 것을 고려해 보세요.".to_string(),
+            ],
+        ))
+    }
+}
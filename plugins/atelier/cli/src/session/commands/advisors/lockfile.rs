@@ -0,0 +1,60 @@
+//! "Lockfile changed without manifest": a dependency lockfile moved while no
+//! manifest that declares those dependencies did — usually an accidental
+//! `install`/`update` rather than an intended dependency change.
+
+use crate::session::commands::advisors::{render_advice, Advisor, AdvisorDecision, AdvisorInput};
+
+/// Lockfile name → the manifest names that own it.
+const LOCKFILES: &[(&str, &[&str])] = &[
+    ("Cargo.lock", &["Cargo.toml"]),
+    ("package-lock.json", &["package.json"]),
+    ("yarn.lock", &["package.json"]),
+    ("pnpm-lock.yaml", &["package.json", "pnpm-workspace.yaml"]),
+    ("bun.lockb", &["package.json"]),
+    ("bun.lock", &["package.json"]),
+    ("poetry.lock", &["pyproject.toml"]),
+    ("uv.lock", &["pyproject.toml"]),
+    ("Pipfile.lock", &["Pipfile"]),
+    ("Gemfile.lock", &["Gemfile"]),
+    ("composer.lock", &["composer.json"]),
+    ("go.sum", &["go.mod"]),
+];
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub struct LockfileAdvisor;
+
+impl Advisor for LockfileAdvisor {
+    fn id(&self) -> &'static str {
+        "lockfile-without-manifest"
+    }
+
+    fn decide(&self, input: &AdvisorInput) -> AdvisorDecision {
+        // A manifest anywhere in the change set excuses the lockfile: a
+        // workspace lockfile sits at the root while the member manifest that
+        // moved it lives in a subdirectory.
+        let names: Vec<&str> = input.changes.iter().map(|p| file_name(p)).collect();
+        let orphaned: Vec<&String> = input
+            .changes
+            .iter()
+            .filter(|path| {
+                LOCKFILES.iter().any(|(lock, manifests)| {
+                    file_name(path) == *lock && !manifests.iter().any(|m| names.contains(m))
+                })
+            })
+            .collect();
+        if orphaned.is_empty() {
+            return AdvisorDecision::Silent;
+        }
+        let mut body = vec![
+            "매니페스트 변경 없이 lockfile 이 바뀌었습니다:".to_string(),
+            String::new(),
+        ];
+        body.extend(orphaned.iter().map(|p| format!("    {p}")));
+        body.push(String::new());
+        body.push("의도한 의존성 변경인지 확인하고, 아니라면 lockfile 을 되돌리세요.".to_string());
+        AdvisorDecision::Notify(render_advice("[atelier] lockfile 변경 확인", &body))
+    }
+}
@@ -0,0 +1,68 @@
+//! Stop-time advisors — small, independent rules that each may suggest one
+//! thing to the agent at the end of a turn. Each advisor is a pure
+//! `decide(input) -> AdvisorDecision` over one shared `AdvisorInput`, so the
+//! repository and transcript are read once per Stop, however many advisors run.
+//!
+//! Every advisor speaks at most once per session (its id is recorded in the
+//! baseline) and can be switched off per project under `session.advisors` in
+//! `.claude/atelier.json`.
+
+pub mod large_diff;
+pub mod lockfile;
+pub mod simplify;
+pub mod tests_not_run;
+
+use crate::session::commands::simplify::{SimplifyRules, DIVIDER};
+use crate::session::core::repo::LineStat;
+use crate::session::core::transcript::Transcript;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Everything an advisor may read. Gathered once by the `advise` command.
+#[derive(Debug, Clone, Default)]
+pub struct AdvisorInput {
+    /// Files attributed to this session — the `/simplify` attribution rule.
+    pub changes: BTreeSet<String>,
+    /// Line counts of the changed files since the baseline `HEAD` (untracked
+    /// files count their lines as additions). `None` for binary content; a
+    /// path missing here has no known counts.
+    pub line_stats: BTreeMap<String, Option<LineStat>>,
+    /// The session transcript; `None` when the payload named none readable.
+    pub transcript: Option<Transcript>,
+    /// What counts as code for this project (`session.simplify`).
+    pub rules: SimplifyRules,
+    /// Repository root, for matching the transcript's absolute paths against
+    /// the root-relative rules.
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdvisorDecision {
+    Silent,
+    /// The rendered banner to show.
+    Notify(String),
+}
+
+pub trait Advisor {
+    /// Stable id — the config key and the once-per-session state key.
+    fn id(&self) -> &'static str;
+    fn decide(&self, input: &AdvisorInput) -> AdvisorDecision;
+}
+
+/// The advisors atelier ships, in the order their banners print.
+pub fn builtin_advisors() -> [&'static dyn Advisor; 4] {
+    [
+        &simplify::SimplifyAdvisor,
+        &tests_not_run::TestsNotRunAdvisor,
+        &large_diff::LargeDiffAdvisor,
+        &lockfile::LockfileAdvisor,
+    ]
+}
+
+/// Renders an advisor banner in the same frame as the `/simplify` one.
+pub fn render_advice(title: &str, body: &[String]) -> String {
+    format!(
+        "\n{DIVIDER}\n{title}\n{DIVIDER}\n\n{}\n\n{DIVIDER}\n",
+        body.join("\n")
+    )
+}
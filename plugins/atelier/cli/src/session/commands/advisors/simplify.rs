@@ -0,0 +1,23 @@
+//! The `/simplify` suggestion as an advisor — the same rule and banner as
+//! `session simplify-check`, applied to the changes the advisor run gathered.
+
+use crate::session::commands::advisors::{Advisor, AdvisorDecision, AdvisorInput};
+use crate::session::commands::simplify::{decide_changes, render_banner, SimplifyDecision};
+use crate::session::core::baseline::SIMPLIFY_ADVISOR;
+
+pub struct SimplifyAdvisor;
+
+impl Advisor for SimplifyAdvisor {
+    fn id(&self) -> &'static str {
+        SIMPLIFY_ADVISOR
+    }
+
+    fn decide(&self, input: &AdvisorInput) -> AdvisorDecision {
+        match decide_changes(input.changes.clone(), &input.rules, &input.line_stats) {
+            SimplifyDecision::Notify { files, total } => {
+                AdvisorDecision::Notify(render_banner(&files, total))
+            }
+            SimplifyDecision::Silent(_) => AdvisorDecision::Silent,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! "Tests not run after a code change": the transcript shows the agent edited
+//! a code file and ran no test command afterwards. Silent without a
+//! transcript — `git status` cannot tell whether tests ran.
+
+use crate::session::commands::advisors::{render_advice, Advisor, AdvisorDecision, AdvisorInput};
+use crate::session::core::transcript::{relative_to_root, BashKind};
+
+pub struct TestsNotRunAdvisor;
+
+impl Advisor for TestsNotRunAdvisor {
+    fn id(&self) -> &'static str {
+        "tests-not-run"
+    }
+
+    fn decide(&self, input: &AdvisorInput) -> AdvisorDecision {
+        let Some(transcript) = &input.transcript else {
+            return AdvisorDecision::Silent;
+        };
+        // Only code the session still owns counts: an edit that was reverted
+        // (or only touched docs) leaves nothing to test.
+        if input
+            .changes
+            .iter()
+            .all(|p| input.rules.is_docs_or_config(p))
+        {
+            return AdvisorDecision::Silent;
+        }
+        let root = input.root.as_deref();
+        let Some(last_code_edit) = transcript
+            .last_edit_where(|p| !input.rules.is_docs_or_config(&relative_to_root(root, p)))
+        else {
+            return AdvisorDecision::Silent;
+        };
+        if transcript.ran_after(last_code_edit, BashKind::Test) {
+            return AdvisorDecision::Silent;
+        }
+        AdvisorDecision::Notify(render_advice(
+            "[atelier] 테스트 실행 확인",
+            &[
+                "마지막 코드 변경 이후 테스트를 실행한 기록이 없습니다.".to_string(),
+                "작업을 마무리하기 전에 관련 테스트를 실행해 보세요.".to_string(),
+            ],
+        ))
+    }
+}
@@ -17,17 +17,22 @@ pub fn run(deps: &SessionDeps, session_id: &str) {
     }
     // SessionStart re-fires on resume/compact/clear, and `save_if_absent`
     // discards the snapshot every time after it. Ask the store first: one file
-    // read settles it, where the snapshot below costs three git processes.
+    // read settles it, where the snapshot below costs several git processes.
     if deps.store.load(session_id).is_some() {
         return;
     }
     if !deps.repo.is_inside_work_tree() {
         return;
     }
+    let dirty = deps.repo.dirty_files();
     let snapshot = Baseline {
         head: deps.repo.head(),
-        dirty: deps.repo.dirty_files(),
+        // Hashed now so a pre-dirty file the session rewrites later is still
+        // attributed to it (see `simplify::session_changes`).
+        dirty_blobs: deps.repo.blob_hashes(&dirty),
+        dirty,
         notified: false,
+        advised: Default::default(),
     };
     // Still `save_if_absent`, not `save`: the early return above is a fast
     // path, this is the guarantee that a concurrent write is never clobbered.
@@ -1,5 +1,8 @@
+pub mod advise;
+pub mod advisors;
 pub mod baseline;
 pub mod payload;
+pub mod report;
 pub mod simplify;
 
 use crate::session::core::baseline::BaselineStore;
@@ -1,6 +1,6 @@
 //! Hook stdin payload fields the session commands consume. Every Claude Code
 //! hook receives `session_id`, `transcript_path`, `cwd`, `hook_event_name` and
-//! `permission_mode` on stdin; only the two fields used here are parsed.
+//! `permission_mode` on stdin; only the fields used here are parsed.
 //!
 //! Deliberately separate from `git::commands::guard::HookPayload`, which reads
 //! `tool_input.*` for PreToolUse — the two schemas share no field.
@@ -14,6 +14,8 @@ pub struct SessionPayload {
     pub session_id: Option<String>,
     /// Session cwd — the project-dir fallback when the shim passes no flag.
     pub cwd: Option<String>,
+    /// The session's JSONL transcript, read by transcript-aware advisors.
+    pub transcript_path: Option<String>,
 }
 
 impl SessionPayload {
@@ -24,6 +26,7 @@ impl SessionPayload {
         SessionPayload {
             session_id: value["session_id"].as_str().map(|s| s.to_string()),
             cwd: value["cwd"].as_str().map(|s| s.to_string()),
+            transcript_path: value["transcript_path"].as_str().map(|s| s.to_string()),
         }
     }
 }
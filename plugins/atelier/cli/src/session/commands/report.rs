@@ -0,0 +1,349 @@
+//! `session report` command — everything this session did relative to its
+//! baseline: commits made since the baseline `HEAD`, the files it changed
+//! (split into code and docs/config the same way `/simplify` judges them),
+//! lines added and removed per file, and the untracked files it created. A
+//! file already dirty at the baseline is counted from the content recorded
+//! then, not from `HEAD`, so edits made before the session stay out.
+//!
+//! Attribution is `simplify::session_changes` and classification is the
+//! project's `SimplifyRules`, so the report and the banner never disagree
+//! about which files belong to the session or which of them are code. The report is a
+//! read: unlike `simplify-check` it never records a missing baseline, and it
+//! never marks the session notified.
+//!
+//! With a transcript, each file also says how it changed — written by an edit
+//! tool, or changed by a shell command (a formatter, a codegen step, a `sed`)
+//! — and the report carries the transcript's activity summary.
+
+use crate::session::commands::simplify::{
+    gather_input, session_changes, session_line_stats, SimplifyRules,
+};
+use crate::session::commands::SessionDeps;
+use crate::session::core::baseline::{is_valid_session_id, Baseline};
+use crate::session::core::repo::{CommitSummary, LineStat};
+use crate::session::core::transcript::{TranscriptSource, TranscriptSummary};
+use crate::shared::config::SimplifyConfig;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Output rendering for `session report`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+/// How a session-changed file got changed, per the transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeVia {
+    /// Written by an edit tool (Write/Edit/MultiEdit/NotebookEdit).
+    Edit,
+    /// Never named by an edit tool — changed as a side effect of a command.
+    Bash,
+}
+
+impl ChangeVia {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeVia::Edit => "edit",
+            ChangeVia::Bash => "bash",
+        }
+    }
+}
+
+/// One session-changed file. Both counts are `None` for binary content, or
+/// when no diff could be taken (no baseline `HEAD` in an empty repository).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub added: Option<u64>,
+    pub removed: Option<u64>,
+    /// `None` without a transcript.
+    pub via: Option<ChangeVia>,
+}
+
+/// What the session did, relative to its baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionReport {
+    pub session_id: String,
+    /// `HEAD` at session start; `None` when the session began in an empty repo.
+    pub baseline_head: Option<String>,
+    pub head: Option<String>,
+    /// Commits made since `baseline_head`, oldest first.
+    pub commits: Vec<CommitSummary>,
+    pub code_files: Vec<FileChange>,
+    /// Files `SimplifyRules` classifies as documentation or configuration.
+    pub doc_files: Vec<FileChange>,
+    /// Untracked files that did not exist (untracked) at session start.
+    pub untracked_created: Vec<String>,
+    /// Sums over every file with known counts.
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    /// What the transcript says ran; `None` without a transcript.
+    pub activity: Option<TranscriptSummary>,
+}
+
+/// True when `path` was already dirty at session start, either by name or
+/// under an untracked directory `git status` collapsed to `dir/`.
+fn predates_session(baseline: &Baseline, path: &str) -> bool {
+    baseline.dirty.contains(path)
+        || baseline
+            .dirty
+            .iter()
+            .any(|d| d.ends_with('/') && path.starts_with(d.as_str()))
+}
+
+/// Replaces each untracked directory entry (`dir/`, as `git status` reports a
+/// new directory) with the untracked files under it, so the report lists
+/// files with line counts rather than a directory without any.
+fn expand_directories(
+    changes: BTreeSet<String>,
+    untracked: &BTreeMap<String, Option<LineStat>>,
+) -> BTreeSet<String> {
+    changes
+        .into_iter()
+        .flat_map(|path| {
+            if !path.ends_with('/') {
+                return vec![path];
+            }
+            let inside: Vec<String> = untracked
+                .keys()
+                .filter(|f| f.starts_with(path.as_str()))
+                .cloned()
+                .collect();
+            if inside.is_empty() {
+                vec![path]
+            } else {
+                inside
+            }
+        })
+        .collect()
+}
+
+/// Assembles the report for a session. `Err` when the session id is rejected
+/// or no baseline was recorded — without one, nothing is attributable.
+pub fn run(
+    deps: &SessionDeps,
+    transcript: &dyn TranscriptSource,
+    config: &SimplifyConfig,
+    session_id: &str,
+) -> Result<SessionReport, String> {
+    if !is_valid_session_id(session_id) {
+        return Err("missing or invalid session id".to_string());
+    }
+    let baseline = deps
+        .store
+        .load(session_id)
+        .ok_or_else(|| format!("no baseline recorded for session {session_id}"))?;
+    let input = gather_input(deps, baseline.clone());
+    let untracked = deps.repo.untracked_files();
+    let changes = expand_directories(session_changes(&baseline, &input), &untracked);
+    let rules = SimplifyRules::resolve(deps.repo, config, &changes);
+
+    let commits = baseline
+        .head
+        .as_deref()
+        .map(|head| deps.repo.commits_since(head))
+        .unwrap_or_default();
+    let stats = session_line_stats(deps, &baseline, &changes);
+
+    let mut report = SessionReport {
+        session_id: session_id.to_string(),
+        head: deps.repo.head(),
+        baseline_head: baseline.head.clone(),
+        commits,
+        code_files: Vec::new(),
+        doc_files: Vec::new(),
+        untracked_created: untracked
+            .keys()
+            .filter(|path| !predates_session(&baseline, path))
+            .cloned()
+            .collect(),
+        lines_added: 0,
+        lines_removed: 0,
+        activity: transcript
+            .transcript()
+            .map(|t| t.summarize(deps.repo.root().as_deref())),
+    };
+    for path in changes {
+        let stat = stats.get(&path).copied().flatten();
+        if let Some(stat) = stat {
+            report.lines_added += stat.added;
+            report.lines_removed += stat.removed;
+        }
+        let via = report.activity.as_ref().map(|a| {
+            if a.edited_files.contains(&path) {
+                ChangeVia::Edit
+            } else {
+                ChangeVia::Bash
+            }
+        });
+        let change = FileChange {
+            added: stat.map(|s| s.added),
+            removed: stat.map(|s| s.removed),
+            via,
+            path,
+        };
+        if rules.is_docs_or_config(&change.path) {
+            report.doc_files.push(change);
+        } else {
+            report.code_files.push(change);
+        }
+    }
+    Ok(report)
+}
+
+/// Abbreviated commit id for display; full ids stay in the JSON form.
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+fn count(n: Option<u64>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn file_table(out: &mut Vec<String>, title: &str, files: &[FileChange]) {
+    if files.is_empty() {
+        return;
+    }
+    out.push(format!("### {title} ({})", files.len()));
+    out.push(String::new());
+    out.push("| File | + | − | Via |".to_string());
+    out.push("|---|---:|---:|---|".to_string());
+    for f in files {
+        out.push(format!(
+            "| `{}` | {} | {} | {} |",
+            f.path,
+            count(f.added),
+            count(f.removed),
+            f.via.map(ChangeVia::as_str).unwrap_or("-")
+        ));
+    }
+    out.push(String::new());
+}
+
+fn yes_no(flag: bool) -> &'static str {
+    if flag {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn activity_section(out: &mut Vec<String>, activity: &TranscriptSummary) {
+    out.push("### Activity".to_string());
+    out.push(String::new());
+    let tools: Vec<String> = activity
+        .tool_counts
+        .iter()
+        .map(|(tool, n)| format!("{tool} ×{n}"))
+        .collect();
+    out.push(format!(
+        "- Tools: {}",
+        if tools.is_empty() {
+            "none".to_string()
+        } else {
+            tools.join(", ")
+        }
+    ));
+    if activity.edited {
+        out.push(format!(
+            "- Tests after last edit: {}",
+            yes_no(activity.tests_after_last_edit)
+        ));
+        out.push(format!(
+            "- Build after last edit: {}",
+            yes_no(activity.build_after_last_edit)
+        ));
+    }
+    out.push(String::new());
+}
+
+impl SessionReport {
+    /// Markdown for PR descriptions and hook output. Empty sections are
+    /// omitted; a session with no changes renders one line saying so.
+    pub fn render_markdown(&self) -> String {
+        let mut out = vec!["## Session report".to_string(), String::new()];
+        let range = match (&self.baseline_head, &self.head) {
+            (Some(from), Some(to)) if from != to => {
+                format!("`{}` → `{}`", short_sha(from), short_sha(to))
+            }
+            (Some(from), _) => format!("`{}`", short_sha(from)),
+            (None, Some(to)) => format!("(empty) → `{}`", short_sha(to)),
+            (None, None) => "(empty)".to_string(),
+        };
+        out.push(format!(
+            "Baseline {range} · +{} −{} lines",
+            self.lines_added, self.lines_removed
+        ));
+        out.push(String::new());
+
+        if self.commits.is_empty()
+            && self.code_files.is_empty()
+            && self.doc_files.is_empty()
+            && self.untracked_created.is_empty()
+        {
+            out.push("No changes attributed to this session.".to_string());
+            out.push(String::new());
+        }
+        if !self.commits.is_empty() {
+            out.push(format!("### Commits ({})", self.commits.len()));
+            out.push(String::new());
+            for c in &self.commits {
+                out.push(format!("- `{}` {}", short_sha(&c.sha), c.subject));
+            }
+            out.push(String::new());
+        }
+        file_table(&mut out, "Code", &self.code_files);
+        file_table(&mut out, "Docs / config", &self.doc_files);
+        if !self.untracked_created.is_empty() {
+            out.push(format!(
+                "### New untracked files ({})",
+                self.untracked_created.len()
+            ));
+            out.push(String::new());
+            for path in &self.untracked_created {
+                out.push(format!("- `{path}`"));
+            }
+            out.push(String::new());
+        }
+        if let Some(activity) = &self.activity {
+            activity_section(&mut out, activity);
+        }
+        out.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_untracked_directory_into_its_files() {
+        let changes: BTreeSet<String> = ["new/".to_string(), "src/lib.rs".to_string()].into();
+        let untracked = BTreeMap::from([
+            ("new/a.rs".to_string(), None),
+            ("new/b.rs".to_string(), None),
+            ("other.txt".to_string(), None),
+        ]);
+        assert_eq!(
+            expand_directories(changes, &untracked)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["new/a.rs", "new/b.rs", "src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn files_under_a_pre_dirty_directory_predate_the_session() {
+        let baseline = Baseline {
+            dirty: ["scratch/".to_string()].into(),
+            ..Baseline::default()
+        };
+        assert!(predates_session(&baseline, "scratch/notes.rs"));
+        assert!(!predates_session(&baseline, "scratchpad.rs"));
+    }
+}
@@ -6,16 +6,27 @@
 //! speak once per session, only about files *this session* touched, and only
 //! when at least one of them is not documentation or configuration.
 //!
+//! A file that was already dirty at session start is the session's too once
+//! its content moves away from the blob hash the baseline recorded — otherwise
+//! heavy rework of a pre-modified file would never reach the banner.
+//!
+//! What counts as code is tunable per project under `session.simplify` in
+//! `.claude/atelier.json` — include/exclude globs, a changed-line threshold,
+//! the banner's listing limit — and `.gitattributes` `linguist-generated` /
+//! `linguist-documentation` mark paths as not code, as they do on GitHub.
+//!
 //! `decide` is pure — all repository and storage access is injected — so the
 //! whole rule set is exercised in memory.
 
 use crate::session::commands::baseline as baseline_command;
 use crate::session::commands::SessionDeps;
 use crate::session::core::baseline::{is_valid_session_id, Baseline};
-use std::collections::BTreeSet;
-
-/// How many paths the banner lists before collapsing the rest into a count.
-pub const MAX_LISTED_FILES: usize = 10;
+use crate::session::core::repo::{LineStat, RepoReader};
+use crate::session::with_project_deps;
+use crate::shared::config::{ProjectConfig, SimplifyConfig};
+use crate::shared::glob::{compile_all, Glob};
+use crate::shared::trace;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Extensions that carry no code to simplify. A session that only touched
 /// these has nothing for `/simplify` to review.
@@ -35,6 +46,13 @@ pub struct SimplifyInput {
     pub dirty: BTreeSet<String>,
     /// Paths changed by commits made since the baseline `HEAD`.
     pub committed: BTreeSet<String>,
+    /// Current blob hashes of the paths that were dirty at session start, are
+    /// still dirty, and have a hash in the baseline — the only paths whose
+    /// attribution the hashes can change.
+    pub dirty_blobs: BTreeMap<String, Option<String>>,
+    /// Line counts of the changed files (see `session_line_stats`). Read only
+    /// when a changed-line threshold is configured; empty otherwise.
+    pub line_stats: BTreeMap<String, Option<LineStat>>,
 }
 
 /// Why the hook stayed silent. Distinct variants so the reasoning is legible
@@ -51,20 +69,24 @@ pub enum SilentReason {
     NoSessionChanges,
     /// Only docs and config changed — nothing for `/simplify` to review.
     DocsOnly,
+    /// Code changed, but fewer lines than `min_changed_lines`.
+    BelowThreshold,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SimplifyDecision {
     Silent(SilentReason),
     Notify {
-        /// Up to `MAX_LISTED_FILES` paths, sorted, for the banner.
+        /// Up to `max_listed_files` paths, sorted, for the banner.
         files: Vec<String>,
         /// Full count of session-changed files.
         total: usize,
     },
 }
 
-/// True for paths whose content `/simplify` has nothing to say about.
+/// True for paths whose content `/simplify` has nothing to say about, by the
+/// built-in extension rules alone. `SimplifyRules::is_docs_or_config` layers
+/// the project's globs and `.gitattributes` on top.
 pub fn is_docs_or_config(path: &str) -> bool {
     let name = path.rsplit('/').next().unwrap_or(path);
     if DOC_FILENAMES.contains(&name) {
@@ -80,20 +102,96 @@ pub fn is_docs_or_config(path: &str) -> bool {
     }
 }
 
+/// The classification and banner rules in force for one set of changes: the
+/// project's `session.simplify` config, plus the `.gitattributes` verdicts for
+/// the changed paths.
+#[derive(Debug, Clone)]
+pub struct SimplifyRules {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+    /// Changed paths `.gitattributes` marks generated or documentation.
+    non_code: BTreeSet<String>,
+    pub min_changed_lines: u64,
+    pub max_listed_files: usize,
+}
+
+impl Default for SimplifyRules {
+    /// The built-in rules, as if no config file or attributes existed.
+    fn default() -> Self {
+        SimplifyRules::new(&SimplifyConfig::default(), BTreeSet::new())
+    }
+}
+
+impl SimplifyRules {
+    pub fn new(config: &SimplifyConfig, non_code: BTreeSet<String>) -> Self {
+        SimplifyRules {
+            include: compile_all(&config.include),
+            exclude: compile_all(&config.exclude),
+            non_code,
+            min_changed_lines: config.min_changed_lines,
+            // A limit of 0 would list nothing but "... and N more".
+            max_listed_files: config.max_listed_files.max(1),
+        }
+    }
+
+    /// Resolves `config` for `paths`, reading their attributes from the
+    /// repository — one process, and none when `paths` is empty.
+    pub fn resolve(
+        repo: &dyn RepoReader,
+        config: &SimplifyConfig,
+        paths: &BTreeSet<String>,
+    ) -> Self {
+        SimplifyRules::new(config, repo.non_code_attributed(paths))
+    }
+
+    /// Most specific rule first: an include glob, then an exclude glob, then
+    /// `.gitattributes`, then the built-in extension lists.
+    pub fn is_docs_or_config(&self, path: &str) -> bool {
+        if self.include.iter().any(|g| g.is_match(path)) {
+            return false;
+        }
+        self.exclude.iter().any(|g| g.is_match(path))
+            || self.non_code.contains(path)
+            || is_docs_or_config(path)
+    }
+}
+
+/// True when a pre-dirty path's content no longer matches the hash recorded at
+/// session start. A path either side failed to hash stays unattributed.
+fn rewritten_since_baseline(baseline: &Baseline, input: &SimplifyInput, path: &str) -> bool {
+    match (baseline.dirty_blobs.get(path), input.dirty_blobs.get(path)) {
+        (Some(before), Some(now)) => before != now,
+        _ => false,
+    }
+}
+
 /// The session's contribution: files dirty now that were not dirty at session
-/// start, plus files the session committed.
-fn session_changes(baseline: &Baseline, input: &SimplifyInput) -> BTreeSet<String> {
+/// start or whose content changed since, plus files the session committed.
+/// Shared with `report`, so the banner and the report never disagree about
+/// what the session touched.
+pub(crate) fn session_changes(baseline: &Baseline, input: &SimplifyInput) -> BTreeSet<String> {
     input
         .dirty
-        .difference(&baseline.dirty)
+        .iter()
+        .filter(|p| !baseline.dirty.contains(*p) || rewritten_since_baseline(baseline, input, p))
         .chain(input.committed.iter())
         .cloned()
         .collect()
 }
 
+/// Pre-dirty paths still dirty now that the baseline hashed — the set worth
+/// re-hashing. Anything else is decided without reading file content.
+fn rehash_candidates(baseline: &Baseline, dirty: &BTreeSet<String>) -> BTreeSet<String> {
+    dirty
+        .intersection(&baseline.dirty)
+        .filter(|p| baseline.dirty_blobs.contains_key(*p))
+        .cloned()
+        .collect()
+}
+
 /// Pure decision: same input, same answer. Borrows its input so the baseline's
 /// path set is never copied to be read.
-pub fn decide(input: &SimplifyInput) -> SimplifyDecision {
+pub fn decide(input: &SimplifyInput, rules: &SimplifyRules) -> SimplifyDecision {
     let Some(baseline) = input.baseline.as_ref() else {
         return SimplifyDecision::Silent(SilentReason::NoBaseline);
     };
@@ -103,24 +201,50 @@ pub fn decide(input: &SimplifyInput) -> SimplifyDecision {
         return SimplifyDecision::Silent(SilentReason::AlreadyNotified);
     }
 
-    let changes = session_changes(baseline, input);
+    decide_changes(session_changes(baseline, input), rules, &input.line_stats)
+}
+
+/// The rule once attribution is settled: speak only when the session changed
+/// something that is not docs or config, by at least the configured number of
+/// lines. Split from `decide` so the simplify advisor applies the same rule to
+/// changes the advisor run already gathered.
+pub fn decide_changes(
+    changes: BTreeSet<String>,
+    rules: &SimplifyRules,
+    line_stats: &BTreeMap<String, Option<LineStat>>,
+) -> SimplifyDecision {
     if changes.is_empty() {
         return SimplifyDecision::Silent(SilentReason::NoSessionChanges);
     }
-    if changes.iter().all(|p| is_docs_or_config(p)) {
+    if changes.iter().all(|p| rules.is_docs_or_config(p)) {
         return SimplifyDecision::Silent(SilentReason::DocsOnly);
     }
+    if rules.min_changed_lines > 0 {
+        // Binary files and paths without counts add nothing.
+        let changed: u64 = changes
+            .iter()
+            .filter(|p| !rules.is_docs_or_config(p))
+            .filter_map(|p| line_stats.get(p).copied().flatten())
+            .map(|s| s.added + s.removed)
+            .sum();
+        if changed < rules.min_changed_lines {
+            return SimplifyDecision::Silent(SilentReason::BelowThreshold);
+        }
+    }
 
     SimplifyDecision::Notify {
         total: changes.len(),
-        files: changes.into_iter().take(MAX_LISTED_FILES).collect(),
+        files: changes.into_iter().take(rules.max_listed_files).collect(),
     }
 }
 
+/// Banner divider shared by every Stop-time advisor, so their output reads as
+/// one family.
+pub const DIVIDER: &str = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━";
+
 /// Renders the suggestion banner. Divider and title are unchanged from the
 /// shell hook; the count sentence now states what is actually counted.
 pub fn render_banner(files: &[String], total: usize) -> String {
-    const DIVIDER: &str = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━";
     let mut listed: Vec<String> = files.iter().map(|f| format!("    {f}")).collect();
     if total > files.len() {
         listed.push(format!("    ... 외 {}개", total - files.len()));
@@ -136,10 +260,62 @@ pub fn render_banner(files: &[String], total: usize) -> String {
     )
 }
 
+/// Reads everything attribution needs for a loaded baseline.
+pub(crate) fn gather_input(deps: &SessionDeps, baseline: Baseline) -> SimplifyInput {
+    let committed = baseline
+        .head
+        .as_deref()
+        .map(|head| deps.repo.files_changed_since(head))
+        .unwrap_or_default();
+    let dirty = deps.repo.dirty_files();
+    // `blob_hashes` spawns nothing for an empty set, so a session that started
+    // on a clean tree pays no extra process here.
+    let dirty_blobs = deps.repo.blob_hashes(&rehash_candidates(&baseline, &dirty));
+    SimplifyInput {
+        baseline: Some(baseline),
+        dirty,
+        committed,
+        dirty_blobs,
+        line_stats: BTreeMap::new(),
+    }
+}
+
+/// Line counts since the baseline `HEAD`, with each untracked file's lines as
+/// additions — untracked files are part of no diff. Empty for a session that
+/// began in an empty repository.
+///
+/// A file among `changes` that was already dirty at session start is counted
+/// from the blob the baseline recorded instead, so edits made before the
+/// session are not reported as its work.
+pub(crate) fn session_line_stats(
+    deps: &SessionDeps,
+    baseline: &Baseline,
+    changes: &BTreeSet<String>,
+) -> BTreeMap<String, Option<LineStat>> {
+    let mut stats = baseline
+        .head
+        .as_deref()
+        .map(|head| deps.repo.line_stats_since(head))
+        .unwrap_or_default();
+    for (path, stat) in deps.repo.untracked_files() {
+        stats.entry(path).or_insert(stat);
+    }
+    let recorded: BTreeMap<String, String> = baseline
+        .dirty_blobs
+        .iter()
+        .filter(|(path, _)| changes.contains(*path))
+        .filter_map(|(path, blob)| Some((path.clone(), blob.clone()?)))
+        .collect();
+    if !recorded.is_empty() {
+        stats.extend(deps.repo.line_stats_from_blobs(&recorded));
+    }
+    stats
+}
+
 /// Gathers the decision inputs, decides, and records the session as notified
 /// so the banner appears once. Returns the decision; printing is the CLI
 /// edge's job (one place, so #725 has a single call site to change).
-pub fn run(deps: &SessionDeps, session_id: &str) -> SimplifyDecision {
+pub fn run(deps: &SessionDeps, config: &SimplifyConfig, session_id: &str) -> SimplifyDecision {
     if !is_valid_session_id(session_id) {
         return SimplifyDecision::Silent(SilentReason::NoSessionId);
     }
@@ -156,17 +332,20 @@ pub fn run(deps: &SessionDeps, session_id: &str) -> SimplifyDecision {
         return SimplifyDecision::Silent(SilentReason::AlreadyNotified);
     }
 
-    let committed = baseline
-        .head
-        .as_deref()
-        .map(|head| deps.repo.files_changed_since(head))
+    let mut input = gather_input(deps, baseline);
+    let changes = input
+        .baseline
+        .as_ref()
+        .map(|b| session_changes(b, &input))
         .unwrap_or_default();
-    let input = SimplifyInput {
-        baseline: Some(baseline),
-        dirty: deps.repo.dirty_files(),
-        committed,
-    };
-    let decision = decide(&input);
+    let rules = SimplifyRules::resolve(deps.repo, config, &changes);
+    // The diff is only worth reading when a threshold will consult it.
+    if rules.min_changed_lines > 0 && !changes.is_empty() {
+        if let Some(baseline) = &input.baseline {
+            input.line_stats = session_line_stats(deps, baseline, &changes);
+        }
+    }
+    let decision = decide(&input, &rules);
 
     if matches!(decision, SimplifyDecision::Notify { .. }) {
         // `decide` only borrowed the input, so the baseline moves back out
@@ -177,5 +356,20 @@ pub fn run(deps: &SessionDeps, session_id: &str) -> SimplifyDecision {
             let _ = deps.store.save(session_id, &notified);
         }
     }
+    trace::info(
+        "simplify_decision",
+        serde_json::json!({ "decision": format!("{decision:?}") }),
+    );
     decision
 }
+
+/// Library entry for `simplify-check`: loads the project's
+/// `.claude/atelier.json` and runs the check against the production store and
+/// the project's repository. Like the hook, a `Notify` marks the session, so
+/// the next call answers `AlreadyNotified`.
+pub fn evaluate(project_dir: &str, session_id: &str) -> SimplifyDecision {
+    let config = ProjectConfig::load(project_dir);
+    with_project_deps(project_dir, |deps| {
+        run(deps, &config.session.simplify, session_id)
+    })
+}
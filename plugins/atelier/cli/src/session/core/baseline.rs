@@ -2,12 +2,15 @@
 //! Claude Code session starts, so Stop-time hooks can tell *this session's*
 //! changes apart from work that was already sitting in the tree.
 //!
-//! The trait is what the commands depend on (DIP); `FsBaselineStore` is the
-//! only production implementation. Keying by `session_id` is what makes
+//! The trait is what the commands depend on (DIP). Production uses
+//! `SqliteBaselineStore` (see `sqlite_store`); `FsBaselineStore` is its
+//! fallback when the database cannot be opened. Keying by `session_id` is what makes
 //! parallel sessions safe: two sessions never touch the same file.
 
+use crate::session::core::sqlite_store::SqliteBaselineStore;
+use crate::shared::state;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
@@ -29,18 +32,53 @@ pub struct Baseline {
     /// work that must not be attributed to this session.
     #[serde(default)]
     pub dirty: BTreeSet<String>,
+    /// Blob hash of each `dirty` path at session start, so a pre-dirty file
+    /// the session rewrote can still be told apart from one it left alone.
+    /// `None` records a path with no file content (deleted, or an untracked
+    /// directory). A dirty path missing from the map — a baseline written
+    /// before hashes were recorded, or a failed hash — keeps the old rule and
+    /// is never attributed to the session.
+    #[serde(default)]
+    pub dirty_blobs: BTreeMap<String, Option<String>>,
     /// Whether this session already got the `/simplify` suggestion. Stop fires
     /// on every turn; the banner is worth showing once.
     #[serde(default)]
     pub notified: bool,
+    /// Ids of the other Stop-time advisors that already spoke this session.
+    /// `/simplify` keeps its own `notified` flag so baselines written before
+    /// advisors existed keep their state.
+    #[serde(default)]
+    pub advised: BTreeSet<String>,
 }
 
+/// Advisor id whose once-per-session state is the legacy `notified` flag.
+pub const SIMPLIFY_ADVISOR: &str = "simplify";
+
 impl Baseline {
     /// Records that the session has been notified. A method rather than a raw
     /// field write so the transition stays one place to extend.
     pub fn mark_notified(&mut self) {
         self.notified = true;
     }
+
+    /// Whether the advisor `id` already spoke this session.
+    pub fn is_advised(&self, id: &str) -> bool {
+        if id == SIMPLIFY_ADVISOR {
+            self.notified
+        } else {
+            self.advised.contains(id)
+        }
+    }
+
+    /// Records that the advisor `id` spoke, so it stays silent for the rest of
+    /// the session.
+    pub fn mark_advised(&mut self, id: &str) {
+        if id == SIMPLIFY_ADVISOR {
+            self.mark_notified();
+        } else {
+            self.advised.insert(id.to_string());
+        }
+    }
 }
 
 /// Accepts only `[A-Za-z0-9_-]{8,}`. This is a path-traversal guard first and a
@@ -74,6 +112,29 @@ pub trait BaselineStore {
     }
 }
 
+/// One file in the store's directory, as `state info` and `state gc` see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateFile {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Time since the last write; `None` when the mtime is unreadable or in
+    /// the future.
+    pub age: Option<Duration>,
+    /// Older than the store's TTL — the next write (or `state gc`) deletes it.
+    pub expired: bool,
+}
+
+/// The production store, tagged with `project`: SQLite — or, when the
+/// database cannot be opened (read-only home, a newer schema), the JSON-file
+/// store, so the hooks keep working either way. Files written by the fallback
+/// are imported the next time the database opens.
+pub fn create_baseline_store(project: &str) -> Box<dyn BaselineStore> {
+    match SqliteBaselineStore::open_default() {
+        Ok(store) => Box::new(store.with_project(project)),
+        Err(_) => Box::new(FsBaselineStore::open_default()),
+    }
+}
+
 /// Baseline store backed by one JSON file per session under a directory.
 pub struct FsBaselineStore {
     dir: PathBuf,
@@ -92,41 +153,71 @@ impl FsBaselineStore {
         }
     }
 
-    /// Final path for a session, or `None` when the id is rejected.
-    fn path_for(&self, session_id: &str) -> Option<PathBuf> {
-        is_valid_session_id(session_id).then(|| self.dir.join(format!("{session_id}.json")))
+    /// The per-user store every command shares (see `shared::state`), after
+    /// moving over any baselines an earlier version left under `$TMPDIR`.
+    pub fn open_default() -> Self {
+        let dir = state::sessions_dir();
+        state::migrate_legacy_sessions(&dir);
+        FsBaselineStore::new(dir, DEFAULT_TTL)
     }
 
-    /// Deletes baselines (and orphaned temp files) older than the TTL. Called
-    /// on write, so the directory is bounded without a background sweeper.
-    /// Every failure is ignored: pruning is housekeeping, never a hard error.
-    fn prune(&self) {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Baselines and orphaned temp files in the directory, sorted by path.
+    /// Anything else found there is not the store's and is never listed.
+    pub fn files(&self) -> Vec<StateFile> {
         let Ok(entries) = std::fs::read_dir(&self.dir) else {
-            return;
+            return Vec::new();
         };
-        for entry in entries.flatten() {
-            let path = entry.path();
-            let is_state_file = matches!(
-                path.extension().and_then(|e| e.to_str()),
-                Some("json") | Some("tmp")
-            );
-            if !is_state_file {
-                continue;
-            }
-            if Self::is_expired(&path, self.ttl) {
-                let _ = std::fs::remove_file(&path);
-            }
-        }
+        let mut files: Vec<StateFile> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("json") | Some("tmp")
+                )
+            })
+            .map(|path| {
+                let meta = std::fs::metadata(&path).ok();
+                let age = meta
+                    .as_ref()
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.elapsed().ok());
+                StateFile {
+                    bytes: meta.map(|m| m.len()).unwrap_or(0),
+                    expired: age.is_some_and(|age| age > self.ttl),
+                    age,
+                    path,
+                }
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
     }
 
-    /// True when the file's mtime is further in the past than `ttl`. A file
-    /// whose mtime is unreadable or in the future is kept.
-    fn is_expired(path: &Path, ttl: Duration) -> bool {
-        std::fs::metadata(path)
-            .and_then(|m| m.modified())
-            .ok()
-            .and_then(|t| t.elapsed().ok())
-            .is_some_and(|age| age > ttl)
+    /// Final path for a session, or `None` when the id is rejected.
+    fn path_for(&self, session_id: &str) -> Option<PathBuf> {
+        is_valid_session_id(session_id).then(|| self.dir.join(format!("{session_id}.json")))
+    }
+
+    /// Deletes baselines (and orphaned temp files) older than the TTL and
+    /// returns the paths it removed. Called on write, so the directory is
+    /// bounded without a background sweeper; `state gc` runs it on demand.
+    /// Every failure is ignored: pruning is housekeeping, never a hard error.
+    /// A file whose mtime is unreadable or in the future is kept.
+    pub fn prune(&self) -> Vec<PathBuf> {
+        self.files()
+            .into_iter()
+            .filter(|f| f.expired && std::fs::remove_file(&f.path).is_ok())
+            .map(|f| f.path)
+            .collect()
     }
 }
 
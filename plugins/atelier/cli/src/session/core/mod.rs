@@ -1,2 +1,4 @@
 pub mod baseline;
 pub mod repo;
+pub mod sqlite_store;
+pub mod transcript;
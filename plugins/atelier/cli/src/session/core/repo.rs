@@ -13,7 +13,25 @@
 
 use crate::git::core::git::{create_git_service, GitService, RealGitService};
 use crate::shared::shell::exec;
-use std::collections::BTreeSet;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// One commit as the session report lists it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitSummary {
+    pub sha: String,
+    pub subject: String,
+}
+
+/// Lines added and removed in one file. A path whose stat is `None` holds
+/// binary content, for which git reports no line counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LineStat {
+    pub added: u64,
+    pub removed: u64,
+}
 
 pub trait RepoReader {
     fn is_inside_work_tree(&self) -> bool;
@@ -24,6 +42,37 @@ pub trait RepoReader {
     /// Paths changed by commits between `base_head` and `HEAD`. Empty when the
     /// base commit no longer resolves (rebase, amend, dropped branch).
     fn files_changed_since(&self, base_head: &str) -> BTreeSet<String>;
+    /// Blob hash of each path's current content, keyed like `dirty_files`
+    /// (relative to the repository root). `None` for a path with no file
+    /// content — deleted, or an untracked directory. Empty when hashing fails,
+    /// so a caller can never mistake a failed read for changed content. The
+    /// blobs are written to the object store, so `line_stats_from_blobs` can
+    /// diff against them later.
+    fn blob_hashes(&self, paths: &BTreeSet<String>) -> BTreeMap<String, Option<String>>;
+    /// Commits reachable from `HEAD` but not from `base_head`, oldest first.
+    /// Empty when the base commit no longer resolves.
+    fn commits_since(&self, base_head: &str) -> Vec<CommitSummary>;
+    /// Per-file line counts between `base_head` and the working tree, so
+    /// committed and uncommitted work are both counted. Untracked files are
+    /// not part of any diff — see `untracked_files`.
+    fn line_stats_since(&self, base_head: &str) -> BTreeMap<String, Option<LineStat>>;
+    /// Per-file line counts from each path's recorded blob (`blob_hashes`) to
+    /// its working-tree content — a deleted file counts every line removed.
+    /// Paths whose blob is gone from the object store are left out.
+    fn line_stats_from_blobs(
+        &self,
+        blobs: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, Option<LineStat>>;
+    /// Untracked, non-ignored files (individually, never collapsed to their
+    /// directory), each with its line count as additions.
+    fn untracked_files(&self) -> BTreeMap<String, Option<LineStat>>;
+    /// The subset of `paths` that `.gitattributes` marks `linguist-generated`
+    /// or `linguist-documentation` — content no reviewer simplifies. Empty
+    /// when the attributes cannot be read.
+    fn non_code_attributed(&self, paths: &BTreeSet<String>) -> BTreeSet<String>;
+    /// Repository root — the directory every path above is relative to.
+    /// Transcript paths are absolute, so matching them needs this anchor.
+    fn root(&self) -> Option<PathBuf>;
 }
 
 /// Real reader bound to a project directory.
@@ -33,6 +82,10 @@ pub struct GitRepoReader {
     /// subsystems cannot disagree about whether a directory is inside a repo —
     /// the guard and the session hooks must draw that line the same way.
     git: RealGitService,
+    /// Repository root, resolved on first use. Status and diff paths are
+    /// relative to it, which is not `project_dir` when the project is a
+    /// subdirectory of the repo.
+    toplevel: OnceLock<Option<PathBuf>>,
 }
 
 pub fn create_repo_reader(project_dir: impl Into<String>) -> GitRepoReader {
@@ -40,6 +93,7 @@ pub fn create_repo_reader(project_dir: impl Into<String>) -> GitRepoReader {
     GitRepoReader {
         git: create_git_service(Some(project_dir.clone())),
         project_dir,
+        toplevel: OnceLock::new(),
     }
 }
 
@@ -51,6 +105,16 @@ impl GitRepoReader {
         let result = exec(&full, None);
         (result.stdout, result.exit_code)
     }
+
+    fn toplevel(&self) -> Option<&Path> {
+        self.toplevel
+            .get_or_init(|| {
+                let (top, exit) = self.git(&["rev-parse", "--show-toplevel"]);
+                let top = top.trim();
+                (exit == 0 && !top.is_empty()).then(|| PathBuf::from(top))
+            })
+            .as_deref()
+    }
 }
 
 /// Parses NUL-separated `git status --porcelain -z` output into paths.
@@ -81,6 +145,96 @@ fn parse_paths_z(raw: &str) -> BTreeSet<String> {
         .collect()
 }
 
+/// Parses `git log -z --format=%H%x09%s` output into commits.
+fn parse_log_z(raw: &str) -> Vec<CommitSummary> {
+    raw.split('\0')
+        .map(|record| record.trim_start_matches('\n'))
+        .filter_map(|record| {
+            let (sha, subject) = record.split_once('\t')?;
+            Some(CommitSummary {
+                sha: sha.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parses `git diff --numstat -z` output. Each record is
+/// `<added>\t<removed>\t<path>`; a rename leaves the path empty and carries
+/// the old and new paths as the next two fields, of which the new one is kept.
+/// Binary files report `-` for both counts and map to `None`.
+fn parse_numstat_z(raw: &str) -> BTreeMap<String, Option<LineStat>> {
+    let mut stats = BTreeMap::new();
+    let mut fields = raw.split('\0');
+    while let Some(record) = fields.next() {
+        let mut parts = record.trim_start_matches('\n').splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let path = if path.is_empty() {
+            let _ = fields.next();
+            match fields.next() {
+                Some(new_path) => new_path,
+                None => continue,
+            }
+        } else {
+            path
+        };
+        let stat = added
+            .parse()
+            .ok()
+            .zip(removed.parse().ok())
+            .map(|(added, removed)| LineStat { added, removed });
+        stats.insert(path.to_string(), stat);
+    }
+    stats
+}
+
+/// Line count of a file as pure additions; `None` for binary content (a NUL
+/// byte, the same heuristic git uses) or an unreadable file.
+fn count_lines(path: &Path) -> Option<LineStat> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    let newlines = bytes.iter().filter(|b| **b == b'\n').count() as u64;
+    let unterminated = u64::from(bytes.last().is_some_and(|b| *b != b'\n'));
+    Some(LineStat {
+        added: newlines + unterminated,
+        removed: 0,
+    })
+}
+
+/// Attributes that mark a path as something other than hand-written code.
+const NON_CODE_ATTRIBUTES: &[&str] = &["linguist-generated", "linguist-documentation"];
+
+/// Parses `git check-attr -z` output (`path NUL attribute NUL value NUL`
+/// triples) into the paths with any of `NON_CODE_ATTRIBUTES` set. `unset`,
+/// `false` and `unspecified` all leave a path as code.
+fn parse_check_attr_z(raw: &str) -> BTreeSet<String> {
+    let fields: Vec<&str> = raw.split('\0').collect();
+    fields
+        .chunks_exact(3)
+        .filter(|t| NON_CODE_ATTRIBUTES.contains(&t[1]) && matches!(t[2], "set" | "true"))
+        .map(|t| t[0].to_string())
+        .collect()
+}
+
+/// Pairs `git hash-object` output (one hash per line, in argument order) with
+/// the paths that were hashed. `None` when the line count does not match —
+/// a misaligned pairing would attribute one file's hash to another.
+fn pair_hashes<'a>(files: &[&'a String], stdout: &str) -> Option<BTreeMap<&'a String, String>> {
+    let hashes: Vec<&str> = stdout.lines().map(str::trim).collect();
+    (hashes.len() == files.len()).then(|| {
+        files
+            .iter()
+            .copied()
+            .zip(hashes.into_iter().map(str::to_string))
+            .collect()
+    })
+}
+
 impl RepoReader for GitRepoReader {
     fn is_inside_work_tree(&self) -> bool {
         GitService::is_inside_work_tree(&self.git)
@@ -108,6 +262,136 @@ impl RepoReader for GitRepoReader {
         }
         parse_paths_z(&stdout)
     }
+
+    fn blob_hashes(&self, paths: &BTreeSet<String>) -> BTreeMap<String, Option<String>> {
+        if paths.is_empty() {
+            return BTreeMap::new();
+        }
+        let Some(root) = self.toplevel() else {
+            return BTreeMap::new();
+        };
+        let files: Vec<&String> = paths.iter().filter(|p| root.join(p).is_file()).collect();
+
+        let mut hashes = BTreeMap::new();
+        if !files.is_empty() {
+            let absolute: Vec<String> = files
+                .iter()
+                .map(|p| root.join(p).to_string_lossy().to_string())
+                .collect();
+            let mut args = vec!["hash-object", "-w", "--"];
+            args.extend(absolute.iter().map(String::as_str));
+            let (stdout, exit) = self.git(&args);
+            let paired = match pair_hashes(&files, &stdout) {
+                Some(paired) if exit == 0 => paired,
+                _ => return BTreeMap::new(),
+            };
+            hashes.extend(paired.into_iter().map(|(p, h)| (p.clone(), Some(h))));
+        }
+        for path in paths {
+            hashes.entry(path.clone()).or_insert(None);
+        }
+        hashes
+    }
+
+    fn commits_since(&self, base_head: &str) -> Vec<CommitSummary> {
+        let range = format!("{base_head}..HEAD");
+        let (stdout, exit) = self.git(&["log", "-z", "--reverse", "--format=%H%x09%s", &range]);
+        if exit != 0 {
+            return Vec::new();
+        }
+        parse_log_z(&stdout)
+    }
+
+    fn line_stats_since(&self, base_head: &str) -> BTreeMap<String, Option<LineStat>> {
+        let (stdout, exit) = self.git(&["diff", "--numstat", "-z", base_head, "--"]);
+        if exit != 0 {
+            return BTreeMap::new();
+        }
+        parse_numstat_z(&stdout)
+    }
+
+    fn line_stats_from_blobs(
+        &self,
+        blobs: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, Option<LineStat>> {
+        let paths: BTreeSet<String> = blobs.keys().cloned().collect();
+        let now = self.blob_hashes(&paths);
+        let mut stats = BTreeMap::new();
+        for (path, before) in blobs {
+            let after = match now.get(path) {
+                Some(Some(hash)) => hash.clone(),
+                // Deleted since: diff against the empty blob, which
+                // `/dev/null` hashes to.
+                Some(None) => match self.git(&["hash-object", "-w", "/dev/null"]) {
+                    (hash, 0) => hash,
+                    _ => continue,
+                },
+                None => continue,
+            };
+            let (stdout, exit) = self.git(&["diff", "--numstat", "-z", before, &after]);
+            if exit != 0 {
+                continue;
+            }
+            // Identical blobs print nothing; otherwise one record, keyed by
+            // the new blob where a path diff would name the file.
+            let stat = match parse_numstat_z(&stdout).into_values().next() {
+                Some(stat) => stat,
+                None => Some(LineStat::default()),
+            };
+            stats.insert(path.clone(), stat);
+        }
+        stats
+    }
+
+    fn untracked_files(&self) -> BTreeMap<String, Option<LineStat>> {
+        let Some(root) = self.toplevel() else {
+            return BTreeMap::new();
+        };
+        // `--full-name` keeps paths root-relative like every other read here;
+        // ls-files otherwise reports them relative to `project_dir`.
+        let (stdout, exit) = self.git(&[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--full-name",
+            "-z",
+        ]);
+        if exit != 0 {
+            return BTreeMap::new();
+        }
+        parse_paths_z(&stdout)
+            .into_iter()
+            .map(|path| {
+                let stat = count_lines(&root.join(&path));
+                (path, stat)
+            })
+            .collect()
+    }
+
+    fn non_code_attributed(&self, paths: &BTreeSet<String>) -> BTreeSet<String> {
+        if paths.is_empty() {
+            return BTreeSet::new();
+        }
+        let Some(root) = self.toplevel() else {
+            return BTreeSet::new();
+        };
+        // Run from the root: check-attr resolves paths against its cwd, and
+        // the root-relative paths then come back exactly as they were given.
+        let root = root.to_string_lossy();
+        let mut args = vec!["git", "-C", root.as_ref(), "check-attr", "-z"];
+        args.extend_from_slice(NON_CODE_ATTRIBUTES);
+        args.push("--");
+        args.extend(paths.iter().map(String::as_str));
+        let result = exec(&args, None);
+        if result.exit_code != 0 {
+            return BTreeSet::new();
+        }
+        parse_check_attr_z(&result.stdout)
+    }
+
+    fn root(&self) -> Option<PathBuf> {
+        self.toplevel().map(Path::to_path_buf)
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +412,69 @@ mod tests {
     fn parses_empty_status_as_no_paths() {
         assert!(parse_status_z("").is_empty());
     }
+
+    #[test]
+    fn parses_numstat_including_renames_and_binaries() {
+        let raw = "3\t1\tsrc/lib.rs\0-\t-\tlogo.png\x002\t0\t\0src/old.rs\0src/new.rs\0";
+        let stats = parse_numstat_z(raw);
+        assert_eq!(
+            stats["src/lib.rs"],
+            Some(LineStat {
+                added: 3,
+                removed: 1
+            })
+        );
+        assert_eq!(stats["logo.png"], None);
+        assert_eq!(
+            stats["src/new.rs"],
+            Some(LineStat {
+                added: 2,
+                removed: 0
+            })
+        );
+        assert!(!stats.contains_key("src/old.rs"));
+    }
+
+    #[test]
+    fn parses_log_records() {
+        let raw = "aaa\tfirst commit\0bbb\tsecond\tsubject\0";
+        assert_eq!(
+            parse_log_z(raw),
+            vec![
+                CommitSummary {
+                    sha: "aaa".to_string(),
+                    subject: "first commit".to_string()
+                },
+                CommitSummary {
+                    sha: "bbb".to_string(),
+                    subject: "second\tsubject".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_check_attr_keeping_only_set_attributes() {
+        let raw = "gen/a.rs\0linguist-generated\0set\0gen/a.rs\0linguist-documentation\0unspecified\0\
+                   docs/x.md\0linguist-generated\0unspecified\0docs/x.md\0linguist-documentation\0true\0\
+                   src/lib.rs\0linguist-generated\0false\0src/lib.rs\0linguist-documentation\0unset\0";
+        assert_eq!(
+            parse_check_attr_z(raw).into_iter().collect::<Vec<_>>(),
+            vec!["docs/x.md", "gen/a.rs"]
+        );
+    }
+
+    #[test]
+    fn pairs_hashes_in_argument_order() {
+        let (a, b) = ("a.rs".to_string(), "b.rs".to_string());
+        let paired = pair_hashes(&[&a, &b], "1111\n2222").unwrap();
+        assert_eq!(paired[&a], "1111");
+        assert_eq!(paired[&b], "2222");
+    }
+
+    #[test]
+    fn refuses_misaligned_hash_output() {
+        let a = "a.rs".to_string();
+        assert!(pair_hashes(&[&a], "1111\n2222").is_none());
+    }
 }
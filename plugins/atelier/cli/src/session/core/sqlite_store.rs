@@ -0,0 +1,276 @@
+//! SQLite-backed `BaselineStore` — the production store. Baselines live in
+//! the shared atelier database (`shared::db`) as one row per session, so a
+//! write is a transaction rather than a temp-file rename, the TTL sweep is one
+//! indexed `DELETE`, and lookups by project need no directory scan.
+//!
+//! Rows keep the same JSON document `FsBaselineStore` writes, so a baseline
+//! reads identically from either store and old files import unchanged.
+
+use crate::session::core::baseline::{is_valid_session_id, Baseline, BaselineStore, DEFAULT_TTL};
+use crate::shared::{db, state};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One stored baseline with its bookkeeping, as `state info` and `state
+/// export` list it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredBaseline {
+    pub session_id: String,
+    /// Project the session was recorded for; `None` for imported files.
+    pub project: Option<String>,
+    /// Unix milliseconds.
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bytes: u64,
+    /// Older than the store's TTL — the next write (or `state gc`) deletes it.
+    pub expired: bool,
+    pub baseline: Baseline,
+}
+
+pub struct SqliteBaselineStore {
+    conn: Connection,
+    ttl: Duration,
+    /// Recorded on every row this store writes.
+    project: Option<String>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn mtime_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    modified
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as i64)
+}
+
+impl SqliteBaselineStore {
+    pub fn new(conn: Connection, ttl: Duration) -> Self {
+        SqliteBaselineStore {
+            conn,
+            ttl,
+            project: None,
+        }
+    }
+
+    pub fn open(path: &Path, ttl: Duration) -> Result<Self, String> {
+        Ok(SqliteBaselineStore::new(db::open(path)?, ttl))
+    }
+
+    /// The per-user store every command shares, after importing any JSON
+    /// baselines an earlier version left on disk (`$TMPDIR/atelier-sessions`
+    /// or the state dir's `sessions/`).
+    pub fn open_default() -> Result<Self, String> {
+        let store = SqliteBaselineStore::open(&db::db_path(), DEFAULT_TTL)?;
+        store.import_dir(&state::legacy_sessions_dir());
+        store.import_dir(&state::sessions_dir());
+        Ok(store)
+    }
+
+    /// Tags every row this store writes with `project`.
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Path of the database file; `None` for an in-memory database.
+    pub fn path(&self) -> Option<String> {
+        self.conn
+            .path()
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Rows last written before this are expired.
+    fn cutoff(&self) -> i64 {
+        now_ms().saturating_sub(self.ttl.as_millis() as i64)
+    }
+
+    /// Moves `<id>.json` baselines from `dir` into the database and removes
+    /// the files — and the directory, once nothing else is in it. Orphaned
+    /// temp files from interrupted writes go too. A row already present wins.
+    /// Returns how many baselines were imported; every failure is skipped.
+    pub fn import_dir(&self, dir: &Path) -> usize {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return 0;
+        };
+        let mut imported = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("tmp") => {
+                    let _ = std::fs::remove_file(&path);
+                }
+                Some("json") => {
+                    let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                        continue;
+                    };
+                    let parsed = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|raw| serde_json::from_str::<Baseline>(&raw).ok());
+                    let Some(baseline) = parsed.filter(|_| is_valid_session_id(session_id)) else {
+                        continue;
+                    };
+                    // The file's mtime is its last write, so TTL ages carry over.
+                    let written = mtime_ms(&path).unwrap_or_else(now_ms);
+                    if let Ok(inserted) = self.insert(session_id, None, &baseline, written, false) {
+                        imported += usize::from(inserted);
+                        let _ = std::fs::remove_file(&path);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let _ = std::fs::remove_dir(dir);
+        imported
+    }
+
+    /// Inserts a row stamped `at`; with `replace`, overwrites an existing one
+    /// (keeping its `created_at`, and its project unless this one is known).
+    fn insert(
+        &self,
+        session_id: &str,
+        project: Option<&str>,
+        baseline: &Baseline,
+        at: i64,
+        replace: bool,
+    ) -> Result<bool, String> {
+        let json = serde_json::to_string(baseline).map_err(|e| e.to_string())?;
+        let on_conflict = if replace {
+            "DO UPDATE SET baseline = excluded.baseline, updated_at = excluded.updated_at,
+                           project = COALESCE(excluded.project, project)"
+        } else {
+            "DO NOTHING"
+        };
+        let sql = format!(
+            "INSERT INTO session_baselines (session_id, project, baseline, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(session_id) {on_conflict}"
+        );
+        self.conn
+            .execute(&sql, params![session_id, project, json, at])
+            .map(|changed| changed > 0)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Writes `baseline` inside one transaction that first sweeps expired
+    /// rows — never this session's own, whatever the TTL is.
+    fn write(&self, session_id: &str, baseline: &Baseline, replace: bool) -> Result<bool, String> {
+        if !is_valid_session_id(session_id) {
+            return Err(format!("invalid session id: {session_id}"));
+        }
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| e.to_string())?;
+        tx.execute(
+            "DELETE FROM session_baselines WHERE updated_at <= ?1 AND session_id <> ?2",
+            params![self.cutoff(), session_id],
+        )
+        .map_err(|e| e.to_string())?;
+        let written = self.insert(
+            session_id,
+            self.project.as_deref(),
+            baseline,
+            now_ms(),
+            replace,
+        )?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(written)
+    }
+
+    /// Every stored baseline, oldest write first, optionally limited to one
+    /// project (an indexed lookup). Rows whose JSON no longer parses are
+    /// skipped — `load` would not return them either.
+    pub fn entries(&self, project: Option<&str>) -> Result<Vec<StoredBaseline>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT session_id, project, baseline, created_at, updated_at
+                 FROM session_baselines
+                 WHERE ?1 IS NULL OR project = ?1
+                 ORDER BY updated_at, session_id",
+            )
+            .map_err(|e| e.to_string())?;
+        let cutoff = self.cutoff();
+        let rows = stmt
+            .query_map(params![project], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(rows
+            .flatten()
+            .filter_map(|(session_id, project, json, created_at, updated_at)| {
+                let baseline = serde_json::from_str(&json).ok()?;
+                Some(StoredBaseline {
+                    session_id,
+                    project,
+                    created_at,
+                    updated_at,
+                    bytes: json.len() as u64,
+                    expired: updated_at <= cutoff,
+                    baseline,
+                })
+            })
+            .collect())
+    }
+
+    /// Deletes expired rows and returns their session ids, sorted.
+    pub fn prune(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("DELETE FROM session_baselines WHERE updated_at <= ?1 RETURNING session_id")
+            .map_err(|e| e.to_string())?;
+        let mut removed: Vec<String> = stmt
+            .query_map(params![self.cutoff()], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .collect();
+        removed.sort();
+        Ok(removed)
+    }
+}
+
+impl BaselineStore for SqliteBaselineStore {
+    fn load(&self, session_id: &str) -> Option<Baseline> {
+        if !is_valid_session_id(session_id) {
+            return None;
+        }
+        let json: String = self
+            .conn
+            .query_row(
+                "SELECT baseline FROM session_baselines WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()??;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn save(&self, session_id: &str, baseline: &Baseline) -> Result<(), String> {
+        self.write(session_id, baseline, true).map(|_| ())
+    }
+
+    /// One conditional insert, so two racing SessionStarts cannot both win.
+    fn save_if_absent(&self, session_id: &str, baseline: &Baseline) -> Result<bool, String> {
+        self.write(session_id, baseline, false)
+    }
+}
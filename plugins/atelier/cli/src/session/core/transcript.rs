@@ -0,0 +1,314 @@
+//! Claude Code session transcript reads. Every hook payload names the
+//! session's JSONL transcript (`transcript_path`); it records what the agent
+//! actually did, which `git status` can only infer after the fact.
+//!
+//! The schema mirrors what suggest-workflow's `SessionEntry` parses: one JSON
+//! object per line, assistant turns carrying `message.content[]` blocks of
+//! `type: "tool_use"` with the tool `name` and its `input`. Lines that do not
+//! parse, or that carry no tool use, are skipped — a transcript is written
+//! while the session runs, so a torn last line is normal.
+//!
+//! On top of the raw sequence, `summarize` answers what the Stop-time rules
+//! ask: which tools ran, whether tests or builds ran after the last edit, and
+//! which files the agent wrote with its edit tools — so a file git reports as
+//! changed can be told apart as edited directly or changed by a shell command.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Tools whose `file_path` the agent wrote.
+pub const EDIT_TOOLS: &[&str] = &["Write", "Edit", "MultiEdit", "NotebookEdit"];
+
+/// Bash command classes, in suggest-workflow's `Bash:<kind>` vocabulary so
+/// the two tools name the same command the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BashKind {
+    Git,
+    Test,
+    Build,
+    Lint,
+    Other,
+}
+
+impl BashKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BashKind::Git => "git",
+            BashKind::Test => "test",
+            BashKind::Build => "build",
+            BashKind::Lint => "lint",
+            BashKind::Other => "other",
+        }
+    }
+}
+
+static GIT_COMMAND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^git\s|^gh\s").unwrap());
+static TEST_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(vitest|jest|mocha|pytest|cargo\s+(test|nextest)|go\s+test|(npm|pnpm|yarn|bun)\s+(run\s+)?test|make\s+test|gradlew?\s+test|mvn\s+test)\b",
+    )
+    .unwrap()
+});
+static BUILD_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(tsc|webpack|vite\s+build|next\s+build|(npm|pnpm|yarn|bun)\s+run\s+build|cargo\s+(build|check)|go\s+build|make|cmake)\b").unwrap()
+});
+static LINT_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(eslint|prettier|biome|rubocop|flake8|ruff|clippy|golangci-lint)\b").unwrap()
+});
+
+/// Classifies a Bash command. Checked in suggest-workflow's order, so a
+/// `make test` is a test run and not a build.
+pub fn classify_bash(command: &str) -> BashKind {
+    let command = command.trim_start();
+    if GIT_COMMAND.is_match(command) {
+        BashKind::Git
+    } else if TEST_COMMAND.is_match(command) {
+        BashKind::Test
+    } else if BUILD_COMMAND.is_match(command) {
+        BashKind::Build
+    } else if LINT_COMMAND.is_match(command) {
+        BashKind::Lint
+    } else {
+        BashKind::Other
+    }
+}
+
+/// One tool invocation, reduced to the inputs the session rules read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolUse {
+    pub name: String,
+    /// `file_path` (or `notebook_path`) of file-editing tools.
+    pub file_path: Option<String>,
+    /// `command` of `Bash`.
+    pub command: Option<String>,
+}
+
+impl ToolUse {
+    /// The file an edit tool wrote; `None` for every other tool.
+    pub fn edited_file(&self) -> Option<&str> {
+        EDIT_TOOLS
+            .contains(&self.name.as_str())
+            .then_some(self.file_path.as_deref())
+            .flatten()
+    }
+
+    /// The class of a Bash command; `None` for every other tool.
+    pub fn bash_kind(&self) -> Option<BashKind> {
+        (self.name == "Bash").then(|| classify_bash(self.command.as_deref().unwrap_or_default()))
+    }
+
+    /// Name as counted in `TranscriptSummary::tool_counts` — `Bash:<kind>`
+    /// for shell commands, the tool name otherwise.
+    pub fn counted_name(&self) -> String {
+        match self.bash_kind() {
+            Some(kind) => format!("Bash:{}", kind.as_str()),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// What a transcript says the session did, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub tool_uses: Vec<ToolUse>,
+}
+
+/// The answers Stop-time rules and the session report read off a transcript.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TranscriptSummary {
+    /// Invocations per tool, Bash split as `Bash:<kind>`.
+    pub tool_counts: BTreeMap<String, usize>,
+    /// Files written by edit tools — relative to the repository root when
+    /// they lie inside it, as given otherwise.
+    pub edited_files: BTreeSet<String>,
+    /// Whether any edit happened at all; the two flags below are `false`
+    /// without one.
+    pub edited: bool,
+    pub tests_after_last_edit: bool,
+    pub build_after_last_edit: bool,
+}
+
+/// Makes an absolute transcript path relative to `root`, the form git reports
+/// paths in. Paths outside the root (or with no root known) are kept as given.
+pub fn relative_to_root(root: Option<&Path>, path: &str) -> String {
+    root.and_then(|root| Path::new(path).strip_prefix(root).ok())
+        .map(|rel| rel.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn tool_use(block: &Value) -> Option<ToolUse> {
+    if block["type"].as_str() != Some("tool_use") {
+        return None;
+    }
+    let input = &block["input"];
+    Some(ToolUse {
+        name: block["name"].as_str()?.to_string(),
+        file_path: input["file_path"]
+            .as_str()
+            .or_else(|| input["notebook_path"].as_str())
+            .map(str::to_string),
+        command: input["command"].as_str().map(str::to_string),
+    })
+}
+
+impl Transcript {
+    /// Parses JSONL transcript content. Never fails: unparseable lines are
+    /// skipped.
+    pub fn parse(raw: &str) -> Transcript {
+        let tool_uses = raw
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|entry| entry["type"].as_str() == Some("assistant"))
+            .filter_map(|entry| entry["message"]["content"].as_array().cloned())
+            .flat_map(|blocks| blocks.iter().filter_map(tool_use).collect::<Vec<_>>())
+            .collect();
+        Transcript { tool_uses }
+    }
+
+    /// Index of the last edit whose file satisfies `counts`.
+    pub fn last_edit_where(&self, counts: impl Fn(&str) -> bool) -> Option<usize> {
+        self.tool_uses
+            .iter()
+            .rposition(|t| t.edited_file().is_some_and(&counts))
+    }
+
+    /// Whether a Bash command of `kind` ran after the tool use at `index`.
+    pub fn ran_after(&self, index: usize, kind: BashKind) -> bool {
+        self.tool_uses
+            .iter()
+            .skip(index + 1)
+            .any(|t| t.bash_kind() == Some(kind))
+    }
+
+    /// Summarizes the transcript; `root` relativizes edited paths.
+    pub fn summarize(&self, root: Option<&Path>) -> TranscriptSummary {
+        let mut tool_counts = BTreeMap::new();
+        for t in &self.tool_uses {
+            *tool_counts.entry(t.counted_name()).or_insert(0) += 1;
+        }
+        let last_edit = self.last_edit_where(|_| true);
+        TranscriptSummary {
+            tool_counts,
+            edited_files: self
+                .tool_uses
+                .iter()
+                .filter_map(ToolUse::edited_file)
+                .map(|p| relative_to_root(root, p))
+                .collect(),
+            edited: last_edit.is_some(),
+            tests_after_last_edit: last_edit.is_some_and(|i| self.ran_after(i, BashKind::Test)),
+            build_after_last_edit: last_edit.is_some_and(|i| self.ran_after(i, BashKind::Build)),
+        }
+    }
+}
+
+/// Where transcript content comes from. Injected so the advisors run against
+/// in-memory transcripts in tests.
+pub trait TranscriptSource {
+    /// The parsed transcript, or `None` when there is none to read.
+    fn transcript(&self) -> Option<Transcript>;
+}
+
+/// Reads the transcript file named by the hook payload.
+pub struct FsTranscriptSource {
+    path: Option<String>,
+}
+
+pub fn create_transcript_source(path: Option<String>) -> FsTranscriptSource {
+    FsTranscriptSource {
+        path: path.filter(|p| !p.is_empty()),
+    }
+}
+
+impl TranscriptSource for FsTranscriptSource {
+    fn transcript(&self) -> Option<Transcript> {
+        let raw = std::fs::read_to_string(self.path.as_ref()?).ok()?;
+        Some(Transcript::parse(&raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bash(command: &str) -> ToolUse {
+        ToolUse {
+            name: "Bash".to_string(),
+            command: Some(command.to_string()),
+            ..ToolUse::default()
+        }
+    }
+
+    fn edit(path: &str) -> ToolUse {
+        ToolUse {
+            name: "Edit".to_string(),
+            file_path: Some(path.to_string()),
+            ..ToolUse::default()
+        }
+    }
+
+    #[test]
+    fn classifies_bash_like_suggest_workflow() {
+        assert_eq!(classify_bash("git commit -m x"), BashKind::Git);
+        assert_eq!(classify_bash("cargo test --workspace"), BashKind::Test);
+        assert_eq!(classify_bash("make test"), BashKind::Test);
+        assert_eq!(classify_bash("cargo build"), BashKind::Build);
+        assert_eq!(classify_bash("npx eslint ."), BashKind::Lint);
+        assert_eq!(classify_bash("ls -la"), BashKind::Other);
+    }
+
+    #[test]
+    fn summary_tracks_runs_after_the_last_edit() {
+        let transcript = Transcript {
+            tool_uses: vec![
+                bash("cargo test"),
+                edit("/repo/src/lib.rs"),
+                bash("cargo build"),
+                edit("/elsewhere/notes.rs"),
+                bash("cargo test"),
+            ],
+        };
+        let summary = transcript.summarize(Some(Path::new("/repo")));
+        assert!(summary.edited);
+        assert!(summary.tests_after_last_edit);
+        assert!(!summary.build_after_last_edit);
+        assert_eq!(summary.tool_counts["Bash:test"], 2);
+        assert_eq!(summary.tool_counts["Edit"], 2);
+        assert_eq!(
+            summary.edited_files.into_iter().collect::<Vec<_>>(),
+            vec!["/elsewhere/notes.rs", "src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn parses_tool_uses_in_order_and_skips_noise() {
+        let raw = [
+            r#"{"type":"user","message":{"content":"fix it"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"ok"},{"type":"tool_use","name":"Edit","input":{"file_path":"/p/src/lib.rs"}}]}}"#,
+            "{torn line",
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+        ]
+        .join("\n");
+        let transcript = Transcript::parse(&raw);
+        assert_eq!(
+            transcript.tool_uses,
+            vec![
+                ToolUse {
+                    name: "Edit".to_string(),
+                    file_path: Some("/p/src/lib.rs".to_string()),
+                    command: None,
+                },
+                ToolUse {
+                    name: "Bash".to_string(),
+                    file_path: None,
+                    command: Some("cargo test".to_string()),
+                },
+            ]
+        );
+    }
+}
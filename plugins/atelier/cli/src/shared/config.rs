@@ -0,0 +1,183 @@
+//! Per-project atelier configuration, read from `<project>/.claude/atelier.json`.
+//! One file with a section per subsystem, so a project has a single place to
+//! tune atelier rather than one file per feature.
+//!
+//! Loading is swallow-all: a missing, unreadable or malformed file yields the
+//! defaults. Most consumers are advisory hooks, and a typo in a config file
+//! must never turn one of those into a failing process; for the commit-message
+//! guard the defaults are "no policy", so a broken file blocks nothing.
+
+use crate::shared::trace;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Project-relative location of the config file.
+pub const CONFIG_REL: &str = ".claude/atelier.json";
+
+/// Banner listing limit when `session.simplify.max_listed_files` is unset.
+pub const DEFAULT_MAX_LISTED_FILES: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub dispatch: DispatchConfig,
+    #[serde(default)]
+    pub git: GitConfig,
+}
+
+/// `git` section — policy for the git guards.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct GitConfig {
+    #[serde(default)]
+    pub commit_message: CommitMessageConfig,
+}
+
+/// `git.commit_message` section — what the commit guard demands of a message
+/// given with `git commit -m` / `-F`. Every rule is off until configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CommitMessageConfig {
+    /// Require a Conventional Commits subject: `type(scope)!: description`.
+    pub conventional: bool,
+    /// Types `conventional` accepts; empty means the standard set.
+    pub types: Vec<String>,
+    /// Regex the message must match somewhere, e.g. `#\d+` or `[A-Z]+-\d+`.
+    pub issue_pattern: Option<String>,
+    /// Longest subject line allowed, in characters.
+    pub max_subject_length: Option<usize>,
+    /// Refuse WIP subjects on protected branches.
+    pub no_wip_on_protected: bool,
+    /// Refuse AI attribution trailers (`Co-Authored-By: Claude …`,
+    /// "Generated with …").
+    pub no_ai_trailers: bool,
+}
+
+/// `dispatch` section — which handlers `atelier hook-dispatch` runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DispatchConfig {
+    /// Handler id → enabled. A handler absent here is enabled.
+    #[serde(default)]
+    pub handlers: BTreeMap<String, bool>,
+}
+
+/// `session` section — the Stop-time advisors.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SessionConfig {
+    /// Advisor id → enabled. An advisor absent here is enabled.
+    #[serde(default)]
+    pub advisors: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub simplify: SimplifyConfig,
+}
+
+/// `session.simplify` section — what counts as code worth a `/simplify`
+/// review. Globs are matched against repository-root-relative paths.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SimplifyConfig {
+    /// Paths that always count as code, even if the rules below say otherwise.
+    pub include: Vec<String>,
+    /// Paths that never count as code: generated sources, snapshots, fixtures.
+    pub exclude: Vec<String>,
+    /// Stay silent until the session's code files changed at least this many
+    /// lines (added + removed). `0` disables the threshold.
+    pub min_changed_lines: u64,
+    /// How many paths the banner lists before collapsing the rest.
+    pub max_listed_files: usize,
+}
+
+impl Default for SimplifyConfig {
+    fn default() -> Self {
+        SimplifyConfig {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            min_changed_lines: 0,
+            max_listed_files: DEFAULT_MAX_LISTED_FILES,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn advisor_enabled(&self, id: &str) -> bool {
+        self.advisors.get(id).copied().unwrap_or(true)
+    }
+}
+
+impl DispatchConfig {
+    pub fn handler_enabled(&self, id: &str) -> bool {
+        self.handlers.get(id).copied().unwrap_or(true)
+    }
+}
+
+impl CommitMessageConfig {
+    /// Whether any rule is on — an empty policy skips message extraction.
+    pub fn is_enabled(&self) -> bool {
+        self.conventional
+            || self.issue_pattern.is_some()
+            || self.max_subject_length.is_some()
+            || self.no_wip_on_protected
+            || self.no_ai_trailers
+    }
+}
+
+impl ProjectConfig {
+    /// Parses a config document; malformed JSON yields the defaults.
+    pub fn parse(raw: &str) -> ProjectConfig {
+        serde_json::from_str(raw).unwrap_or_default()
+    }
+
+    /// Loads `<project_dir>/.claude/atelier.json`, defaulting on any failure.
+    /// The outcome (read, absent or malformed) goes to the structured log,
+    /// since all three look the same from the outside.
+    pub fn load(project_dir: &str) -> ProjectConfig {
+        let path = Path::new(project_dir).join(CONFIG_REL);
+        let (config, source) = match std::fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str(&raw) {
+                Ok(config) => (config, "file"),
+                Err(_) => (ProjectConfig::default(), "malformed"),
+            },
+            Err(_) => (ProjectConfig::default(), "absent"),
+        };
+        trace::info(
+            "config_load",
+            serde_json::json!({ "path": path.to_string_lossy(), "source": source }),
+        );
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absent_advisor_is_enabled() {
+        let config = ProjectConfig::parse(r#"{"session":{"advisors":{"large-diff":false}}}"#);
+        assert!(!config.session.advisor_enabled("large-diff"));
+        assert!(config.session.advisor_enabled("simplify"));
+    }
+
+    #[test]
+    fn partial_simplify_section_keeps_other_defaults() {
+        let config = ProjectConfig::parse(
+            r#"{"session":{"simplify":{"exclude":["*.snap"],"min_changed_lines":20}}}"#,
+        );
+        let simplify = &config.session.simplify;
+        assert_eq!(simplify.exclude, vec!["*.snap".to_string()]);
+        assert_eq!(simplify.min_changed_lines, 20);
+        assert_eq!(simplify.max_listed_files, DEFAULT_MAX_LISTED_FILES);
+        assert!(simplify.include.is_empty());
+    }
+
+    #[test]
+    fn malformed_config_falls_back_to_defaults() {
+        assert_eq!(ProjectConfig::parse("{not json"), ProjectConfig::default());
+        assert_eq!(
+            ProjectConfig::parse(r#"{"session":{"advisors":"yes"}}"#),
+            ProjectConfig::default()
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! atelier's embedded database — one SQLite file under the state root
+//! (`shared::state`) that every subsystem's durable state shares, so a
+//! feature that needs to remember something adds a table, not a directory of
+//! JSON files with its own locking and pruning story.
+//!
+//! The schema is versioned with `PRAGMA user_version`: `MIGRATIONS[i]` takes
+//! the database from version `i` to `i + 1`, and each runs in its own
+//! transaction. Append to the list; never edit an entry that has shipped.
+//!
+//! Several hook processes can run at once (parallel sessions, Stop racing
+//! SessionStart), so connections use WAL and wait on a busy database instead
+//! of failing.
+
+use crate::shared::state::state_root;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the database inside the state root.
+pub const DB_FILE: &str = "atelier.db";
+
+/// How long a writer waits for another process's transaction to finish.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Schema steps, oldest first.
+const MIGRATIONS: &[&str] = &[
+    // v1 — session baselines (see `session::core::sqlite_store`).
+    "CREATE TABLE session_baselines (
+         session_id TEXT PRIMARY KEY,
+         project    TEXT,
+         baseline   TEXT NOT NULL,
+         created_at INTEGER NOT NULL,
+         updated_at INTEGER NOT NULL
+     );
+     CREATE INDEX idx_session_baselines_project ON session_baselines(project);
+     CREATE INDEX idx_session_baselines_updated ON session_baselines(updated_at);",
+    // v2 — default-branch detection cache (see `git::core::branch_cache`).
+    "CREATE TABLE default_branch_cache (
+         repo        TEXT PRIMARY KEY,
+         fingerprint TEXT NOT NULL,
+         branch      TEXT NOT NULL,
+         cached_at   INTEGER NOT NULL
+     );",
+];
+
+/// Schema version this build writes.
+pub fn schema_version() -> u32 {
+    MIGRATIONS.len() as u32
+}
+
+/// Location of the shared database.
+pub fn db_path() -> PathBuf {
+    state_root().join(DB_FILE)
+}
+
+/// Opens (creating if needed) the database at `path` and brings its schema
+/// up to date.
+pub fn open(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+    }
+    let conn =
+        Connection::open(path).map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT).map_err(|e| e.to_string())?;
+    // `journal_mode` answers with a row, so it cannot go through `execute`.
+    conn.query_row("PRAGMA journal_mode=WAL", [], |_| Ok(()))
+        .map_err(|e| e.to_string())?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+/// A private in-memory database with the current schema — for tests.
+pub fn open_in_memory() -> Result<Connection, String> {
+    let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+/// Applies every migration past the database's `user_version`. Refuses a
+/// database written by a newer atelier rather than guessing at its layout.
+fn migrate(conn: &Connection) -> Result<(), String> {
+    let current: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if current > schema_version() {
+        return Err(format!(
+            "database schema v{current} is newer than this atelier (v{})",
+            schema_version()
+        ));
+    }
+    for (from, ddl) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let to = from + 1;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute_batch(ddl)
+            .and_then(|_| tx.execute_batch(&format!("PRAGMA user_version = {to}")))
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("schema migration v{from} → v{to} failed: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_version(conn: &Connection) -> u32 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn fresh_database_reaches_current_version() {
+        let conn = open_in_memory().unwrap();
+        assert_eq!(user_version(&conn), schema_version());
+    }
+
+    #[test]
+    fn reopening_is_a_no_op() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("nested/atelier.db");
+        drop(open(&path).unwrap());
+        let conn = open(&path).unwrap();
+        assert_eq!(user_version(&conn), schema_version());
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("atelier.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA user_version = 999").unwrap();
+        drop(conn);
+        assert!(open(&path).unwrap_err().contains("newer"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Path globs for project config — the subset of gitignore syntax a config
+//! author reaches for: `*` and `?` stay within one path segment, `**` crosses
+//! segments, and a pattern without `/` matches a file name at any depth.
+//!
+//! Compiled to a `regex::Regex` rather than pulling in a glob crate: the
+//! patterns are few and short, and `regex` is already a dependency.
+
+use regex::Regex;
+
+/// A compiled glob, matched against repository-root-relative paths.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    /// Compiles `pattern`; `None` for an empty pattern.
+    pub fn new(pattern: &str) -> Option<Glob> {
+        let trimmed = pattern.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        // Like gitignore: a slash anywhere but the end anchors the pattern at
+        // the root; otherwise it may match below any directory.
+        let anchored = trimmed.trim_end_matches('/').contains('/');
+        let body = trimmed.strip_prefix('/').unwrap_or(trimmed);
+        let mut re = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        re.push_str("(?:.*/)?");
+                    } else {
+                        re.push_str(".*");
+                    }
+                }
+                '*' => re.push_str("[^/]*"),
+                '?' => re.push_str("[^/]"),
+                c => re.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        // A directory pattern (`dist/`) covers everything under it.
+        if body.ends_with('/') {
+            re.push_str(".*");
+        }
+        re.push('$');
+        Regex::new(&re).ok().map(|regex| Glob {
+            pattern: trimmed.to_string(),
+            regex,
+        })
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// Compiles every pattern, dropping the ones that do not compile — config
+/// loading never fails, so a bad pattern only loses itself.
+pub fn compile_all(patterns: &[String]) -> Vec<Glob> {
+    patterns.iter().filter_map(|p| Glob::new(p)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Glob::new(pattern).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn slashless_pattern_matches_file_name_at_any_depth() {
+        assert!(matches("*.snap", "snapshots/a.snap"));
+        assert!(matches("*.snap", "a.snap"));
+        assert!(!matches("*.snap", "a.snap.rs"));
+    }
+
+    #[test]
+    fn slash_anchors_pattern_at_root() {
+        assert!(matches(".github/**", ".github/workflows/ci.yml"));
+        assert!(!matches(".github/**", "vendor/.github/ci.yml"));
+        assert!(matches("/src/*.rs", "src/lib.rs"));
+        assert!(!matches("src/*.rs", "src/nested/lib.rs"));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        assert!(matches("tests/**/fixtures/**", "tests/fixtures/a.json"));
+        assert!(matches(
+            "tests/**/fixtures/**",
+            "tests/unit/fixtures/deep/a.rs"
+        ));
+        assert!(matches("**/generated.rs", "generated.rs"));
+    }
+
+    #[test]
+    fn directory_pattern_covers_its_contents() {
+        assert!(matches("dist/", "pkg/dist/bundle.js"));
+        assert!(matches("gen/**", "gen/"));
+    }
+
+    #[test]
+    fn question_mark_and_literals() {
+        assert!(matches("v?.rs", "v1.rs"));
+        assert!(!matches("v?.rs", "v/.rs"));
+        assert!(matches("a+b.rs", "a+b.rs"));
+        assert!(Glob::new("  ").is_none());
+    }
+}
@@ -0,0 +1,96 @@
+//! Claude Code's structured hook output: the JSON document a hook may print
+//! on stdout (with exit 0) instead of signalling through exit codes.
+//!
+//! ```json
+//! {
+//!   "hookSpecificOutput": {
+//!     "hookEventName": "PreToolUse",
+//!     "permissionDecision": "deny",
+//!     "permissionDecisionReason": "…",
+//!     "additionalContext": "…"
+//!   },
+//!   "systemMessage": "…"
+//! }
+//! ```
+//!
+//! Every field is optional and absent fields are omitted, so a hook with
+//! nothing to say prints nothing at all — see `HookOutput::render`.
+
+use serde::Serialize;
+
+/// `hookSpecificOutput.permissionDecision` for PreToolUse. `Ask` puts the
+/// call in front of the user instead of refusing it outright.
+///
+/// There is deliberately no `allow`: Claude Code reads it as "skip the
+/// permission prompt", which is a grant no guard is in a position to make.
+/// A check that passes says nothing and leaves the normal flow in charge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecision {
+    Ask,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookSpecificOutput {
+    pub hook_event_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission_decision: Option<PermissionDecision>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission_decision_reason: Option<String>,
+    /// Text added to the model's context.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_context: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook_specific_output: Option<HookSpecificOutput>,
+    /// Text shown to the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_message: Option<String>,
+}
+
+impl HookOutput {
+    pub fn is_empty(&self) -> bool {
+        self.hook_specific_output.is_none() && self.system_message.is_none()
+    }
+
+    /// The stdout document, or `None` when there is nothing to say.
+    pub fn render(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        serde_json::to_string(self).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_output_renders_nothing() {
+        assert_eq!(HookOutput::default().render(), None);
+    }
+
+    #[test]
+    fn deny_uses_claude_code_field_names() {
+        let output = HookOutput {
+            hook_specific_output: Some(HookSpecificOutput {
+                hook_event_name: "PreToolUse".to_string(),
+                permission_decision: Some(PermissionDecision::Deny),
+                permission_decision_reason: Some("protected".to_string()),
+                additional_context: None,
+            }),
+            system_message: None,
+        };
+        assert_eq!(
+            output.render().unwrap(),
+            r#"{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"deny","permissionDecisionReason":"protected"}}"#
+        );
+    }
+}
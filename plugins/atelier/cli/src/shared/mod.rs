@@ -2,5 +2,11 @@
 //! owns the other — so anything they share lives here rather than one of them
 //! reaching into the other's internals.
 
+pub mod config;
+pub mod db;
+pub mod glob;
+pub mod hook_output;
 pub mod process;
 pub mod shell;
+pub mod state;
+pub mod trace;
@@ -26,3 +26,12 @@ pub fn default_project_dir(explicit: Option<String>) -> String {
             .unwrap_or_else(|_| ".".to_string())
     })
 }
+
+/// The canonical spelling of a project directory, so state keyed by project
+/// matches however a hook or a user wrote the path. Unresolvable paths are
+/// kept as given.
+pub fn canonical_project(dir: &str) -> String {
+    std::fs::canonicalize(dir)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| dir.to_string())
+}
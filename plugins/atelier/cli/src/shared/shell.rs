@@ -4,22 +4,61 @@
 //! `exec` never throws and returns trimEnd'd stdout/stderr plus the exit code;
 //! `exec_or_throw` returns the trimmed stdout or an error string on non-zero
 //! exit, embedding the same message format as the TS `execOrThrow`.
+//!
+//! A call with `ExecOptions::timeout` runs the child in its own process group
+//! and kills the whole group at the deadline, so a `gh` stuck on an auth
+//! prompt takes its helpers down with it instead of holding a hook open.
+//!
+//! Every call is recorded at `debug` in the structured log (`shared::trace`)
+//! with its duration and exit code.
 
+use crate::shared::trace::{self, Level};
 use std::collections::HashMap;
-use std::process::Command;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How often a deadline-bound call checks whether the child has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long a call waits for its output readers past its deadline or after
+/// the kill — a descendant that left the process group, or outlived an
+/// exited child, can keep the pipes open forever.
+const DRAIN_GRACE: Duration = Duration::from_millis(100);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExecResult {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
+    /// The deadline passed and the process group was killed; `exit_code` is
+    /// `-1` and the output is whatever arrived before the kill.
+    pub timed_out: bool,
 }
 
+/// The signature of [`exec`], so a service can hold it as a seam and its
+/// unit tests can stand in a fake (e.g. one that always times out).
+pub type Exec = fn(&[&str], Option<&ExecOptions>) -> ExecResult;
+
 #[derive(Debug, Clone, Default)]
 pub struct ExecOptions {
     pub cwd: Option<String>,
     /// Extra env vars merged on top of the inherited environment.
     pub env: Option<HashMap<String, String>>,
+    /// Kill the command (and everything it spawned) after this long.
+    pub timeout: Option<Duration>,
+}
+
+impl ExecResult {
+    fn failed(stderr: String) -> ExecResult {
+        ExecResult {
+            stdout: String::new(),
+            stderr,
+            exit_code: -1,
+            timed_out: false,
+        }
+    }
 }
 
 /// Trims trailing whitespace the same way JS `String.prototype.trimEnd` does.
@@ -31,12 +70,28 @@ fn trim_end(s: &str) -> String {
 /// A spawn failure is surfaced as exit code `-1` with the OS error on stderr,
 /// matching the "never throw" contract of the TS `exec`.
 pub fn exec(command: &[&str], options: Option<&ExecOptions>) -> ExecResult {
+    if !trace::enabled(Level::Debug) {
+        return exec_untraced(command, options);
+    }
+    let started = Instant::now();
+    let result = exec_untraced(command, options);
+    trace::event(
+        Level::Debug,
+        "exec",
+        serde_json::json!({
+            "cmd": command,
+            "cwd": options.and_then(|o| o.cwd.as_deref()),
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "exit_code": result.exit_code,
+            "timed_out": result.timed_out,
+        }),
+    );
+    result
+}
+
+fn exec_untraced(command: &[&str], options: Option<&ExecOptions>) -> ExecResult {
     if command.is_empty() {
-        return ExecResult {
-            stdout: String::new(),
-            stderr: "empty command".to_string(),
-            exit_code: -1,
-        };
+        return ExecResult::failed("empty command".to_string());
     }
 
     let mut cmd = Command::new(command[0]);
@@ -53,31 +108,207 @@ pub fn exec(command: &[&str], options: Option<&ExecOptions>) -> ExecResult {
         }
     }
 
+    if let Some(timeout) = options.and_then(|o| o.timeout) {
+        return exec_with_deadline(cmd, command, timeout);
+    }
+
     match cmd.output() {
         Ok(out) => ExecResult {
             stdout: trim_end(&String::from_utf8_lossy(&out.stdout)),
             stderr: trim_end(&String::from_utf8_lossy(&out.stderr)),
             exit_code: out.status.code().unwrap_or(-1),
+            timed_out: false,
         },
-        Err(e) => ExecResult {
-            stdout: String::new(),
-            stderr: e.to_string(),
-            exit_code: -1,
+        Err(e) => ExecResult::failed(e.to_string()),
+    }
+}
+
+/// Reads a pipe to the end on its own thread, so a chatty child never blocks
+/// on a full pipe while the caller is polling for its exit.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = tx.send(buf);
+        });
+    }
+    rx
+}
+
+fn exec_with_deadline(mut cmd: Command, command: &[&str], timeout: Duration) -> ExecResult {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return ExecResult::failed(e.to_string()),
+    };
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+            Ok(None) | Err(_) => break None,
+        }
+    };
+
+    // The child exiting doesn't close its pipes when a grandchild (a
+    // credential helper, a pager, a daemon) inherited them, so reading them
+    // is bound by the same deadline, plus the grace the kill path gets.
+    let read_deadline = deadline + DRAIN_GRACE;
+    let read_by_deadline = |rx: &mpsc::Receiver<Vec<u8>>| {
+        let left = read_deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok(buf) => Some(trim_end(&String::from_utf8_lossy(&buf))),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Some(String::new()),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+        }
+    };
+    let (out, err) = match status {
+        Some(status) => match (read_by_deadline(&stdout), read_by_deadline(&stderr)) {
+            (Some(stdout), Some(stderr)) => {
+                return ExecResult {
+                    stdout,
+                    stderr,
+                    exit_code: status.code().unwrap_or(-1),
+                    timed_out: false,
+                }
+            }
+            unread => unread,
         },
+        None => (None, None),
+    };
+
+    kill_group(&mut child);
+    let read = |rx: mpsc::Receiver<Vec<u8>>| {
+        rx.recv_timeout(DRAIN_GRACE)
+            .map(|buf| trim_end(&String::from_utf8_lossy(&buf)))
+            .unwrap_or_default()
+    };
+    let stdout = out.unwrap_or_else(|| read(stdout));
+    let partial = err.unwrap_or_else(|| read(stderr));
+    let note = format!(
+        "timed out after {}ms: {}",
+        timeout.as_millis(),
+        command.join(" ")
+    );
+    ExecResult {
+        stdout,
+        stderr: if partial.is_empty() {
+            note
+        } else {
+            format!("{partial}\n{note}")
+        },
+        exit_code: -1,
+        timed_out: true,
     }
 }
 
+/// Kills the child's whole process group (it leads one, see `process_group`
+/// above) and reaps the child.
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // SAFETY: `kill` has no memory-safety preconditions; a negative pid
+        // addresses the process group the child leads.
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
 /// Runs `command` and returns trimmed stdout, or an error string on non-zero
 /// exit. The error message mirrors the TS format exactly.
 pub fn exec_or_throw(command: &[&str], options: Option<&ExecOptions>) -> Result<String, String> {
-    let result = exec(command, options);
-    if result.exit_code != 0 {
-        return Err(format!(
-            "Command failed (exit {}): {}\n{}",
-            result.exit_code,
-            command.join(" "),
-            result.stderr
-        ));
-    }
-    Ok(result.stdout)
+    exec(command, options).into_stdout(command)
+}
+
+impl ExecResult {
+    /// `exec_or_throw`'s judgement of a finished `command`: its stdout, or the
+    /// error string on non-zero exit.
+    pub fn into_stdout(self, command: &[&str]) -> Result<String, String> {
+        if self.exit_code != 0 {
+            return Err(format!(
+                "Command failed (exit {}): {}\n{}",
+                self.exit_code,
+                command.join(" "),
+                self.stderr
+            ));
+        }
+        Ok(self.stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_timeout(ms: u64) -> ExecOptions {
+        ExecOptions {
+            timeout: Some(Duration::from_millis(ms)),
+            ..ExecOptions::default()
+        }
+    }
+
+    #[test]
+    fn fast_command_finishes_inside_its_deadline() {
+        let r = exec(
+            &["sh", "-c", "echo out; echo err >&2"],
+            Some(&with_timeout(5000)),
+        );
+        assert_eq!((r.stdout.as_str(), r.stderr.as_str()), ("out", "err"));
+        assert_eq!(r.exit_code, 0);
+        assert!(!r.timed_out);
+    }
+
+    #[test]
+    fn hung_command_is_killed_with_its_children() {
+        let started = Instant::now();
+        // The background `sleep` holds stdout open: without the group kill the
+        // output readers would wait the full 30s for it.
+        let r = exec(
+            &["sh", "-c", "echo partial; sleep 30 & sleep 30"],
+            Some(&with_timeout(200)),
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(r.timed_out);
+        assert_eq!(r.exit_code, -1);
+        assert_eq!(r.stdout, "partial");
+        assert!(r.stderr.contains("timed out after 200ms"));
+    }
+
+    #[test]
+    fn exited_command_whose_child_holds_its_pipes_times_out() {
+        let started = Instant::now();
+        // `sh` exits at once, but the background `sleep` inherited stdout and
+        // stderr: reading them to EOF would wait the full 30s.
+        let r = exec(
+            &["sh", "-c", "echo partial; sleep 30 &"],
+            Some(&with_timeout(200)),
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(r.timed_out);
+        assert_eq!(r.exit_code, -1);
+        assert_eq!(r.stdout, "partial");
+        assert!(r.stderr.contains("timed out after 200ms"));
+    }
+
+    #[test]
+    fn spawn_failure_is_not_a_timeout() {
+        let r = exec(&["atelier-no-such-binary"], Some(&with_timeout(1000)));
+        assert_eq!(r.exit_code, -1);
+        assert!(!r.timed_out);
+    }
 }
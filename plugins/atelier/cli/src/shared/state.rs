@@ -0,0 +1,181 @@
+//! Where atelier keeps state that must outlive a process: per-user, under the
+//! XDG state directory, because `$TMPDIR` is the wrong home for it — /tmp is
+//! cleaned at reboot or namespaced per service (systemd `PrivateTmp`), and a
+//! resumed session that lost its baseline goes silent with `NoBaseline`.
+//!
+//! ```text
+//! $ATELIER_STATE_DIR                    explicit override (tests, sandboxes)
+//! $XDG_STATE_HOME/atelier               when set to an absolute path
+//! $HOME/.local/state/atelier            the XDG default
+//! $TMPDIR/atelier                       last resort, no home directory
+//! ```
+//!
+//! Baselines written by earlier versions under `$TMPDIR/atelier-sessions` are
+//! moved over by `migrate_legacy_sessions` the first time a command opens the
+//! store; the emptied legacy directory is removed, so the move happens once.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Overrides every other rule when set.
+pub const STATE_DIR_ENV: &str = "ATELIER_STATE_DIR";
+
+/// Pure resolution rule; the env-reading wrapper is `state_root`. Relative
+/// XDG values are ignored, as the XDG spec requires.
+pub fn resolve_state_root(
+    override_dir: Option<OsString>,
+    xdg_state_home: Option<OsString>,
+    home: Option<OsString>,
+) -> PathBuf {
+    let non_empty = |v: Option<OsString>| v.filter(|v| !v.is_empty()).map(PathBuf::from);
+    if let Some(dir) = non_empty(override_dir) {
+        return dir;
+    }
+    if let Some(xdg) = non_empty(xdg_state_home).filter(|p| p.is_absolute()) {
+        return xdg.join("atelier");
+    }
+    match non_empty(home) {
+        Some(home) => home.join(".local").join("state").join("atelier"),
+        None => std::env::temp_dir().join("atelier"),
+    }
+}
+
+/// atelier's state root for the current user.
+pub fn state_root() -> PathBuf {
+    resolve_state_root(
+        std::env::var_os(STATE_DIR_ENV),
+        std::env::var_os("XDG_STATE_HOME"),
+        std::env::var_os("HOME"),
+    )
+}
+
+/// One JSON baseline per session.
+pub fn sessions_dir() -> PathBuf {
+    state_root().join("sessions")
+}
+
+/// Where versions before the XDG move kept session baselines.
+pub fn legacy_sessions_dir() -> PathBuf {
+    std::env::temp_dir().join("atelier-sessions")
+}
+
+/// Moves `*.json` baselines from `legacy` into `target`, then removes
+/// `legacy` if that left it empty. A baseline already present in `target`
+/// wins — it can only have been written by a newer atelier. Returns how many
+/// files moved. Every failure is skipped: migration is best-effort, and a
+/// baseline left behind costs at most one self-healed session.
+pub fn migrate_dir(legacy: &Path, target: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(legacy) else {
+        return 0;
+    };
+    if std::fs::create_dir_all(target).is_err() {
+        return 0;
+    }
+    let mut moved = 0;
+    for entry in entries.flatten() {
+        let from = entry.path();
+        if from.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let to = target.join(entry.file_name());
+        if to.exists() {
+            let _ = std::fs::remove_file(&from);
+            continue;
+        }
+        if move_file(&from, &to) {
+            moved += 1;
+        }
+    }
+    // Fails (and keeps the directory) while anything else is still inside.
+    let _ = std::fs::remove_dir(legacy);
+    moved
+}
+
+/// `rename`, falling back to copy-and-delete when `$TMPDIR` is a different
+/// filesystem. The copy keeps the original mtime, so TTL pruning still sees
+/// the baseline's real age.
+fn move_file(from: &Path, to: &Path) -> bool {
+    if std::fs::rename(from, to).is_ok() {
+        return true;
+    }
+    let mtime = std::fs::metadata(from).and_then(|m| m.modified()).ok();
+    if std::fs::copy(from, to).is_err() {
+        let _ = std::fs::remove_file(to);
+        return false;
+    }
+    if let (Some(mtime), Ok(file)) = (mtime, std::fs::File::options().write(true).open(to)) {
+        let _ = file.set_modified(mtime);
+    }
+    let _ = std::fs::remove_file(from);
+    true
+}
+
+/// Migrates the legacy session directory into `sessions_dir()`.
+pub fn migrate_legacy_sessions(target: &Path) -> usize {
+    migrate_dir(&legacy_sessions_dir(), target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(s: &str) -> Option<OsString> {
+        Some(OsString::from(s))
+    }
+
+    #[test]
+    fn override_wins_then_xdg_then_home() {
+        assert_eq!(
+            resolve_state_root(os("/o"), os("/x"), os("/h")),
+            PathBuf::from("/o")
+        );
+        assert_eq!(
+            resolve_state_root(None, os("/x"), os("/h")),
+            PathBuf::from("/x/atelier")
+        );
+        assert_eq!(
+            resolve_state_root(os(""), None, os("/h")),
+            PathBuf::from("/h/.local/state/atelier")
+        );
+    }
+
+    #[test]
+    fn relative_xdg_state_home_is_ignored() {
+        assert_eq!(
+            resolve_state_root(None, os("rel/state"), os("/h")),
+            PathBuf::from("/h/.local/state/atelier")
+        );
+    }
+
+    #[test]
+    fn migration_moves_baselines_once_and_keeps_newer_copies() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let legacy = tmp.path().join("legacy");
+        let target = tmp.path().join("state/sessions");
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(legacy.join("old-session.json"), "{}").unwrap();
+        std::fs::write(legacy.join("both-session.json"), r#"{"notified":false}"#).unwrap();
+        std::fs::write(target.join("both-session.json"), r#"{"notified":true}"#).unwrap();
+
+        assert_eq!(migrate_dir(&legacy, &target), 1);
+        assert!(target.join("old-session.json").is_file());
+        assert_eq!(
+            std::fs::read_to_string(target.join("both-session.json")).unwrap(),
+            r#"{"notified":true}"#
+        );
+        assert!(!legacy.exists());
+        assert_eq!(migrate_dir(&legacy, &target), 0);
+    }
+
+    #[test]
+    fn migration_keeps_legacy_dir_holding_other_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let legacy = tmp.path().join("legacy");
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::write(legacy.join(".s.1.0.tmp"), "").unwrap();
+
+        assert_eq!(migrate_dir(&legacy, &tmp.path().join("target")), 0);
+        assert!(legacy.join(".s.1.0.tmp").is_file());
+    }
+}
@@ -0,0 +1,243 @@
+//! Structured after-the-fact log. Hooks own stdout and stderr (a stray line
+//! there changes what Claude Code sees), so atelier's diagnostics go to a
+//! JSON-lines file instead, and only when asked for:
+//!
+//! ```text
+//! ATELIER_LOG=info     spans, decisions, config sources
+//! ATELIER_LOG=debug    … plus every subprocess with its duration and exit code
+//! ATELIER_LOG=off      (or unset) nothing is written
+//! ```
+//!
+//! Records go to `<state root>/logs/atelier.jsonl` — one object per line with
+//! `ts` (Unix ms), `pid`, `level`, `span` (the enclosing spans, outermost
+//! first, joined by `/`), `event`, and the event's own fields. The file
+//! rotates at `MAX_BYTES` into `.1` … `.KEEP`.
+//!
+//! Logging is best-effort by design: any failure to open, rotate or write is
+//! swallowed, because no diagnostic is worth failing a hook over.
+
+use crate::shared::state::state_root;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Selects what is recorded.
+pub const LOG_ENV: &str = "ATELIER_LOG";
+
+/// Size at which the active file is rotated.
+pub const MAX_BYTES: u64 = 1024 * 1024;
+
+/// Rotated generations kept next to the active file.
+pub const KEEP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+
+    /// `ATELIER_LOG` → the most verbose level recorded; `None` disables.
+    pub fn parse(value: &str) -> Option<Level> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "0" | "off" | "false" | "none" => None,
+            "debug" | "trace" => Some(Level::Debug),
+            _ => Some(Level::Info),
+        }
+    }
+}
+
+struct Sink {
+    path: PathBuf,
+    max: Level,
+    /// Serializes writers within the process; `O_APPEND` covers the rest.
+    lock: Mutex<()>,
+}
+
+static SINK: OnceLock<Option<Sink>> = OnceLock::new();
+
+thread_local! {
+    static SPANS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Where the log lives for the current user.
+pub fn log_path() -> PathBuf {
+    state_root().join("logs").join("atelier.jsonl")
+}
+
+fn sink() -> Option<&'static Sink> {
+    SINK.get_or_init(|| {
+        let max = Level::parse(&std::env::var(LOG_ENV).ok()?)?;
+        Some(Sink {
+            path: log_path(),
+            max,
+            lock: Mutex::new(()),
+        })
+    })
+    .as_ref()
+}
+
+/// Whether records at `level` are written — lets a call site skip building
+/// fields nobody will read.
+pub fn enabled(level: Level) -> bool {
+    sink().is_some_and(|s| level <= s.max)
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Shifts `path` → `.1` → … → `.keep` once it has reached `max_bytes`,
+/// dropping the oldest generation.
+pub fn rotate(path: &Path, max_bytes: u64, keep: usize) {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return;
+    };
+    if meta.len() < max_bytes {
+        return;
+    }
+    let generation = |n: usize| PathBuf::from(format!("{}.{n}", path.display()));
+    let _ = std::fs::remove_file(generation(keep));
+    for n in (1..keep).rev() {
+        let _ = std::fs::rename(generation(n), generation(n + 1));
+    }
+    let _ = std::fs::rename(path, generation(1));
+}
+
+/// One JSON line: the envelope fields plus the event's own. An event field
+/// never overrides the envelope.
+pub fn record(level: Level, span: &str, event: &str, fields: Value) -> String {
+    let mut line = Map::new();
+    line.insert("ts".into(), Value::from(now_ms() as u64));
+    line.insert("pid".into(), Value::from(std::process::id()));
+    line.insert("level".into(), Value::from(level.as_str()));
+    line.insert("span".into(), Value::from(span));
+    line.insert("event".into(), Value::from(event));
+    if let Value::Object(fields) = fields {
+        for (key, value) in fields {
+            line.entry(key).or_insert(value);
+        }
+    }
+    Value::Object(line).to_string()
+}
+
+/// Records `event` with `fields` (a JSON object) under the current spans.
+pub fn event(level: Level, event: &str, fields: Value) {
+    let Some(sink) = sink().filter(|s| level <= s.max) else {
+        return;
+    };
+    let span = SPANS.with(|s| s.borrow().join("/"));
+    let line = record(level, &span, event, fields);
+    let _guard = sink.lock.lock();
+    if let Some(dir) = sink.path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    rotate(&sink.path, MAX_BYTES, KEEP);
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&sink.path)
+    {
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+/// Shorthand for an `Info` event — decisions and config sources.
+pub fn info(name: &str, fields: Value) {
+    event(Level::Info, name, fields);
+}
+
+/// An open span: records `span_start` now and `span_end` with its duration
+/// when dropped. Events in between carry its name in `span`.
+pub struct Span {
+    name: String,
+    started: Instant,
+}
+
+/// Opens a span named `name` on this thread.
+pub fn span(name: impl Into<String>) -> Span {
+    let name = name.into();
+    SPANS.with(|s| s.borrow_mut().push(name.clone()));
+    info("span_start", Value::Object(Map::new()));
+    Span {
+        name,
+        started: Instant::now(),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        info(
+            "span_end",
+            serde_json::json!({ "duration_ms": self.started.elapsed().as_millis() as u64 }),
+        );
+        SPANS.with(|s| {
+            let mut spans = s.borrow_mut();
+            if spans.last() == Some(&self.name) {
+                spans.pop();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_parse_from_the_env_value() {
+        assert_eq!(Level::parse(""), None);
+        assert_eq!(Level::parse("off"), None);
+        assert_eq!(Level::parse("1"), Some(Level::Info));
+        assert_eq!(Level::parse("info"), Some(Level::Info));
+        assert_eq!(Level::parse("DEBUG"), Some(Level::Debug));
+    }
+
+    #[test]
+    fn record_carries_the_envelope_and_the_fields() {
+        let line = record(
+            Level::Debug,
+            "git guard",
+            "exec",
+            serde_json::json!({"exit_code": 0, "event": "spoofed"}),
+        );
+        let value: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["level"], "debug");
+        assert_eq!(value["span"], "git guard");
+        assert_eq!(value["event"], "exec");
+        assert_eq!(value["exit_code"], 0);
+        assert!(value["ts"].is_u64() && value["pid"].is_u64());
+    }
+
+    #[test]
+    fn rotation_shifts_generations_and_drops_the_oldest() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let log = tmp.path().join("atelier.jsonl");
+        let gen = |n: usize| tmp.path().join(format!("atelier.jsonl.{n}"));
+        std::fs::write(&log, "current").unwrap();
+        std::fs::write(gen(1), "one").unwrap();
+        std::fs::write(gen(2), "two").unwrap();
+
+        rotate(&log, 100, 2);
+        assert!(log.exists(), "below the limit nothing moves");
+
+        rotate(&log, 4, 2);
+        assert!(!log.exists());
+        assert_eq!(std::fs::read_to_string(gen(1)).unwrap(), "current");
+        assert_eq!(std::fs::read_to_string(gen(2)).unwrap(), "one");
+        assert!(!gen(3).exists());
+    }
+}
@@ -0,0 +1,106 @@
+//! The state clap surface: opens the default store and prints what the
+//! commands return.
+
+use crate::session::core::sqlite_store::SqliteBaselineStore;
+use crate::shared::process::canonical_project;
+use crate::shared::state::{legacy_sessions_dir, sessions_dir};
+use crate::state::commands;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(
+    name = "state",
+    version,
+    about = "Inspect, sweep and export atelier's on-disk state"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Show the database, its baselines and which of them have expired
+    Info,
+    /// Delete expired session baselines
+    Gc {
+        /// List what would be deleted without deleting it
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+    },
+    /// Print stored baselines
+    Export {
+        /// Only sessions recorded for this project directory
+        #[arg(long = "project")]
+        project: Option<String>,
+        /// Only this session
+        #[arg(long = "session-id")]
+        session_id: Option<String>,
+        #[arg(long = "format", value_enum, default_value_t = commands::export::ExportFormat::Json)]
+        format: commands::export::ExportFormat,
+    },
+}
+
+fn output<T: Serialize>(result: Result<T, String>) -> i32 {
+    match result {
+        Ok(data) => {
+            let json = serde_json::to_string_pretty(&data).unwrap_or_else(|_| "null".to_string());
+            println!("{json}");
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+/// Parses `argv` (including the leading program name) with the state clap
+/// surface and runs the selected command.
+pub fn run_from<I, T>(argv: I) -> i32
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    run(Cli::parse_from(argv))
+}
+
+pub fn run(cli: Cli) -> i32 {
+    let Some(command) = cli.command else {
+        use clap::CommandFactory;
+        let _ = Cli::command().print_help();
+        println!();
+        return 0;
+    };
+    // Opening the store is what imports leftover JSON baselines, so `info`
+    // never reports files that the next hook would have moved anyway.
+    let store = match SqliteBaselineStore::open_default() {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+    match command {
+        Commands::Info => output(commands::info::run(
+            &store,
+            &[legacy_sessions_dir(), sessions_dir()],
+        )),
+        Commands::Gc { dry_run } => output(commands::gc::run(&store, dry_run)),
+        Commands::Export {
+            project,
+            session_id,
+            format,
+        } => {
+            let project = project.as_deref().map(canonical_project);
+            match commands::export::run(&store, project.as_deref(), session_id.as_deref()) {
+                Ok(rows) => {
+                    print!("{}", commands::export::render(&rows, format));
+                    0
+                }
+                Err(e) => output::<()>(Err(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! `state export` — stored baselines with their bookkeeping, for debugging a
+//! silent hook or carrying state to another machine.
+
+use crate::session::core::sqlite_store::{SqliteBaselineStore, StoredBaseline};
+
+/// Output rendering for `state export`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ExportFormat {
+    /// One pretty-printed JSON array
+    #[default]
+    Json,
+    /// One compact JSON object per line
+    Jsonl,
+}
+
+/// Baselines matching every given filter, oldest write first.
+pub fn run(
+    store: &SqliteBaselineStore,
+    project: Option<&str>,
+    session_id: Option<&str>,
+) -> Result<Vec<StoredBaseline>, String> {
+    Ok(store
+        .entries(project)?
+        .into_iter()
+        .filter(|e| session_id.is_none_or(|id| e.session_id == id))
+        .collect())
+}
+
+pub fn render(rows: &[StoredBaseline], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Json => {
+            let json = serde_json::to_string_pretty(rows).unwrap_or_else(|_| "[]".to_string());
+            format!("{json}\n")
+        }
+        ExportFormat::Jsonl => rows
+            .iter()
+            .filter_map(|row| serde_json::to_string(row).ok())
+            .map(|line| format!("{line}\n"))
+            .collect(),
+    }
+}
@@ -0,0 +1,32 @@
+//! `state gc` — runs the TTL prune that every baseline write does implicitly.
+
+use crate::session::core::sqlite_store::SqliteBaselineStore;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GcReport {
+    pub dry_run: bool,
+    /// Deleted session ids — or, on a dry run, the ones that would be deleted.
+    pub removed: Vec<String>,
+    pub kept: usize,
+}
+
+pub fn run(store: &SqliteBaselineStore, dry_run: bool) -> Result<GcReport, String> {
+    let before = store.entries(None)?;
+    let removed: Vec<String> = if dry_run {
+        let mut expired: Vec<String> = before
+            .iter()
+            .filter(|e| e.expired)
+            .map(|e| e.session_id.clone())
+            .collect();
+        expired.sort();
+        expired
+    } else {
+        store.prune()?
+    };
+    Ok(GcReport {
+        dry_run,
+        kept: before.len().saturating_sub(removed.len()),
+        removed,
+    })
+}
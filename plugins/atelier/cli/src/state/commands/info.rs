@@ -0,0 +1,67 @@
+//! `state info` — the database's location and TTL, and each stored baseline
+//! with its age and whether the next prune deletes it.
+
+use crate::session::core::sqlite_store::SqliteBaselineStore;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub project: Option<String>,
+    pub bytes: u64,
+    pub age_secs: u64,
+    pub expired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StateInfo {
+    /// Database file; `None` for an in-memory store.
+    pub database: Option<String>,
+    pub ttl_secs: u64,
+    pub sessions: usize,
+    pub projects: usize,
+    pub expired: usize,
+    pub bytes: u64,
+    pub entries: Vec<SessionInfo>,
+    /// JSON-file locations of earlier versions that still exist — only when
+    /// something in them could not be imported.
+    pub legacy_dirs: Vec<String>,
+}
+
+pub fn run(store: &SqliteBaselineStore, legacy_dirs: &[PathBuf]) -> Result<StateInfo, String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    let entries: Vec<SessionInfo> = store
+        .entries(None)?
+        .into_iter()
+        .map(|e| SessionInfo {
+            age_secs: (now.saturating_sub(e.updated_at).max(0) / 1000) as u64,
+            session_id: e.session_id,
+            project: e.project,
+            bytes: e.bytes,
+            expired: e.expired,
+        })
+        .collect();
+    Ok(StateInfo {
+        database: store.path(),
+        ttl_secs: store.ttl().as_secs(),
+        sessions: entries.len(),
+        projects: entries
+            .iter()
+            .filter_map(|e| e.project.as_deref())
+            .collect::<BTreeSet<_>>()
+            .len(),
+        expired: entries.iter().filter(|e| e.expired).count(),
+        bytes: entries.iter().map(|e| e.bytes).sum(),
+        entries,
+        legacy_dirs: legacy_dirs
+            .iter()
+            .filter(|d| d.exists())
+            .map(|d| d.display().to_string())
+            .collect(),
+    })
+}
@@ -0,0 +1,3 @@
+pub mod export;
+pub mod gc;
+pub mod info;
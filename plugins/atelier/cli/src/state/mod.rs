@@ -0,0 +1,20 @@
+//! State subsystem — inspects, sweeps and exports what atelier keeps on disk
+//! between processes: the shared database (`shared::db`), today holding the
+//! per-session baselines.
+//!
+//! ```text
+//! atelier state info                     # where state lives, what is in it, what is expired
+//! atelier state gc [--dry-run]           # delete what the TTL has expired
+//! atelier state export [--project <dir>] [--session-id <id>] [--format json|jsonl]
+//! ```
+//!
+//! `gc` is the pruning every baseline write already does implicitly, run on
+//! demand. Output contract (same as `git`): JSON on stdout and exit 0, or
+//! `Error: <message>` on stderr and exit 1 when the database cannot be opened.
+
+#[cfg(feature = "cli")]
+mod cli;
+pub mod commands;
+
+#[cfg(feature = "cli")]
+pub use cli::{run, run_from, Cli, Commands};
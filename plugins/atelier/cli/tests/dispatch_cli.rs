@@ -0,0 +1,155 @@
+//! End-to-end black-box tests for `atelier hook-dispatch` — one binary call
+//! per hook event, against a real repository and an isolated state dir.
+
+use assert_cmd::Command;
+use std::path::Path;
+
+fn atelier(state: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("atelier").expect("locate `atelier` cargo binary");
+    cmd.env("ATELIER_STATE_DIR", state);
+    cmd
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+/// A repository on `main` with one commit, inside a TempDir that also holds
+/// the state dir.
+fn fixture() -> (tempfile::TempDir, String) {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    git(&project, &["init", "-q", "-b", "main"]);
+    std::fs::write(project.join("README.md"), "# fixture\n").unwrap();
+    git(&project, &["add", "."]);
+    git(&project, &["commit", "-q", "-m", "init"]);
+    let project = project.to_str().unwrap().to_string();
+    (tmp, project)
+}
+
+fn payload(event: &str, tool: Option<&str>, project: &str, file: &str) -> String {
+    serde_json::json!({
+        "hook_event_name": event,
+        "tool_name": tool,
+        "session_id": "sess-dispatch-cli-01",
+        "cwd": project,
+        "tool_input": {"file_path": format!("{project}/{file}")},
+    })
+    .to_string()
+}
+
+#[test]
+fn write_on_the_default_branch_is_denied_as_json() {
+    let (tmp, project) = fixture();
+    let out = atelier(&tmp.path().join("state"))
+        .args(["hook-dispatch", "--default-branch", "main"])
+        .write_stdin(payload("PreToolUse", Some("Write"), &project, "src/a.rs"))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["hookSpecificOutput"]["hookEventName"], "PreToolUse");
+    assert_eq!(json["hookSpecificOutput"]["permissionDecision"], "deny");
+    assert!(json["hookSpecificOutput"]["permissionDecisionReason"].is_string());
+}
+
+#[test]
+fn session_start_then_stop_routes_to_the_session_handlers() {
+    let (tmp, project) = fixture();
+    let state = tmp.path().join("state");
+    atelier(&state)
+        .arg("hook-dispatch")
+        .write_stdin(payload("SessionStart", None, &project, ""))
+        .assert()
+        .success()
+        .stdout("");
+    std::fs::write(Path::new(&project).join("main.rs"), "fn main() {}\n").unwrap();
+
+    let out = atelier(&state)
+        .arg("hook-dispatch")
+        .write_stdin(payload("Stop", None, &project, ""))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert!(json["systemMessage"].as_str().unwrap().contains("main.rs"));
+    assert!(json.get("hookSpecificOutput").is_none());
+}
+
+#[test]
+fn a_disabled_handler_is_not_run() {
+    let (tmp, project) = fixture();
+    std::fs::create_dir_all(Path::new(&project).join(".claude")).unwrap();
+    std::fs::write(
+        Path::new(&project).join(".claude/atelier.json"),
+        r#"{"dispatch":{"handlers":{"guard-write":false}}}"#,
+    )
+    .unwrap();
+    atelier(&tmp.path().join("state"))
+        .args(["hook-dispatch", "--default-branch", "main"])
+        .write_stdin(payload("PreToolUse", Some("Edit"), &project, "src/a.rs"))
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn atelier_log_records_spans_subprocesses_and_decisions() {
+    let (tmp, project) = fixture();
+    let state = tmp.path().join("state");
+    atelier(&state)
+        .env("ATELIER_LOG", "debug")
+        .args(["hook-dispatch", "--default-branch", "main"])
+        .write_stdin(payload("PreToolUse", Some("Write"), &project, "src/a.rs"))
+        .assert()
+        .success();
+
+    let log = std::fs::read_to_string(state.join("logs/atelier.jsonl")).unwrap();
+    let records: Vec<serde_json::Value> = log
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let find = |event: &str| {
+        records
+            .iter()
+            .find(|r| r["event"] == event)
+            .unwrap_or_else(|| panic!("no {event} record in:\n{log}"))
+    };
+    assert_eq!(find("span_start")["span"], "hook-dispatch");
+    assert_eq!(find("config_load")["source"], "absent");
+    let exec = find("exec");
+    assert_eq!(exec["cmd"][0], "git");
+    assert!(exec["duration_ms"].is_u64() && exec["exit_code"].is_i64());
+    let decision = find("guard_decision");
+    assert_eq!(decision["span"], "hook-dispatch/guard-write");
+    assert_eq!(
+        (&decision["target"], &decision["allowed"]),
+        (&"write".into(), &false.into())
+    );
+    assert_eq!(find("dispatch_decision")["permission_decision"], "deny");
+    assert_eq!(records.last().unwrap()["event"], "span_end");
+}
+
+#[test]
+fn without_atelier_log_nothing_is_written() {
+    let (tmp, project) = fixture();
+    let state = tmp.path().join("state");
+    atelier(&state)
+        .env_remove("ATELIER_LOG")
+        .args(["hook-dispatch", "--default-branch", "main"])
+        .write_stdin(payload("PreToolUse", Some("Write"), &project, "src/a.rs"))
+        .assert()
+        .success();
+    assert!(!state.join("logs").exists());
+}
@@ -0,0 +1,195 @@
+//! Routing and merging for `hook-dispatch`, against an in-memory
+//! `HandlerRunner` that records every call.
+
+use atelier::dispatch::route::{dispatch, handlers_for, DispatchEvent, Handler, HandlerRunner};
+use atelier::git::commands::guard::{GuardTargetKind, HookPayload};
+use atelier::git::types::GuardDecision;
+use atelier::session::commands::advise::Advice;
+use atelier::session::commands::payload::SessionPayload;
+use atelier::shared::config::ProjectConfig;
+use atelier::shared::hook_output::PermissionDecision;
+use std::cell::RefCell;
+
+/// Blocks the guard targets listed in `deny`, escalates those in `ask`;
+/// advises with `advice`.
+#[derive(Default)]
+struct MockRunner {
+    deny: Vec<GuardTargetKind>,
+    ask: Vec<GuardTargetKind>,
+    advice: Vec<&'static str>,
+    calls: RefCell<Vec<String>>,
+}
+
+impl HandlerRunner for MockRunner {
+    fn guard(&self, target: GuardTargetKind, _payload: &HookPayload) -> GuardDecision {
+        self.calls.borrow_mut().push(format!("guard {target:?}"));
+        let blocked = self.deny.contains(&target);
+        let ask = self.ask.contains(&target);
+        GuardDecision {
+            allowed: !blocked,
+            ask,
+            reason: if blocked {
+                Some(format!("{target:?} blocked"))
+            } else {
+                ask.then(|| format!("{target:?} asks"))
+            },
+            context: (blocked || ask).then(|| "current branch: main".to_string()),
+        }
+    }
+
+    fn baseline(&self, payload: &SessionPayload) {
+        self.calls.borrow_mut().push(format!(
+            "baseline {}",
+            payload.session_id.clone().unwrap_or_default()
+        ));
+    }
+
+    fn advise(&self, _payload: &SessionPayload) -> Vec<Advice> {
+        self.calls.borrow_mut().push("advise".to_string());
+        self.advice
+            .iter()
+            .map(|m| Advice {
+                advisor: "mock",
+                message: m.to_string(),
+            })
+            .collect()
+    }
+}
+
+fn event(name: &str, tool: Option<&str>) -> DispatchEvent {
+    let payload = serde_json::json!({
+        "hook_event_name": name,
+        "tool_name": tool,
+        "session_id": "sess-dispatch-01",
+        "tool_input": {"command": "git commit -m x", "file_path": "/p/src/a.rs"},
+    });
+    DispatchEvent::parse(&payload.to_string())
+}
+
+fn enabled(event: &DispatchEvent) -> Vec<Handler> {
+    handlers_for(event, &ProjectConfig::default().dispatch)
+}
+
+#[test]
+fn events_reach_the_handlers_their_matchers_named() {
+    assert_eq!(
+        enabled(&event("PreToolUse", Some("Edit"))),
+        vec![Handler::GuardWrite]
+    );
+    assert_eq!(
+        enabled(&event("PreToolUse", Some("Bash"))),
+        vec![Handler::GuardCommit, Handler::GuardPr]
+    );
+    assert_eq!(
+        enabled(&event("SessionStart", None)),
+        vec![Handler::Baseline]
+    );
+    assert_eq!(enabled(&event("Stop", None)), vec![Handler::Advise]);
+    assert!(enabled(&event("PreToolUse", Some("Read"))).is_empty());
+    assert!(enabled(&DispatchEvent::parse("not json")).is_empty());
+}
+
+#[test]
+fn disabled_handlers_are_skipped() {
+    let config = ProjectConfig::parse(r#"{"dispatch":{"handlers":{"guard-pr":false}}}"#);
+    assert_eq!(
+        handlers_for(&event("PreToolUse", Some("Bash")), &config.dispatch),
+        vec![Handler::GuardCommit]
+    );
+}
+
+#[test]
+fn any_block_wins_and_keeps_every_reason() {
+    let event = event("PreToolUse", Some("Bash"));
+    let runner = MockRunner {
+        deny: vec![GuardTargetKind::Commit, GuardTargetKind::Pr],
+        ..MockRunner::default()
+    };
+    let output = dispatch(&event, &enabled(&event), &runner);
+    let specific = output.hook_specific_output.unwrap();
+    assert_eq!(specific.hook_event_name, "PreToolUse");
+    assert_eq!(specific.permission_decision, Some(PermissionDecision::Deny));
+    assert_eq!(
+        specific.permission_decision_reason.as_deref(),
+        Some("Commit blocked\n\nPr blocked")
+    );
+    // Both guards saw the same branch: the context says it once.
+    assert_eq!(
+        specific.additional_context.as_deref(),
+        Some("current branch: main")
+    );
+    assert_eq!(
+        *runner.calls.borrow(),
+        vec!["guard Commit".to_string(), "guard Pr".to_string()]
+    );
+}
+
+#[test]
+fn a_block_outranks_an_ask() {
+    let event = event("PreToolUse", Some("Bash"));
+    let runner = MockRunner {
+        deny: vec![GuardTargetKind::Pr],
+        ask: vec![GuardTargetKind::Commit],
+        ..MockRunner::default()
+    };
+    let specific = dispatch(&event, &enabled(&event), &runner)
+        .hook_specific_output
+        .unwrap();
+    assert_eq!(specific.permission_decision, Some(PermissionDecision::Deny));
+    assert_eq!(
+        specific.permission_decision_reason.as_deref(),
+        Some("Pr blocked")
+    );
+}
+
+#[test]
+fn an_ask_without_a_block_escalates() {
+    let event = event("PreToolUse", Some("Bash"));
+    let runner = MockRunner {
+        ask: vec![GuardTargetKind::Commit],
+        ..MockRunner::default()
+    };
+    let specific = dispatch(&event, &enabled(&event), &runner)
+        .hook_specific_output
+        .unwrap();
+    assert_eq!(specific.permission_decision, Some(PermissionDecision::Ask));
+    assert_eq!(
+        specific.permission_decision_reason.as_deref(),
+        Some("Commit asks")
+    );
+}
+
+#[test]
+fn passing_guards_say_nothing() {
+    let event = event("PreToolUse", Some("Bash"));
+    let runner = MockRunner {
+        deny: vec![GuardTargetKind::Write],
+        ..MockRunner::default()
+    };
+    let output = dispatch(&event, &enabled(&event), &runner);
+    assert!(output.is_empty());
+    assert_eq!(output.render(), None);
+}
+
+#[test]
+fn advice_becomes_the_system_message() {
+    let event = event("Stop", None);
+    let runner = MockRunner {
+        advice: vec!["first\n", "second\n"],
+        ..MockRunner::default()
+    };
+    let output = dispatch(&event, &enabled(&event), &runner);
+    assert!(output.hook_specific_output.is_none());
+    assert_eq!(output.system_message.as_deref(), Some("first\nsecond"));
+}
+
+#[test]
+fn session_start_records_the_payload_session() {
+    let event = event("SessionStart", None);
+    let runner = MockRunner::default();
+    assert!(dispatch(&event, &enabled(&event), &runner).is_empty());
+    assert_eq!(
+        *runner.calls.borrow(),
+        vec!["baseline sess-dispatch-01".to_string()]
+    );
+}
@@ -0,0 +1,315 @@
+//! Tests for `doctor`'s checks over a mock world: each test breaks one part
+//! of an otherwise healthy environment and pins the verdict it gets.
+
+mod drift_mocks;
+mod git_mocks;
+
+use atelier::doctor::checks::{
+    run, Check, CheckStatus, DoctorDeps, DoctorFormat, DoctorInput, DoctorReport,
+};
+use atelier::doctor::probe::ToolProbe;
+use atelier::git::commands::hook::create_hook_command;
+use atelier::git::core::github::GhAuthStatus;
+use drift_mocks::{block, deps as drift_deps, paths, FixedClock, MemFs, USER_CLAUDE_MD};
+use git_mocks::{MockFs, MockGit, MockGitHub, MockWarmer};
+
+const PROJECT: &str = "/proj";
+const HOME: &str = "/home/u";
+const GUARDS: &str = r#"{"hooks":{"PreToolUse":[
+  {"matcher":"Write|Edit|MultiEdit|Bash","hooks":[{"type":"command","command":"atelier hook-dispatch --project-dir \"${CLAUDE_PROJECT_DIR:-.}\" --default-branch main"}]}
+]}}"#;
+
+/// Tools on PATH, by name.
+struct Tools(&'static [&'static str]);
+
+impl ToolProbe for Tools {
+    fn version(&self, tool: &str) -> Option<String> {
+        self.0
+            .contains(&tool)
+            .then(|| format!("{tool} version 1.0"))
+    }
+}
+
+/// One doctor run's world. Defaults are healthy: both tools, gh logged in,
+/// origin/HEAD warmed, current guards in project settings, no drift.
+struct World {
+    tools: Tools,
+    gh: MockGitHub,
+    git: MockGit,
+    warmer: MockWarmer,
+    settings: MockFs,
+    artifacts: MemFs,
+    plugin_root: bool,
+    state_dir: std::path::PathBuf,
+    _tmp: tempfile::TempDir,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let settings = MockFs::new();
+        settings.set(&format!("{PROJECT}/.claude/settings.json"), GUARDS);
+        let artifacts = MemFs::with_sources("style");
+        artifacts.insert(USER_CLAUDE_MD, &block("style"));
+        World {
+            tools: Tools(&["git", "gh"]),
+            gh: MockGitHub::default(),
+            git: MockGit::default(),
+            warmer: MockWarmer::default(),
+            settings,
+            artifacts,
+            plugin_root: true,
+            state_dir: tmp.path().join("state"),
+            _tmp: tmp,
+        }
+    }
+}
+
+impl World {
+    fn report(&self) -> DoctorReport {
+        let hook = create_hook_command(&self.settings);
+        let clock = FixedClock;
+        let drift = drift_deps(&self.artifacts, &clock);
+        run(
+            &DoctorDeps {
+                tools: &self.tools,
+                gh_auth: &self.gh,
+                git: &self.git,
+                warmer: &self.warmer,
+                hook: &hook,
+                drift: &drift,
+            },
+            &DoctorInput {
+                project_dir: PROJECT.to_string(),
+                home: Some(HOME.to_string()),
+                drift: self.plugin_root.then(paths),
+                state_dir: self.state_dir.clone(),
+            },
+        )
+    }
+
+    fn check(&self, name: &str) -> Check {
+        self.report()
+            .checks
+            .into_iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("no {name} check"))
+    }
+}
+
+#[test]
+fn healthy_environment_passes_every_check() {
+    let report = World::default().report();
+    let names: Vec<&str> = report.checks.iter().map(|c| c.name).collect();
+    assert_eq!(
+        names,
+        [
+            "git",
+            "gh",
+            "gh-auth",
+            "origin-head",
+            "guard-hooks",
+            "drift",
+            "state-dir"
+        ]
+    );
+    assert!(
+        report.checks.iter().all(|c| c.status == CheckStatus::Pass),
+        "{report:#?}"
+    );
+    assert_eq!(report.exit_code(), 0);
+}
+
+#[test]
+fn missing_git_fails_and_skips_origin_head() {
+    let world = World {
+        tools: Tools(&["gh"]),
+        ..World::default()
+    };
+    let report = world.report();
+    assert_eq!(report.checks[0].status, CheckStatus::Fail);
+    assert_eq!(report.checks[3].status, CheckStatus::Warn);
+    assert!(report.checks[3].detail.starts_with("skipped"));
+    assert_eq!(report.exit_code(), 1);
+}
+
+#[test]
+fn missing_gh_only_warns_and_skips_the_auth_check() {
+    let world = World {
+        tools: Tools(&["git"]),
+        gh: MockGitHub {
+            auth_status: Box::new(|| panic!("gh must not be asked")),
+            ..MockGitHub::default()
+        },
+        ..World::default()
+    };
+    let report = world.report();
+    assert_eq!(report.checks[1].status, CheckStatus::Warn);
+    assert_eq!(report.checks[2].status, CheckStatus::Warn);
+    assert_eq!(report.exit_code(), 0);
+}
+
+#[test]
+fn unauthenticated_gh_names_the_host_it_checked() {
+    let world = World {
+        gh: MockGitHub {
+            auth_status: Box::new(|| GhAuthStatus {
+                host: Some("ghe.example.com".to_string()),
+                authenticated: false,
+                detail: "You are not logged into any GitHub hosts.".to_string(),
+            }),
+            ..MockGitHub::default()
+        },
+        ..World::default()
+    };
+    let check = world.check("gh-auth");
+    assert_eq!(check.status, CheckStatus::Warn);
+    assert!(check.detail.contains("ghe.example.com"), "{}", check.detail);
+    assert!(check.detail.contains("not logged into"), "{}", check.detail);
+}
+
+#[test]
+fn cold_origin_head_warns() {
+    let world = World {
+        warmer: MockWarmer {
+            origin_head: Box::new(|| None),
+            ..MockWarmer::default()
+        },
+        ..World::default()
+    };
+    let check = world.check("origin-head");
+    assert_eq!(check.status, CheckStatus::Warn);
+    assert!(check.detail.contains("set-head"));
+}
+
+#[test]
+fn outside_a_repository_origin_head_warns_without_reading() {
+    let world = World {
+        git: MockGit {
+            is_inside_work_tree: Box::new(|| false),
+            ..MockGit::default()
+        },
+        warmer: MockWarmer {
+            origin_head: Box::new(|| panic!("no repository to read")),
+            ..MockWarmer::default()
+        },
+        ..World::default()
+    };
+    assert_eq!(world.check("origin-head").status, CheckStatus::Warn);
+}
+
+#[test]
+fn per_guard_registration_is_a_stale_generation() {
+    let world = World::default();
+    world.settings.set(
+        &format!("{HOME}/.claude/settings.json"),
+        r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"atelier git guard commit --project-dir \"${CLAUDE_PROJECT_DIR:-.}\" --default-branch main"}]}]}}"#,
+    );
+    let check = world.check("guard-hooks");
+    assert_eq!(check.status, CheckStatus::Fail);
+    assert!(check
+        .detail
+        .contains("atelier git guard commit --project-dir"));
+}
+
+#[test]
+fn legacy_guard_script_is_a_stale_generation() {
+    let world = World::default();
+    world.settings.set(
+        &format!("{PROJECT}/.claude/settings.json"),
+        r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"/x/plugins/git-utils/scripts/default-branch-guard-commit-hook.sh"}]}]}}"#,
+    );
+    assert_eq!(world.check("guard-hooks").status, CheckStatus::Fail);
+}
+
+#[test]
+fn hook_dispatch_covers_both_guards() {
+    let world = World::default();
+    world.settings.set(
+        &format!("{PROJECT}/.claude/settings.json"),
+        r#"{"hooks":{"PreToolUse":[{"matcher":"*","hooks":[{"type":"command","command":"atelier hook-dispatch --default-branch main"}]}]}}"#,
+    );
+    assert_eq!(world.check("guard-hooks").status, CheckStatus::Pass);
+}
+
+#[test]
+fn missing_guards_warn_with_the_setup_command() {
+    let world = World {
+        settings: MockFs::new(),
+        ..World::default()
+    };
+    let check = world.check("guard-hooks");
+    assert_eq!(check.status, CheckStatus::Warn);
+    assert!(
+        check.detail.contains("no guard registered"),
+        "{}",
+        check.detail
+    );
+    assert!(check.detail.contains("atelier git setup guard"));
+}
+
+#[test]
+fn unparsable_settings_fail() {
+    let world = World::default();
+    world
+        .settings
+        .set(&format!("{PROJECT}/.claude/settings.json"), "{not json");
+    assert_eq!(world.check("guard-hooks").status, CheckStatus::Fail);
+}
+
+#[test]
+fn drifted_artifact_warns_and_no_plugin_root_skips() {
+    let world = World::default();
+    world
+        .artifacts
+        .insert(USER_CLAUDE_MD, &block("hand-edited style"));
+    let check = world.check("drift");
+    assert_eq!(check.status, CheckStatus::Warn);
+    assert!(check.detail.contains("/atelier:update"));
+
+    let world = World {
+        plugin_root: false,
+        ..World::default()
+    };
+    let check = world.check("drift");
+    assert_eq!(check.status, CheckStatus::Warn);
+    assert!(check.detail.starts_with("skipped"));
+}
+
+#[test]
+fn unwritable_state_dir_fails() {
+    let world = World::default();
+    // A file where the directory should be: neither create nor write works.
+    std::fs::write(&world.state_dir, "").unwrap();
+    let world = World {
+        state_dir: world.state_dir.join("nested"),
+        ..world
+    };
+    assert_eq!(world.check("state-dir").status, CheckStatus::Fail);
+}
+
+#[test]
+fn table_and_json_render_the_same_checks() {
+    let report = World {
+        tools: Tools(&["git"]),
+        ..World::default()
+    }
+    .report();
+    let table = report.render(DoctorFormat::Table);
+    assert!(
+        table.starts_with("CHECK        STATUS  DETAIL\n"),
+        "{table}"
+    );
+    assert!(
+        table.contains("\ngh           warn    `gh` is not on PATH\n"),
+        "{table}"
+    );
+    assert!(
+        table.ends_with("→ 5 passed, 2 warned, 0 failed\n"),
+        "{table}"
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&report.render(DoctorFormat::Json)).unwrap();
+    assert_eq!(json["checks"][1]["name"], "gh");
+    assert_eq!(json["checks"][1]["status"], "warn");
+}
@@ -0,0 +1,56 @@
+//! End-to-end black-box test for `atelier doctor` against the real binary,
+//! with `HOME` and the state dir inside a TempDir. Only the checks the
+//! fixture controls are asserted — whether `gh` exists is the host's business.
+
+use assert_cmd::Command;
+use std::path::Path;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn stale_guard_fails_the_json_report() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(project.join(".claude")).unwrap();
+    git(&project, &["init", "-q", "-b", "main"]);
+    std::fs::write(
+        project.join(".claude/settings.json"),
+        r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"atelier git guard commit"}]}]}}"#,
+    )
+    .unwrap();
+
+    let out = Command::cargo_bin("atelier")
+        .unwrap()
+        .args(["doctor", "--format", "json", "--project-dir"])
+        .arg(&project)
+        .env("HOME", tmp.path())
+        .env("ATELIER_STATE_DIR", tmp.path().join("state"))
+        .env_remove("CLAUDE_PLUGIN_ROOT")
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let status = |name: &str| {
+        json["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .map(|c| c["status"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    assert_eq!(status("git"), "pass");
+    assert_eq!(status("origin-head"), "warn");
+    assert_eq!(status("guard-hooks"), "fail");
+    assert_eq!(status("drift"), "warn");
+    assert_eq!(status("state-dir"), "pass");
+}
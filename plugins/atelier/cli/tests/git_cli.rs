@@ -77,6 +77,117 @@ fn git_pr_guard_alias_non_matching_command_passes() {
         .code(0);
 }
 
+/// A repository on `main` with one commit.
+fn repo_on_main() -> tempfile::TempDir {
+    let tmp = tempfile::TempDir::new().unwrap();
+    for args in [
+        &["init", "-q", "-b", "main"][..],
+        &["commit", "-q", "--allow-empty", "-m", "init"][..],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    tmp
+}
+
+fn guard_commit_json(repo: &std::path::Path) -> serde_json::Value {
+    let out = atelier()
+        .args([
+            "git",
+            "guard",
+            "commit",
+            "--format",
+            "json",
+            "--default-branch",
+            "main",
+        ])
+        .args(["--project-dir", repo.to_str().unwrap()])
+        .write_stdin(r#"{"tool_input":{"command":"git commit -m x"}}"#)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn git_guard_json_denies_on_exit_zero() {
+    let repo = repo_on_main();
+    let json = guard_commit_json(repo.path());
+    assert_eq!(json["hookSpecificOutput"]["permissionDecision"], "deny");
+    assert!(json["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap()
+        .contains("main"));
+}
+
+#[test]
+fn git_guard_json_asks_for_a_commit_mid_rebase() {
+    let repo = repo_on_main();
+    std::fs::create_dir(repo.path().join(".git/rebase-merge")).unwrap();
+    let json = guard_commit_json(repo.path());
+    assert_eq!(json["hookSpecificOutput"]["permissionDecision"], "ask");
+}
+
+#[test]
+fn git_guard_commit_applies_the_project_message_policy() {
+    let repo = repo_on_main();
+    std::process::Command::new("git")
+        .args(["switch", "-q", "-c", "feature/x"])
+        .current_dir(repo.path())
+        .status()
+        .unwrap();
+    std::fs::create_dir(repo.path().join(".claude")).unwrap();
+    std::fs::write(
+        repo.path().join(".claude/atelier.json"),
+        r#"{"git":{"commit_message":{"conventional":true}}}"#,
+    )
+    .unwrap();
+    std::fs::write(repo.path().join("MSG"), "update stuff\n").unwrap();
+    let out = atelier()
+        .args(["git", "guard", "commit", "--format", "json"])
+        .args(["--project-dir", repo.path().to_str().unwrap()])
+        .write_stdin(r#"{"tool_input":{"command":"git commit -F MSG"}}"#)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["hookSpecificOutput"]["permissionDecision"], "deny");
+    assert!(json["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap()
+        .starts_with("[Commit Message Guard]"));
+}
+
+#[test]
+fn git_guard_json_pass_prints_nothing() {
+    atelier()
+        .args(["git", "guard", "pr", "--format", "json"])
+        .write_stdin(r#"{"tool_input":{"command":"echo hello"}}"#)
+        .assert()
+        .code(0)
+        .stdout("");
+}
+
+#[test]
+fn git_cache_clear_reports_removed_entries() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    atelier()
+        .env("ATELIER_STATE_DIR", tmp.path())
+        .args(["git", "cache", "clear"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""cleared": 0"#));
+}
+
 #[test]
 fn git_hook_list_empty_in_temp_project() {
     // Point project-dir at a fresh temp dir so there is no settings.json; list
@@ -11,6 +11,8 @@ use atelier::git::types::{
     GuardCommandTarget, GuardDecision, GuardInput, GuardOutput, GuardTarget, PrGuardInput,
     PrGuardOutput,
 };
+use atelier::shared::config::CommitMessageConfig;
+use atelier::shared::hook_output::PermissionDecision;
 
 /// Branch guard stub: blocks, echoing the received target in the reason so
 /// tests can assert what reached the service.
@@ -20,9 +22,11 @@ impl GuardService for StubBranchGuard {
     fn check(&self, input: &GuardInput) -> GuardOutput {
         GuardOutput {
             allowed: false,
+            ask: false,
             reason: Some(format!("branch-guard: {:?}", input.target)),
             current_branch: None,
             default_branch: None,
+            in_progress: None,
         }
     }
 }
@@ -54,6 +58,7 @@ fn input_with(target: GuardCommandTarget) -> GuardCommandInput {
         create_branch_script: "git switch -c".to_string(),
         default_branch: None,
         protected_branches: None,
+        commit_message: CommitMessageConfig::default(),
     }
 }
 
@@ -181,12 +186,73 @@ fn guard_target_kind_binds_only_its_payload_field() {
 fn guard_decision_exit_code_maps_hook_contract() {
     let allow = GuardDecision {
         allowed: true,
+        ask: false,
         reason: None,
+        context: None,
     };
     let block = GuardDecision {
         allowed: false,
+        ask: false,
         reason: Some("blocked".to_string()),
+        context: None,
     };
     assert_eq!(allow.exit_code(), 0);
     assert_eq!(block.exit_code(), 2);
 }
+
+#[test]
+fn guard_decision_json_maps_block_ask_and_pass() {
+    let decision = |allowed, ask| GuardDecision {
+        allowed,
+        ask,
+        reason: Some("why".to_string()),
+        context: None,
+    };
+    let deny = decision(false, false).hook_output();
+    let specific = deny.hook_specific_output.unwrap();
+    assert_eq!(specific.hook_event_name, "PreToolUse");
+    assert_eq!(specific.permission_decision, Some(PermissionDecision::Deny));
+    assert_eq!(specific.permission_decision_reason.as_deref(), Some("why"));
+
+    let ask = decision(true, true);
+    assert_eq!(ask.permission(), Some(PermissionDecision::Ask));
+    // The exit-code contract has no third outcome: an ask passes there.
+    assert_eq!(ask.exit_code(), 0);
+
+    assert_eq!(decision(true, false).hook_output().render(), None);
+}
+
+#[test]
+fn guard_decision_context_carries_the_git_facts_of_a_block_or_ask() {
+    let output = |allowed, ask, in_progress| GuardOutput {
+        allowed,
+        ask,
+        reason: Some("why".to_string()),
+        current_branch: Some("main".to_string()),
+        default_branch: Some("main".to_string()),
+        in_progress,
+    };
+    let ask = GuardDecision::from(output(true, true, Some("merge"))).hook_output();
+    assert_eq!(
+        ask.hook_specific_output
+            .unwrap()
+            .additional_context
+            .as_deref(),
+        Some("[atelier guard] current branch: main, default branch: main, merge in progress")
+    );
+    let deny: GuardDecision = output(false, false, None).into();
+    assert_eq!(
+        deny.context.as_deref(),
+        Some("[atelier guard] current branch: main, default branch: main")
+    );
+    // A pass has nothing to explain.
+    assert_eq!(GuardDecision::from(output(true, false, None)).context, None);
+
+    let pr: GuardDecision = StubPrGuard
+        .check(&PrGuardInput { tool_command: None })
+        .into();
+    assert_eq!(
+        pr.context.as_deref(),
+        Some("[atelier guard] open PR for this branch: #7")
+    );
+}
@@ -2,11 +2,14 @@
 //! bash an LLM used to re-execute on every setup. What is pinned here is the
 //! part that was fragile by hand: a detection failure must *omit* the flag
 //! rather than emit a bare one, the project-dir placeholder must survive
-//! verbatim, and a re-run must not leave a second guard behind.
+//! verbatim, and a re-run must not leave a second guard behind — neither a
+//! second dispatcher nor a per-guard entry from the earlier generation.
 
 mod git_mocks;
 
-use atelier::git::commands::guard_setup::{run, GuardSetupDeps, GuardSetupInput};
+use atelier::git::commands::guard_setup::{
+    guard_generation, run, GuardGeneration, GuardSetupDeps, GuardSetupInput,
+};
 use atelier::git::commands::hook::create_hook_command;
 use atelier::git::types::{CmdResult, GuardSetupOutput, HookScope};
 use git_mocks::{MockFs, MockGit, MockGitHub, MockWarmer, Recorder};
@@ -247,7 +250,7 @@ fn preserves_literal_claude_project_dir_placeholder() {
 }
 
 #[test]
-fn registers_both_write_and_commit_guards_in_single_write() {
+fn registers_one_dispatch_entry_for_every_guard_in_single_write() {
     let fs = MockFs::new();
     let out = ok(setup(
         &fs,
@@ -256,8 +259,8 @@ fn registers_both_write_and_commit_guards_in_single_write() {
             ..Default::default()
         },
     ));
-    // One write: two `register` calls would leave a half-registered file if the
-    // second failed.
+    // One write: a purge and a `register` call apart would leave a
+    // half-migrated file if the second failed.
     assert_eq!(fs.write_count(), 1);
 
     let settings = written(&fs, &out);
@@ -266,11 +269,46 @@ fn registers_both_write_and_commit_guards_in_single_write() {
         .iter()
         .map(|g| g["matcher"].as_str().unwrap())
         .collect();
-    assert_eq!(matchers, vec!["Write|Edit", "Bash"]);
+    assert_eq!(matchers, vec!["Write|Edit|MultiEdit|Bash"]);
 
     let commands = registered_commands(&settings);
-    assert!(commands[0].starts_with("atelier git guard write "));
-    assert!(commands[1].starts_with("atelier git guard commit "));
+    assert_eq!(commands.len(), 1);
+    assert!(commands[0].starts_with("atelier hook-dispatch "));
+}
+
+#[test]
+fn registered_commands_classify_as_the_current_generation() {
+    // `doctor` judges settings.json with `guard_generation`; whatever setup
+    // writes, pinned or not, must read back as current.
+    for git in [MockGit::default(), git_without_detection()] {
+        let out = ok(setup(
+            &MockFs::new(),
+            Scenario {
+                git,
+                ..Default::default()
+            },
+        ));
+        let generations: Vec<_> = out.commands.iter().map(|c| guard_generation(c)).collect();
+        assert_eq!(generations, vec![Some(GuardGeneration::Current)]);
+    }
+    // Per-guard entries would run a second time next to the dispatcher
+    for stale in [
+        r#"atelier git guard write --project-dir "${CLAUDE_PROJECT_DIR:-.}""#,
+        "atelier git guard commit --default-branch main",
+        "atelier git guard pr",
+        "atelier git pr-guard",
+    ] {
+        assert_eq!(
+            guard_generation(stale),
+            Some(GuardGeneration::Stale),
+            "{stale}"
+        );
+    }
+    assert_eq!(
+        guard_generation("atelier hook-dispatch"),
+        Some(GuardGeneration::Current)
+    );
+    assert_eq!(guard_generation("atelier hook-dispatcher"), None);
 }
 
 // ---- warm-up ----
@@ -289,6 +327,7 @@ fn warm_up_runs_before_detection_against_project_dir() {
             warm_calls.push("warm_origin_head");
             true
         }),
+        ..MockWarmer::default()
     };
     let gh = MockGitHub {
         default_branch: Box::new(move || {
@@ -339,6 +378,7 @@ fn warm_up_failure_does_not_abort_setup() {
     let fs = MockFs::new();
     let warmer = MockWarmer {
         warm_origin_head: Box::new(|| false),
+        ..MockWarmer::default()
     };
     let out = ok(setup(
         &fs,
@@ -349,8 +389,8 @@ fn warm_up_failure_does_not_abort_setup() {
         },
     ));
     assert!(!out.origin_head_warmed);
-    assert_eq!(out.commands.len(), 2);
-    assert_eq!(registered_commands(&written(&fs, &out)).len(), 2);
+    assert_eq!(out.commands.len(), 1);
+    assert_eq!(registered_commands(&written(&fs, &out)).len(), 1);
 }
 
 // ---- idempotency and migration ----
@@ -377,8 +417,8 @@ fn rerun_is_idempotent_no_duplicate_hooks() {
     let commands = registered_commands(&written(&fs, &out));
     assert_eq!(
         commands.len(),
-        2,
-        "expected exactly two guards: {commands:?}"
+        1,
+        "expected exactly one dispatch entry: {commands:?}"
     );
 }
 
@@ -391,6 +431,8 @@ fn removes_stale_guard_entry_with_previous_pin() {
     let fs = MockFs::new();
     let stale_write = r#"atelier git guard write --project-dir \"${CLAUDE_PROJECT_DIR:-.}\" --default-branch main"#;
     let stale_commit = r#"atelier git guard commit --project-dir \"${CLAUDE_PROJECT_DIR:-.}\" --default-branch main"#;
+    let stale_dispatch =
+        r#"atelier hook-dispatch --project-dir \"${CLAUDE_PROJECT_DIR:-.}\" --default-branch main"#;
     fs.set(
         &format!("{PROJECT_DIR}/.claude/settings.json"),
         &format!(
@@ -398,8 +440,10 @@ fn removes_stale_guard_entry_with_previous_pin() {
                 {{"matcher":"Write|Edit","hooks":[{{"type":"command","command":"{stale_write}"}}]}},
                 {{"matcher":"Bash","hooks":[
                     {{"type":"command","command":"{stale_commit}"}},
+                    {{"type":"command","command":"atelier git guard pr"}},
                     {{"type":"command","command":"protect-stagnation.sh"}}
-                ]}}
+                ]}},
+                {{"matcher":"Write|Edit|MultiEdit|Bash","hooks":[{{"type":"command","command":"{stale_dispatch}"}}]}}
             ]}}}}"#
         ),
     );
@@ -425,23 +469,22 @@ fn removes_stale_guard_entry_with_previous_pin() {
     ));
     assert_eq!(
         out.removed.len(),
-        2,
-        "both stale pins retired: {:?}",
+        4,
+        "per-guard entries and the stale pin retired: {:?}",
         out.removed
     );
 
+    // The dispatcher runs every guard; a surviving per-guard entry would run
+    // its guard twice.
     let commands = registered_commands(&written(&fs, &out));
-    assert_eq!(
-        commands
-            .iter()
-            .filter(|c| c.contains("guard write"))
-            .count(),
-        1
+    assert!(
+        commands.iter().all(|c| !c.contains("atelier git guard")),
+        "per-guard entries must be retired: {commands:?}"
     );
     assert_eq!(
         commands
             .iter()
-            .filter(|c| c.contains("guard commit"))
+            .filter(|c| c.starts_with("atelier hook-dispatch"))
             .count(),
         1
     );
@@ -472,6 +515,6 @@ fn dry_run_does_not_write_settings() {
     assert_eq!(fs.write_count(), 0);
     assert!(fs.get(&out.settings_path).is_none());
     // The plan is still fully reported.
-    assert_eq!(out.commands.len(), 2);
+    assert_eq!(out.commands.len(), 1);
     assert_eq!(out.default_branch, Some("main".to_string()));
 }
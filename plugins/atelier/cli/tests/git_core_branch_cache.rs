@@ -0,0 +1,183 @@
+//! Default-branch cache: the decorator answers from the cache while the
+//! repository fingerprint holds, and re-detects once anything detection reads
+//! has moved. Repositories are bare `.git` layouts in a TempDir — the
+//! fingerprint reads files, never git.
+
+mod git_mocks;
+
+use atelier::git::core::branch_cache::{
+    create_cached_git_service, BranchCache, RepoFingerprint, SqliteBranchCache, CACHE_TTL,
+};
+use atelier::git::core::git::GitService;
+use git_mocks::MockGit;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+struct Fixture {
+    tmp: tempfile::TempDir,
+}
+
+impl Fixture {
+    /// A repository at `repo/` with a `.git` holding `HEAD` and `packed-refs`.
+    fn new() -> Self {
+        let fx = Fixture {
+            tmp: tempfile::TempDir::new().unwrap(),
+        };
+        std::fs::create_dir_all(fx.git_dir().join("refs/remotes/origin")).unwrap();
+        std::fs::write(fx.git_dir().join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(fx.git_dir().join("packed-refs"), "").unwrap();
+        fx
+    }
+
+    fn repo(&self) -> PathBuf {
+        self.tmp.path().join("repo")
+    }
+
+    fn git_dir(&self) -> PathBuf {
+        self.repo().join(".git")
+    }
+
+    fn cache(&self, ttl: Duration) -> SqliteBranchCache {
+        SqliteBranchCache::new(self.tmp.path().join("state/atelier.db"), ttl)
+    }
+}
+
+/// Rewrites `path` with an mtime an hour in the future, so the change is
+/// visible whatever the filesystem's timestamp granularity.
+fn touch(path: &Path, content: &str) {
+    std::fs::write(path, content).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(3600))
+        .unwrap();
+}
+
+/// A MockGit whose detection answers `branch` and counts its calls.
+fn counting_git(branch: &'static str) -> (MockGit, Rc<Cell<usize>>) {
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    let git = MockGit {
+        detect_default_branch: Box::new(move || {
+            counter.set(counter.get() + 1);
+            Ok(branch.to_string())
+        }),
+        ..MockGit::default()
+    };
+    (git, calls)
+}
+
+#[test]
+fn unchanged_repository_is_detected_once() {
+    let fx = Fixture::new();
+    let cache = fx.cache(CACHE_TTL);
+    let (git, calls) = counting_git("trunk");
+    let cached = create_cached_git_service(&git, &cache, fx.repo());
+
+    assert_eq!(cached.detect_default_branch().unwrap(), "trunk");
+    assert_eq!(cached.detect_default_branch().unwrap(), "trunk");
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn the_cache_outlives_the_process_that_filled_it() {
+    let fx = Fixture::new();
+    let (git, calls) = counting_git("trunk");
+    for _ in 0..2 {
+        let cache = fx.cache(CACHE_TTL);
+        let cached = create_cached_git_service(&git, &cache, fx.repo());
+        cached.detect_default_branch().unwrap();
+    }
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn moved_refs_invalidate_the_entry() {
+    let fx = Fixture::new();
+    let cache = fx.cache(CACHE_TTL);
+    let (git, calls) = counting_git("main");
+    let cached = create_cached_git_service(&git, &cache, fx.repo());
+
+    cached.detect_default_branch().unwrap();
+    touch(
+        &fx.git_dir().join("packed-refs"),
+        "abc refs/remotes/origin/main\n",
+    );
+    cached.detect_default_branch().unwrap();
+    assert_eq!(calls.get(), 2);
+
+    std::fs::write(
+        fx.git_dir().join("refs/remotes/origin/HEAD"),
+        "ref: refs/remotes/origin/trunk\n",
+    )
+    .unwrap();
+    cached.detect_default_branch().unwrap();
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn expired_entries_are_re_detected() {
+    let fx = Fixture::new();
+    let cache = fx.cache(Duration::ZERO);
+    let (git, calls) = counting_git("main");
+    let cached = create_cached_git_service(&git, &cache, fx.repo());
+    cached.detect_default_branch().unwrap();
+    cached.detect_default_branch().unwrap();
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn failed_detection_is_not_cached() {
+    let fx = Fixture::new();
+    let cache = fx.cache(CACHE_TTL);
+    let git = MockGit {
+        detect_default_branch: Box::new(|| Err("no remote".to_string())),
+        ..MockGit::default()
+    };
+    let cached = create_cached_git_service(&git, &cache, fx.repo());
+    assert!(cached.detect_default_branch().is_err());
+    assert_eq!(cache.clear().unwrap(), 0);
+}
+
+#[test]
+fn outside_a_repository_detection_passes_through() {
+    let fx = Fixture::new();
+    let cache = fx.cache(CACHE_TTL);
+    let (git, calls) = counting_git("main");
+    let cached = create_cached_git_service(&git, &cache, fx.tmp.path());
+    cached.detect_default_branch().unwrap();
+    cached.detect_default_branch().unwrap();
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn worktrees_share_their_repository_entry() {
+    let fx = Fixture::new();
+    let worktree_git = fx.git_dir().join("worktrees/wt");
+    std::fs::create_dir_all(&worktree_git).unwrap();
+    std::fs::write(worktree_git.join("commondir"), "../..\n").unwrap();
+    let worktree = fx.tmp.path().join("wt");
+    std::fs::create_dir_all(worktree.join("src")).unwrap();
+    std::fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", worktree_git.display()),
+    )
+    .unwrap();
+
+    let main = RepoFingerprint::read(&fx.repo()).unwrap();
+    let linked = RepoFingerprint::read(&worktree.join("src")).unwrap();
+    assert_eq!(main, linked);
+}
+
+#[test]
+fn clear_reports_what_it_removed() {
+    let fx = Fixture::new();
+    let cache = fx.cache(CACHE_TTL);
+    let (git, calls) = counting_git("main");
+    let cached = create_cached_git_service(&git, &cache, fx.repo());
+    cached.detect_default_branch().unwrap();
+
+    assert_eq!(cache.clear().unwrap(), 1);
+    cached.detect_default_branch().unwrap();
+    assert_eq!(calls.get(), 2);
+}
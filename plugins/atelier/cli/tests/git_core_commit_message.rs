@@ -0,0 +1,109 @@
+//! Rule-by-rule tests for the commit-message policy
+//! (`git::core::commit_message::violations`) and its block message.
+
+use atelier::git::core::commit_message::{render_reason, violations};
+use atelier::shared::config::CommitMessageConfig;
+
+fn policy() -> CommitMessageConfig {
+    CommitMessageConfig::default()
+}
+
+#[test]
+fn empty_policy_accepts_anything() {
+    assert!(violations(&policy(), "wip", Some("main")).is_empty());
+}
+
+#[test]
+fn conventional_accepts_type_scope_and_breaking_marker() {
+    let p = CommitMessageConfig {
+        conventional: true,
+        ..policy()
+    };
+    for ok in [
+        "feat: add x",
+        "fix(parser): handle y",
+        "refactor!: drop z",
+        "\nchore(deps)!: bump\n\nbody",
+    ] {
+        assert!(violations(&p, ok, None).is_empty(), "{ok}");
+    }
+    for bad in ["add x", "feat:add x", "Feat: add x", "feature: add x", ""] {
+        assert_eq!(violations(&p, bad, None).len(), 1, "{bad}");
+    }
+}
+
+#[test]
+fn conventional_uses_configured_types_and_exempts_git_subjects() {
+    let p = CommitMessageConfig {
+        conventional: true,
+        types: vec!["feature".to_string()],
+        ..policy()
+    };
+    assert!(violations(&p, "feature: x", None).is_empty());
+    assert!(!violations(&p, "feat: x", None).is_empty());
+    assert!(violations(&p, "fixup! feature: x", None).is_empty());
+    assert!(violations(&p, "Merge branch 'a' into b", None).is_empty());
+    assert!(violations(&p, "Revert \"feature: x\"", None).is_empty());
+}
+
+#[test]
+fn issue_pattern_matches_anywhere_in_the_message() {
+    let p = CommitMessageConfig {
+        issue_pattern: Some(r"[A-Z]+-\d+".to_string()),
+        ..policy()
+    };
+    assert!(violations(&p, "fix: y\n\nRefs ABC-12", None).is_empty());
+    assert_eq!(violations(&p, "fix: y", None).len(), 1);
+}
+
+#[test]
+fn invalid_issue_pattern_is_ignored() {
+    let p = CommitMessageConfig {
+        issue_pattern: Some("(".to_string()),
+        ..policy()
+    };
+    assert!(violations(&p, "fix: y", None).is_empty());
+}
+
+#[test]
+fn subject_length_counts_characters_of_the_subject_only() {
+    let p = CommitMessageConfig {
+        max_subject_length: Some(10),
+        ..policy()
+    };
+    assert!(violations(&p, "가나다라마바사아자차\n\na much longer body line", None).is_empty());
+    assert_eq!(violations(&p, "eleven char", None).len(), 1);
+}
+
+#[test]
+fn wip_is_refused_only_on_a_protected_branch() {
+    let p = CommitMessageConfig {
+        no_wip_on_protected: true,
+        ..policy()
+    };
+    assert_eq!(violations(&p, "WIP: half done", Some("main")).len(), 1);
+    assert_eq!(violations(&p, "chore: wip", Some("main")).len(), 1);
+    assert!(violations(&p, "WIP: half done", None).is_empty());
+    assert!(violations(&p, "fix: wipe cache", Some("main")).is_empty());
+}
+
+#[test]
+fn ai_trailers_are_each_reported() {
+    let p = CommitMessageConfig {
+        no_ai_trailers: true,
+        ..policy()
+    };
+    let message =
+        "feat: x\n\n🤖 Generated with Claude\n\nCo-Authored-By: Claude <noreply@anthropic.com>";
+    assert_eq!(violations(&p, message, None).len(), 2);
+    assert!(violations(&p, "feat: x\n\nCo-authored-by: Kim <kim@example.com>", None).is_empty());
+}
+
+#[test]
+fn reason_lists_every_violation() {
+    let reason = render_reason(&["a".to_string(), "b".to_string()]);
+    assert_eq!(
+        reason,
+        "[Commit Message Guard] 커밋 메시지가 프로젝트 규칙에 맞지 않습니다.\n  - a\n  - b\n메시지를 고친 뒤 다시 커밋해주세요."
+    );
+}
@@ -8,6 +8,7 @@ use atelier::git::core::guard::{
     create_guard_service, is_inside_any_git_repo, is_inside_project_dir, GuardService,
 };
 use atelier::git::types::{GuardInput, GuardTarget};
+use atelier::shared::config::CommitMessageConfig;
 use git_mocks::MockGit;
 
 fn base_input() -> GuardInput {
@@ -17,6 +18,7 @@ fn base_input() -> GuardInput {
         create_branch_script: "git switch -c".to_string(),
         default_branch: None,
         protected_branches: None,
+        commit_message: CommitMessageConfig::default(),
     }
 }
 
@@ -46,6 +48,52 @@ fn merge_passes() {
     assert!(check(git, &base_input()).allowed);
 }
 
+fn commit_input() -> GuardInput {
+    let mut input = base_input();
+    input.target = GuardTarget::Commit {
+        command: Some("git commit -m \"wip\"".to_string()),
+    };
+    input
+}
+
+#[test]
+fn rebase_commit_asks() {
+    // Mid-rebase HEAD is detached, so the branch the commit lands on is unknown.
+    let mut git = MockGit::default();
+    git.special_state_flags = Box::new(|| (true, false));
+    git.current_branch = Box::new(String::new);
+    let out = check(git, &commit_input());
+    assert!(out.allowed && out.ask);
+    assert!(out.reason.unwrap().contains("rebase"));
+    assert_eq!(out.in_progress, Some("rebase"));
+}
+
+#[test]
+fn merge_commit_on_protected_branch_asks() {
+    let mut git = MockGit::default();
+    git.special_state_flags = Box::new(|| (false, true));
+    let out = check(git, &commit_input());
+    assert!(out.allowed && out.ask);
+    assert_eq!(out.in_progress, Some("merge"));
+}
+
+#[test]
+fn merge_commit_on_feature_branch_passes_without_asking() {
+    let mut git = MockGit::default();
+    git.special_state_flags = Box::new(|| (false, true));
+    git.current_branch = Box::new(|| "feat/x".to_string());
+    let out = check(git, &commit_input());
+    assert!(out.allowed && !out.ask);
+}
+
+#[test]
+fn rebase_write_passes_without_asking() {
+    let mut git = MockGit::default();
+    git.special_state_flags = Box::new(|| (true, false));
+    let out = check(git, &base_input());
+    assert!(out.allowed && !out.ask);
+}
+
 #[test]
 fn detached_passes() {
     // Detached HEAD: `git branch --show-current` prints nothing.
@@ -420,3 +468,78 @@ fn relative_file_path_outside_project_on_default_blocks() {
     };
     assert!(!check(MockGit::default(), &input).allowed);
 }
+
+fn policy_input(command: &str) -> GuardInput {
+    let mut input = base_input();
+    input.target = GuardTarget::Commit {
+        command: Some(command.to_string()),
+    };
+    input.commit_message = CommitMessageConfig {
+        conventional: true,
+        no_wip_on_protected: true,
+        ..CommitMessageConfig::default()
+    };
+    input
+}
+
+fn on_feature() -> MockGit {
+    let mut git = MockGit::default();
+    git.current_branch = Box::new(|| "feature/x".to_string());
+    git
+}
+
+#[test]
+fn commit_message_policy_blocks_on_a_feature_branch() {
+    let out = check(on_feature(), &policy_input("git commit -m 'add thing'"));
+    assert!(!out.allowed);
+    let reason = out.reason.unwrap();
+    assert!(reason.starts_with("[Commit Message Guard]"), "{reason}");
+    assert!(reason.contains("Conventional Commits"), "{reason}");
+    assert_eq!(out.current_branch.as_deref(), Some("feature/x"));
+}
+
+#[test]
+fn commit_message_policy_passes_a_conforming_message() {
+    let out = check(
+        on_feature(),
+        &policy_input("git commit -m 'feat: add thing'"),
+    );
+    assert!(out.allowed);
+    assert_eq!(out.reason, None);
+}
+
+#[test]
+fn commit_message_policy_ignores_wip_off_protected_branches() {
+    let out = check(on_feature(), &policy_input("git commit -m 'chore: wip'"));
+    assert!(out.allowed);
+}
+
+#[test]
+fn commit_message_violations_join_the_branch_block_reason() {
+    let out = check(
+        MockGit::default(),
+        &policy_input("git commit -m 'chore: wip'"),
+    );
+    assert!(!out.allowed);
+    let reason = out.reason.unwrap();
+    assert!(reason.starts_with("[Branch Guard]"), "{reason}");
+    assert!(reason.contains("\n\n[Commit Message Guard]"), "{reason}");
+    assert!(reason.contains("보호 브랜치(main)에 WIP"), "{reason}");
+}
+
+#[test]
+fn commit_message_policy_overrides_a_rebase_ask() {
+    let mut git = MockGit::default();
+    git.special_state_flags = Box::new(|| (true, false));
+    let out = check(git, &policy_input("git commit -m 'wip'"));
+    assert!(!out.allowed);
+    assert!(!out.ask);
+}
+
+#[test]
+fn unreadable_message_leaves_the_branch_decision_alone() {
+    let out = check(on_feature(), &policy_input("git commit"));
+    assert!(out.allowed);
+    let out = check(on_feature(), &policy_input("git commit -m \"$(generate)\""));
+    assert!(out.allowed);
+}
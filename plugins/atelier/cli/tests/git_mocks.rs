@@ -14,7 +14,9 @@ use std::collections::HashMap;
 
 use atelier::git::commands::hook::HookFs;
 use atelier::git::core::git::{GitService, OriginHeadWarmer};
-use atelier::git::core::github::{GitHubService, RepoDefaultBranch, ReviewThreadsResult};
+use atelier::git::core::github::{
+    GhAuth, GhAuthStatus, GitHubService, RepoDefaultBranch, ReviewThreadsResult,
+};
 use atelier::git::types::{DetectedBranch, GitSpecialState};
 
 type R<T> = Result<T, String>;
@@ -122,6 +124,7 @@ pub struct MockGitHub {
     /// through the same `DetectedBranch::new` funnel the real service does —
     /// a blank answer must collapse to absence here exactly as it would live.
     pub default_branch: Box<dyn Fn() -> Option<String>>,
+    pub auth_status: Box<dyn Fn() -> GhAuthStatus>,
 }
 
 impl Default for MockGitHub {
@@ -136,6 +139,11 @@ impl Default for MockGitHub {
             }),
             detect_current_pr_number: Box::new(|| Ok(None)),
             default_branch: Box::new(|| None),
+            auth_status: Box::new(|| GhAuthStatus {
+                host: Some("github.com".to_string()),
+                authenticated: true,
+                detail: String::new(),
+            }),
         }
     }
 }
@@ -157,15 +165,24 @@ impl RepoDefaultBranch for MockGitHub {
     }
 }
 
-/// Mockable `OriginHeadWarmer`. Defaults to a successful warm-up.
+impl GhAuth for MockGitHub {
+    fn auth_status(&self) -> GhAuthStatus {
+        (self.auth_status)()
+    }
+}
+
+/// Mockable `OriginHeadWarmer`. Defaults to a successful warm-up and an
+/// `origin/HEAD` already naming `main`.
 pub struct MockWarmer {
     pub warm_origin_head: Box<dyn Fn() -> bool>,
+    pub origin_head: Box<dyn Fn() -> Option<String>>,
 }
 
 impl Default for MockWarmer {
     fn default() -> Self {
         MockWarmer {
             warm_origin_head: Box::new(|| true),
+            origin_head: Box::new(|| Some("main".to_string())),
         }
     }
 }
@@ -174,6 +191,9 @@ impl OriginHeadWarmer for MockWarmer {
     fn warm_origin_head(&self) -> bool {
         (self.warm_origin_head)()
     }
+    fn origin_head(&self) -> Option<String> {
+        (self.origin_head)()
+    }
 }
 
 /// Records the arguments a mock receives, for tests that pin call order or the
@@ -0,0 +1,147 @@
+//! The library entry points — `drift::check`, `git::guard::evaluate`,
+//! `session::simplify::evaluate` — called the way an embedding tool would,
+//! against real TempDir fixtures and real git instead of the binary.
+
+mod drift_mocks;
+
+use atelier::drift::core::types::{
+    ArtifactStatus, DriftPaths, RULES_COPY_REL, TEMPLATE_CLAUDE_MD_REL, TEMPLATE_RULES_REL,
+};
+use atelier::git::guard::{GuardConfig, GuardTargetKind, HookPayload};
+use atelier::session::simplify::{SilentReason, SimplifyDecision};
+use drift_mocks::{block, RULES_BODY};
+use std::path::Path;
+
+fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+/// A repository on `main` with one commit.
+fn repo(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    write(&dir.join("README.md"), "# fixture\n");
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_str().unwrap().to_string()
+}
+
+/// Set in the child `isolated` spawns, where the test body really runs.
+const ISOLATED_ENV: &str = "ATELIER_LIB_API_ISOLATED";
+
+/// Runs `body` — the test named `test` — in a child copy of this test binary
+/// whose `ATELIER_STATE_DIR` is a fresh directory. The library entry points
+/// read the state root from the environment, and setting it here would leak
+/// into every test running alongside (or leave them on the real one).
+fn isolated(test: &str, body: impl FnOnce()) {
+    if std::env::var_os(ISOLATED_ENV).is_some() {
+        return body();
+    }
+    let state = tempfile::TempDir::new().unwrap();
+    let out = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture"])
+        .env(ISOLATED_ENV, "1")
+        .env("ATELIER_STATE_DIR", state.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success() && stdout.contains("1 passed"),
+        "{test} in isolated state:\n{stdout}{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn drift_check_reports_installed_and_missing_artifacts() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let root = tmp.path();
+    write(
+        &root.join("plugin").join(TEMPLATE_CLAUDE_MD_REL),
+        &block("style\n"),
+    );
+    write(&root.join("plugin").join(TEMPLATE_RULES_REL), RULES_BODY);
+    write(&root.join("proj").join(RULES_COPY_REL), RULES_BODY);
+    let paths = DriftPaths {
+        plugin_root: path_str(&root.join("plugin")),
+        claude_md: path_str(&root.join("home/CLAUDE.md")),
+        project_dir: path_str(&root.join("proj")),
+    };
+
+    let report = atelier::drift::check(&paths).unwrap();
+    let statuses: Vec<ArtifactStatus> = report.findings.iter().map(|f| f.status).collect();
+    assert_eq!(
+        statuses,
+        vec![ArtifactStatus::NotInstalled, ArtifactStatus::Ok]
+    );
+    assert_eq!(report.exit_code(), 0);
+}
+
+#[test]
+fn guard_evaluate_blocks_writes_on_the_default_branch() {
+    // Default-branch detection goes through the per-user branch cache.
+    isolated("guard_evaluate_blocks_writes_on_the_default_branch", || {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        repo(&project);
+        // No remote to detect the default branch from, so name it.
+        let config = GuardConfig {
+            default_branch: Some("main".to_string()),
+            ..GuardConfig::new(GuardTargetKind::Write, path_str(&project))
+        };
+        let payload = HookPayload {
+            command: None,
+            file_path: Some(path_str(&project.join("src/lib.rs"))),
+        };
+
+        let decision = atelier::git::guard::evaluate(payload, &config);
+        assert!(!decision.allowed);
+        assert_eq!(decision.exit_code(), 2);
+
+        git(&project, &["checkout", "-q", "-b", "feature"]);
+        let payload = HookPayload {
+            command: None,
+            file_path: Some(path_str(&project.join("src/lib.rs"))),
+        };
+        assert!(atelier::git::guard::evaluate(payload, &config).allowed);
+    });
+}
+
+#[test]
+fn simplify_evaluate_anchors_then_notifies_once() {
+    isolated("simplify_evaluate_anchors_then_notifies_once", || {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        repo(&project);
+        let project = path_str(&project);
+        let id = "sess-lib-api-01";
+
+        assert_eq!(
+            atelier::session::simplify::evaluate(&project, id),
+            SimplifyDecision::Silent(SilentReason::NoBaseline)
+        );
+        write(&Path::new(&project).join("src/main.rs"), "fn main() {}\n");
+        assert!(matches!(
+            atelier::session::simplify::evaluate(&project, id),
+            SimplifyDecision::Notify { .. }
+        ));
+        assert_eq!(
+            atelier::session::simplify::evaluate(&project, id),
+            SimplifyDecision::Silent(SilentReason::AlreadyNotified)
+        );
+    });
+}
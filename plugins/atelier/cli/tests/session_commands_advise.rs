@@ -0,0 +1,196 @@
+//! Black-box tests for the Stop-time advisors and the `session advise` run:
+//! each built-in rule, once-per-session state per advisor, and per-project
+//! switches. Store, repository and transcript are in-memory doubles.
+
+mod session_mocks;
+
+use atelier::session::commands::advise::{run, run_with, Advice};
+use atelier::session::commands::advisors::large_diff::LargeDiffAdvisor;
+use atelier::session::commands::advisors::lockfile::LockfileAdvisor;
+use atelier::session::commands::advisors::tests_not_run::TestsNotRunAdvisor;
+use atelier::session::commands::advisors::{Advisor, AdvisorDecision, AdvisorInput};
+use atelier::session::commands::SessionDeps;
+use atelier::session::core::baseline::BaselineStore;
+use atelier::shared::config::{ProjectConfig, SessionConfig};
+use session_mocks::{
+    baseline, bash, edit, lines, paths, MemRepo, MemStore, MemTranscript, SESSION,
+};
+use std::collections::BTreeMap;
+
+fn ids(advice: &[Advice]) -> Vec<&'static str> {
+    advice.iter().map(|a| a.advisor).collect()
+}
+
+fn changed(files: &[&str]) -> AdvisorInput {
+    AdvisorInput {
+        changes: paths(files),
+        ..AdvisorInput::default()
+    }
+}
+
+#[test]
+fn each_advisor_speaks_once_per_session() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs", "Cargo.lock"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let transcript = MemTranscript::of(vec![edit("/p/src/lib.rs")]);
+    let config = SessionConfig::default();
+
+    let first = run(&deps, &transcript, &config, SESSION);
+    assert_eq!(
+        ids(&first),
+        vec!["simplify", "tests-not-run", "lockfile-without-manifest"]
+    );
+    let stored = store.load(SESSION).unwrap();
+    // `/simplify` keeps the legacy flag so `simplify-check` agrees with it.
+    assert!(stored.notified);
+    assert!(stored.is_advised("tests-not-run"));
+
+    assert!(run(&deps, &transcript, &config, SESSION).is_empty());
+}
+
+#[test]
+fn disabled_advisor_never_runs_or_records() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let config = ProjectConfig::parse(r#"{"session":{"advisors":{"simplify":false}}}"#).session;
+
+    let advice = run(&deps, &MemTranscript::default(), &config, SESSION);
+    assert!(advice.is_empty());
+    assert!(!store.load(SESSION).unwrap().notified);
+}
+
+#[test]
+fn silent_without_baseline_and_records_it() {
+    let store = MemStore::default();
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let advice = run_with(
+        &deps,
+        &MemTranscript::default(),
+        &SessionConfig::default(),
+        SESSION,
+        &[&LargeDiffAdvisor],
+    );
+    assert!(advice.is_empty());
+    assert!(store.load(SESSION).is_some());
+}
+
+#[test]
+fn tests_not_run_after_last_code_edit() {
+    let mut input = changed(&["src/lib.rs"]);
+    input.transcript = MemTranscript::of(vec![
+        bash("cargo test"),
+        edit("/p/src/lib.rs"),
+        bash("git status"),
+    ])
+    .0;
+    assert!(matches!(
+        TestsNotRunAdvisor.decide(&input),
+        AdvisorDecision::Notify(_)
+    ));
+
+    input.transcript = MemTranscript::of(vec![edit("/p/src/lib.rs"), bash("cargo test -p x")]).0;
+    assert_eq!(TestsNotRunAdvisor.decide(&input), AdvisorDecision::Silent);
+}
+
+#[test]
+fn tests_not_run_ignores_doc_edits_and_missing_transcript() {
+    let mut input = changed(&["src/lib.rs", "README.md"]);
+    input.transcript = MemTranscript::of(vec![
+        edit("/p/src/lib.rs"),
+        bash("pnpm test"),
+        edit("/p/README.md"),
+    ])
+    .0;
+    assert_eq!(TestsNotRunAdvisor.decide(&input), AdvisorDecision::Silent);
+
+    input.transcript = None;
+    assert_eq!(TestsNotRunAdvisor.decide(&input), AdvisorDecision::Silent);
+}
+
+#[test]
+fn large_diff_by_lines_or_file_count() {
+    let mut input = changed(&["src/a.rs", "src/b.rs"]);
+    input.line_stats = BTreeMap::from([
+        ("src/a.rs".to_string(), lines(300, 100)),
+        ("src/b.rs".to_string(), lines(50, 10)),
+    ]);
+    assert_eq!(LargeDiffAdvisor.decide(&input), AdvisorDecision::Silent);
+
+    input
+        .line_stats
+        .insert("src/b.rs".to_string(), lines(90, 10));
+    assert!(matches!(
+        LargeDiffAdvisor.decide(&input),
+        AdvisorDecision::Notify(_)
+    ));
+
+    let many: Vec<String> = (0..25).map(|n| format!("src/f{n}.rs")).collect();
+    let many: Vec<&str> = many.iter().map(String::as_str).collect();
+    assert!(matches!(
+        LargeDiffAdvisor.decide(&changed(&many)),
+        AdvisorDecision::Notify(_)
+    ));
+}
+
+#[test]
+fn lockfile_without_manifest() {
+    match LockfileAdvisor.decide(&changed(&["Cargo.lock", "src/lib.rs"])) {
+        AdvisorDecision::Notify(message) => assert!(message.contains("Cargo.lock")),
+        other => panic!("expected Notify, got {other:?}"),
+    }
+    // A member manifest in a subdirectory excuses the workspace lockfile.
+    assert_eq!(
+        LockfileAdvisor.decide(&changed(&["Cargo.lock", "crates/core/Cargo.toml"])),
+        AdvisorDecision::Silent
+    );
+    assert_eq!(
+        LockfileAdvisor.decide(&changed(&["web/package.json", "web/pnpm-lock.yaml"])),
+        AdvisorDecision::Silent
+    );
+}
+
+#[test]
+fn tests_not_run_ignores_edits_the_project_excludes() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs", "tests/snapshots/lib.snap"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    // Tests ran after the code edit; the snapshot written afterwards is not
+    // code, so it does not reopen the question.
+    let transcript = MemTranscript::of(vec![
+        edit("/p/src/lib.rs"),
+        bash("cargo test"),
+        edit("/p/tests/snapshots/lib.snap"),
+    ]);
+    let config = ProjectConfig::parse(r#"{"session":{"simplify":{"exclude":["*.snap"]}}}"#).session;
+
+    let advice = run(&deps, &transcript, &config, SESSION);
+    assert!(!ids(&advice).contains(&"tests-not-run"));
+}
@@ -0,0 +1,258 @@
+//! Black-box tests for `session report`: attribution matches the simplify
+//! banner, files split into code and docs/config, and line counts come from
+//! the baseline diff or — for untracked files — from the file itself. With a
+//! transcript, each file says whether an edit tool or a command changed it.
+
+mod session_mocks;
+
+use atelier::session::commands::report::run;
+use atelier::session::commands::report::ChangeVia;
+use atelier::session::commands::SessionDeps;
+use atelier::session::core::repo::CommitSummary;
+use atelier::shared::config::{ProjectConfig, SimplifyConfig};
+use session_mocks::{
+    baseline, bash, edit, hashed_baseline, lines, paths, MemRepo, MemStore, MemTranscript, SESSION,
+};
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn reports_commits_files_and_line_counts() {
+    let store = MemStore::with(SESSION, baseline("head0", &["src/old.rs"]));
+    let repo = MemRepo {
+        head: Some("head9".to_string()),
+        dirty: paths(&["src/old.rs", "src/wip.rs", "docs/guide.md", "src/new.rs"]),
+        committed: HashMap::from([("head0".to_string(), paths(&["src/done.rs"]))]),
+        commits: HashMap::from([(
+            "head0".to_string(),
+            vec![CommitSummary {
+                sha: "abcdef0123".to_string(),
+                subject: "feat: done".to_string(),
+            }],
+        )]),
+        line_stats: BTreeMap::from([
+            ("src/old.rs".to_string(), lines(50, 50)),
+            ("src/wip.rs".to_string(), lines(4, 1)),
+            ("src/done.rs".to_string(), lines(10, 0)),
+            ("docs/guide.md".to_string(), lines(2, 2)),
+        ]),
+        untracked: BTreeMap::from([("src/new.rs".to_string(), lines(7, 0))]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let report = run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION,
+    )
+    .unwrap();
+    assert_eq!(report.baseline_head.as_deref(), Some("head0"));
+    assert_eq!(report.head.as_deref(), Some("head9"));
+    assert_eq!(report.commits.len(), 1);
+
+    // The pre-dirty `src/old.rs` is excluded exactly as the banner excludes it.
+    let code: Vec<&str> = report.code_files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(code, vec!["src/done.rs", "src/new.rs", "src/wip.rs"]);
+    let docs: Vec<&str> = report.doc_files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(docs, vec!["docs/guide.md"]);
+
+    assert_eq!(report.code_files[1].added, Some(7));
+    assert_eq!(report.untracked_created, vec!["src/new.rs".to_string()]);
+    assert_eq!((report.lines_added, report.lines_removed), (23, 3));
+}
+
+#[test]
+fn pre_dirty_file_counts_only_lines_changed_since_its_baseline_blob() {
+    let store = MemStore::with(
+        SESSION,
+        hashed_baseline("head0", &[("src/lib.rs", "blob-a")]),
+    );
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        blobs: HashMap::from([("src/lib.rs".to_string(), "blob-b".to_string())]),
+        // Against `HEAD` the count includes the edits made before the session.
+        line_stats: BTreeMap::from([("src/lib.rs".to_string(), lines(30, 5))]),
+        blob_line_stats: BTreeMap::from([("src/lib.rs".to_string(), lines(2, 1))]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let report = run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION,
+    )
+    .unwrap();
+    assert_eq!(report.code_files[0].path, "src/lib.rs");
+    assert_eq!(report.code_files[0].added, Some(2));
+    assert_eq!((report.lines_added, report.lines_removed), (2, 1));
+}
+
+#[test]
+fn binary_file_has_no_line_counts() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["assets/logo.png"]),
+        line_stats: BTreeMap::from([("assets/logo.png".to_string(), None)]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let report = run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION,
+    )
+    .unwrap();
+    assert_eq!(report.code_files[0].added, None);
+    assert_eq!(report.code_files[0].removed, None);
+    assert_eq!(report.lines_added, 0);
+}
+
+#[test]
+fn untracked_files_present_at_start_are_not_created() {
+    let store = MemStore::with(SESSION, baseline("head0", &["notes.txt", "scratch/"]));
+    let repo = MemRepo {
+        untracked: BTreeMap::from([
+            ("notes.txt".to_string(), lines(1, 0)),
+            ("scratch/a.rs".to_string(), lines(1, 0)),
+            ("fresh.rs".to_string(), lines(1, 0)),
+        ]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let report = run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION,
+    )
+    .unwrap();
+    assert_eq!(report.untracked_created, vec!["fresh.rs".to_string()]);
+}
+
+#[test]
+fn errors_without_baseline_and_records_nothing() {
+    // A report is a read: it must not anchor a baseline the way the Stop
+    // hook's self-healing does.
+    let store = MemStore::default();
+    let repo = MemRepo::default();
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    assert!(run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION
+    )
+    .is_err());
+    assert!(run(&deps, &MemTranscript(None), &SimplifyConfig::default(), "").is_err());
+    assert!(store.entries.borrow().is_empty());
+}
+
+#[test]
+fn markdown_lists_sections_with_content_only() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        line_stats: BTreeMap::from([("src/lib.rs".to_string(), lines(3, 1))]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let markdown = run(
+        &deps,
+        &MemTranscript(None),
+        &SimplifyConfig::default(),
+        SESSION,
+    )
+    .unwrap()
+    .render_markdown();
+    assert!(markdown.contains("### Code (1)"));
+    assert!(markdown.contains("| `src/lib.rs` | 3 | 1 | - |"));
+    assert!(!markdown.contains("### Commits"));
+    assert!(!markdown.contains("### Docs / config"));
+}
+
+#[test]
+fn transcript_splits_edited_from_command_changed_files() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs", "src/generated.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let transcript = MemTranscript::of(vec![
+        edit("/p/src/lib.rs"),
+        bash("cargo run --bin codegen"),
+        bash("cargo test"),
+    ]);
+
+    let report = run(&deps, &transcript, &SimplifyConfig::default(), SESSION).unwrap();
+    let via: Vec<(&str, Option<ChangeVia>)> = report
+        .code_files
+        .iter()
+        .map(|f| (f.path.as_str(), f.via))
+        .collect();
+    assert_eq!(
+        via,
+        vec![
+            ("src/generated.rs", Some(ChangeVia::Bash)),
+            ("src/lib.rs", Some(ChangeVia::Edit)),
+        ]
+    );
+    let activity = report.activity.as_ref().unwrap();
+    assert!(activity.tests_after_last_edit);
+
+    let markdown = report.render_markdown();
+    assert!(markdown.contains("| `src/lib.rs` | - | - | edit |"));
+    assert!(markdown.contains("### Activity"));
+    assert!(markdown.contains("- Tests after last edit: yes"));
+}
+
+#[test]
+fn classification_follows_project_rules() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs", "src/gen/api.rs", "tests/lib.snap"]),
+        non_code: paths(&["src/gen/api.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let config = ProjectConfig::parse(r#"{"session":{"simplify":{"exclude":["*.snap"]}}}"#)
+        .session
+        .simplify;
+
+    let report = run(&deps, &MemTranscript(None), &config, SESSION).unwrap();
+    let code: Vec<&str> = report.code_files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(code, vec!["src/lib.rs"]);
+    assert_eq!(report.doc_files.len(), 2);
+}
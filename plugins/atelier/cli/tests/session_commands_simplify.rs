@@ -7,8 +7,9 @@ mod session_mocks;
 use atelier::session::commands::simplify::{run, SilentReason, SimplifyDecision};
 use atelier::session::commands::SessionDeps;
 use atelier::session::core::baseline::BaselineStore;
-use session_mocks::{baseline, paths, MemRepo, MemStore, SESSION};
-use std::collections::HashMap;
+use atelier::shared::config::{ProjectConfig, SimplifyConfig};
+use session_mocks::{baseline, hashed_baseline, lines, paths, MemRepo, MemStore, SESSION};
+use std::collections::{BTreeMap, HashMap};
 
 fn notified_files(decision: &SimplifyDecision) -> (Vec<String>, usize) {
     match decision {
@@ -29,7 +30,7 @@ fn notify_when_session_adds_code_file() {
         repo: &repo,
     };
 
-    let (files, total) = notified_files(&run(&deps, SESSION));
+    let (files, total) = notified_files(&run(&deps, &SimplifyConfig::default(), SESSION));
     assert_eq!(files, vec!["src/lib.rs".to_string()]);
     assert_eq!(total, 1);
     // Notifying marks the session so the banner does not repeat every Stop.
@@ -51,12 +52,99 @@ fn silent_when_all_dirty_predates_session() {
     };
 
     assert_eq!(
-        run(&deps, SESSION),
+        run(&deps, &SimplifyConfig::default(), SESSION),
         SimplifyDecision::Silent(SilentReason::NoSessionChanges)
     );
     assert!(!store.load(SESSION).unwrap().notified);
 }
 
+#[test]
+fn notify_when_pre_dirty_file_rewritten_during_session() {
+    // `src/lib.rs` was modified before the session; the session then rewrote
+    // it. Path membership alone would hide that work.
+    let store = MemStore::with(
+        SESSION,
+        hashed_baseline("head0", &[("src/lib.rs", "blob-a")]),
+    );
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        blobs: HashMap::from([("src/lib.rs".to_string(), "blob-b".to_string())]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let (files, total) = notified_files(&run(&deps, &SimplifyConfig::default(), SESSION));
+    assert_eq!(files, vec!["src/lib.rs".to_string()]);
+    assert_eq!(total, 1);
+}
+
+#[test]
+fn silent_when_pre_dirty_file_content_unchanged() {
+    let store = MemStore::with(
+        SESSION,
+        hashed_baseline("head0", &[("src/lib.rs", "blob-a")]),
+    );
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        blobs: HashMap::from([("src/lib.rs".to_string(), "blob-a".to_string())]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    assert_eq!(
+        run(&deps, &SimplifyConfig::default(), SESSION),
+        SimplifyDecision::Silent(SilentReason::NoSessionChanges)
+    );
+}
+
+#[test]
+fn notify_when_pre_dirty_file_deleted_during_session() {
+    // Deleting a file the user had modified is a change too: its hash goes
+    // from a blob to no content at all.
+    let store = MemStore::with(
+        SESSION,
+        hashed_baseline("head0", &[("src/lib.rs", "blob-a")]),
+    );
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let (files, _) = notified_files(&run(&deps, &SimplifyConfig::default(), SESSION));
+    assert_eq!(files, vec!["src/lib.rs".to_string()]);
+}
+
+#[test]
+fn pre_dirty_file_without_recorded_hash_stays_unattributed() {
+    // Baselines written before hashes were recorded carry no `dirty_blobs`;
+    // they keep the path-membership rule rather than guessing.
+    let store = MemStore::with(SESSION, baseline("head0", &["src/lib.rs"]));
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        blobs: HashMap::from([("src/lib.rs".to_string(), "blob-b".to_string())]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    assert_eq!(
+        run(&deps, &SimplifyConfig::default(), SESSION),
+        SimplifyDecision::Silent(SilentReason::NoSessionChanges)
+    );
+}
+
 #[test]
 fn silent_when_already_notified_in_session() {
     let mut already = baseline("head0", &[]);
@@ -72,7 +160,7 @@ fn silent_when_already_notified_in_session() {
     };
 
     assert_eq!(
-        run(&deps, SESSION),
+        run(&deps, &SimplifyConfig::default(), SESSION),
         SimplifyDecision::Silent(SilentReason::AlreadyNotified)
     );
 }
@@ -97,7 +185,7 @@ fn silent_when_only_docs_and_config_changed() {
     };
 
     assert_eq!(
-        run(&deps, SESSION),
+        run(&deps, &SimplifyConfig::default(), SESSION),
         SimplifyDecision::Silent(SilentReason::DocsOnly)
     );
 }
@@ -116,7 +204,7 @@ fn notify_when_docs_and_code_mixed() {
 
     // Docs still count toward the total once code is in the mix — the banner
     // lists what the session touched, not only the code.
-    let (files, total) = notified_files(&run(&deps, SESSION));
+    let (files, total) = notified_files(&run(&deps, &SimplifyConfig::default(), SESSION));
     assert_eq!(files, vec!["docs/guide.md", "src/lib.rs"]);
     assert_eq!(total, 2);
 }
@@ -138,7 +226,7 @@ fn counts_files_committed_since_baseline_head() {
     };
 
     // Union, not sum: a file both committed and dirty is counted once.
-    let (files, total) = notified_files(&run(&deps, SESSION));
+    let (files, total) = notified_files(&run(&deps, &SimplifyConfig::default(), SESSION));
     assert_eq!(files, vec!["src/done.rs", "src/wip.rs"]);
     assert_eq!(total, 2);
 }
@@ -157,7 +245,7 @@ fn silent_when_session_id_missing() {
     };
 
     assert_eq!(
-        run(&deps, ""),
+        run(&deps, &SimplifyConfig::default(), ""),
         SimplifyDecision::Silent(SilentReason::NoSessionId)
     );
     assert!(store.entries.borrow().is_empty());
@@ -171,6 +259,7 @@ fn silent_when_baseline_absent_and_records_it() {
     let repo = MemRepo {
         head: Some("head5".to_string()),
         dirty: paths(&["src/pre-existing.rs"]),
+        blobs: HashMap::from([("src/pre-existing.rs".to_string(), "blob-p".to_string())]),
         ..MemRepo::default()
     };
     let deps = SessionDeps {
@@ -179,11 +268,158 @@ fn silent_when_baseline_absent_and_records_it() {
     };
 
     assert_eq!(
-        run(&deps, SESSION),
+        run(&deps, &SimplifyConfig::default(), SESSION),
         SimplifyDecision::Silent(SilentReason::NoBaseline)
     );
     let recorded = store.load(SESSION).expect("baseline recorded on Stop");
     assert_eq!(recorded.head.as_deref(), Some("head5"));
     assert_eq!(recorded.dirty, paths(&["src/pre-existing.rs"]));
+    assert_eq!(
+        recorded.dirty_blobs.get("src/pre-existing.rs"),
+        Some(&Some("blob-p".to_string()))
+    );
     assert!(!recorded.notified);
 }
+
+fn simplify_config(raw: &str) -> SimplifyConfig {
+    ProjectConfig::parse(&format!(r#"{{"session":{{"simplify":{raw}}}}}"#))
+        .session
+        .simplify
+}
+
+#[test]
+fn excluded_globs_count_as_docs() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/snapshots/a.snap", ".github/workflows/ci.sh"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let config = simplify_config(r#"{"exclude":["*.snap",".github/**"]}"#);
+
+    assert_eq!(
+        run(&deps, &config, SESSION),
+        SimplifyDecision::Silent(SilentReason::DocsOnly)
+    );
+}
+
+#[test]
+fn gitattributes_generated_and_documentation_are_not_code() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["src/gen/api.rs", "manual/intro.html"]),
+        non_code: paths(&["src/gen/api.rs", "manual/intro.html"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    assert_eq!(
+        run(&deps, &SimplifyConfig::default(), SESSION),
+        SimplifyDecision::Silent(SilentReason::DocsOnly)
+    );
+}
+
+#[test]
+fn include_glob_overrides_every_other_rule() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["schemas/api.json"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    let config = simplify_config(r#"{"include":["schemas/*.json"],"exclude":["schemas/**"]}"#);
+
+    let (files, _) = notified_files(&run(&deps, &config, SESSION));
+    assert_eq!(files, vec!["schemas/api.json".to_string()]);
+}
+
+#[test]
+fn changed_line_threshold_counts_code_files_only() {
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs", "README.md"]),
+        line_stats: BTreeMap::from([
+            ("src/lib.rs".to_string(), lines(3, 2)),
+            ("README.md".to_string(), lines(100, 0)),
+        ]),
+        ..MemRepo::default()
+    };
+
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    assert_eq!(
+        run(
+            &deps,
+            &simplify_config(r#"{"min_changed_lines":6}"#),
+            SESSION
+        ),
+        SimplifyDecision::Silent(SilentReason::BelowThreshold)
+    );
+    assert!(!store.load(SESSION).unwrap().notified);
+
+    notified_files(&run(
+        &deps,
+        &simplify_config(r#"{"min_changed_lines":5}"#),
+        SESSION,
+    ));
+}
+
+#[test]
+fn changed_line_threshold_counts_a_pre_dirty_file_from_its_baseline_blob() {
+    // `src/lib.rs` was 40 lines into an edit at session start; the session
+    // rewrote two more. Diffed from `HEAD` it would clear any threshold.
+    let store = MemStore::with(
+        SESSION,
+        hashed_baseline("head0", &[("src/lib.rs", "blob-a")]),
+    );
+    let repo = MemRepo {
+        dirty: paths(&["src/lib.rs"]),
+        blobs: HashMap::from([("src/lib.rs".to_string(), "blob-b".to_string())]),
+        line_stats: BTreeMap::from([("src/lib.rs".to_string(), lines(41, 1))]),
+        blob_line_stats: BTreeMap::from([("src/lib.rs".to_string(), lines(1, 1))]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+    assert_eq!(
+        run(
+            &deps,
+            &simplify_config(r#"{"min_changed_lines":3}"#),
+            SESSION
+        ),
+        SimplifyDecision::Silent(SilentReason::BelowThreshold)
+    );
+}
+
+#[test]
+fn banner_listing_limit_is_configurable() {
+    let store = MemStore::with(SESSION, baseline("head0", &[]));
+    let repo = MemRepo {
+        dirty: paths(&["a.rs", "b.rs", "c.rs"]),
+        ..MemRepo::default()
+    };
+    let deps = SessionDeps {
+        store: &store,
+        repo: &repo,
+    };
+
+    let (files, total) = notified_files(&run(
+        &deps,
+        &simplify_config(r#"{"max_listed_files":2}"#),
+        SESSION,
+    ));
+    assert_eq!((files.len(), total), (2, 3));
+}
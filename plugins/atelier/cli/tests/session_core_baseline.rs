@@ -101,7 +101,9 @@ fn write_is_atomic_under_concurrent_calls() {
             dirty: (0..=writer)
                 .map(|n| format!("src/writer{writer}/file{n}.rs"))
                 .collect(),
+            dirty_blobs: Default::default(),
             notified: writer % 2 == 0,
+            advised: Default::default(),
         })
         .collect();
 
@@ -128,3 +130,22 @@ fn write_is_atomic_under_concurrent_calls() {
     // Temp files are renamed into place, never left behind.
     assert_eq!(entries(tmp.path()), vec![format!("{SESSION}.json")]);
 }
+
+#[test]
+fn legacy_baseline_file_still_loads() {
+    // Files written before blob hashes and advisor state existed must keep
+    // loading: a parse failure would read as "no baseline" and re-anchor a
+    // session mid-way.
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        tmp.path().join(format!("{SESSION}.json")),
+        r#"{"head":"head0","dirty":["src/lib.rs"],"notified":true}"#,
+    )
+    .unwrap();
+    let store = FsBaselineStore::new(tmp.path(), DEFAULT_TTL);
+
+    let loaded = store.load(SESSION).unwrap();
+    assert!(loaded.dirty_blobs.is_empty());
+    assert!(loaded.is_advised("simplify"));
+    assert!(!loaded.is_advised("large-diff"));
+}
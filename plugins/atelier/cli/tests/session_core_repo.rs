@@ -0,0 +1,62 @@
+//! `GitRepoReader` reads that diff against content recorded earlier, run
+//! against a real temporary git repository.
+
+use atelier::session::core::repo::{create_repo_reader, LineStat, RepoReader};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Runs a git command in `cwd`, panicking on non-zero exit.
+fn git(args: &[&str], cwd: &Path) {
+    let out = Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+fn stat(added: u64, removed: u64) -> Option<LineStat> {
+    Some(LineStat { added, removed })
+}
+
+#[test]
+fn line_stats_from_blobs_count_only_changes_since_the_recorded_content() {
+    let repo = TempDir::new().unwrap();
+    let dir = repo.path();
+    git(&["init", "-q", "-b", "main"], dir);
+    std::fs::write(dir.join("a.rs"), "1\n").unwrap();
+    std::fs::write(dir.join("b.rs"), "1\n").unwrap();
+    std::fs::write(dir.join("c.rs"), "1\n").unwrap();
+    git(&["add", "."], dir);
+    git(&["commit", "-q", "-m", "init"], dir);
+
+    // Dirty before the "session": three lines more in each file.
+    for file in ["a.rs", "b.rs", "c.rs"] {
+        std::fs::write(dir.join(file), "1\n2\n3\n4\n").unwrap();
+    }
+    let reader = create_repo_reader(dir.to_str().unwrap());
+    let paths: BTreeSet<String> = ["a.rs", "b.rs", "c.rs"].map(String::from).into();
+    let recorded: BTreeMap<String, String> = reader
+        .blob_hashes(&paths)
+        .into_iter()
+        .map(|(path, blob)| (path, blob.unwrap()))
+        .collect();
+
+    // The session rewrites one line of `a.rs`, deletes `b.rs`, leaves `c.rs`.
+    std::fs::write(dir.join("a.rs"), "1\n2\nthree\n4\n").unwrap();
+    std::fs::remove_file(dir.join("b.rs")).unwrap();
+
+    let stats = reader.line_stats_from_blobs(&recorded);
+    assert_eq!(stats["a.rs"], stat(1, 1));
+    assert_eq!(stats["b.rs"], stat(0, 4));
+    assert_eq!(stats["c.rs"], stat(0, 0));
+    // Against `HEAD`, `a.rs` would also count the three pre-session lines.
+    assert_eq!(reader.line_stats_since("HEAD")["a.rs"], stat(3, 0));
+}
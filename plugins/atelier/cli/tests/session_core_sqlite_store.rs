@@ -0,0 +1,127 @@
+//! Black-box tests for the SQLite baseline store: the same write contract the
+//! file store pins (never clobber, reject hostile ids, bounded by TTL), plus
+//! what only the database offers — atomic first-writer-wins across processes,
+//! project tagging and the import of JSON files.
+
+mod session_mocks;
+
+use atelier::session::core::baseline::{BaselineStore, DEFAULT_TTL};
+use atelier::session::core::sqlite_store::SqliteBaselineStore;
+use atelier::shared::db;
+use session_mocks::{baseline, SESSION};
+use std::time::Duration;
+
+fn memory(ttl: Duration) -> SqliteBaselineStore {
+    SqliteBaselineStore::new(db::open_in_memory().unwrap(), ttl)
+}
+
+#[test]
+fn save_if_absent_never_overwrites() {
+    let store = memory(DEFAULT_TTL);
+    let first = baseline("head0", &["src/pre-existing.rs"]);
+    assert!(store.save_if_absent(SESSION, &first).unwrap());
+    assert!(!store
+        .save_if_absent(SESSION, &baseline("head9", &[]))
+        .unwrap());
+    assert_eq!(store.load(SESSION).unwrap(), first);
+
+    let mut notified = first.clone();
+    notified.mark_notified();
+    store.save(SESSION, &notified).unwrap();
+    assert!(store.load(SESSION).unwrap().notified);
+}
+
+#[test]
+fn rejects_hostile_session_ids() {
+    let store = memory(DEFAULT_TTL);
+    let value = baseline("head0", &[]);
+    for hostile in ["../../etc/evil", "short", "sess'; DROP TABLE x; --"] {
+        assert!(store.save(hostile, &value).is_err(), "{hostile}");
+        assert!(store.load(hostile).is_none());
+    }
+    assert!(store.entries(None).unwrap().is_empty());
+}
+
+#[test]
+fn write_prunes_expired_rows_but_never_its_own() {
+    // TTL 0: every row already stored is expired by the next write.
+    let store = memory(Duration::ZERO);
+    let value = baseline("head0", &[]);
+    store.save("sess-stale-01", &value).unwrap();
+    store.save("sess-fresh-02", &value).unwrap();
+
+    assert!(store.load("sess-stale-01").is_none());
+    assert!(store.load("sess-fresh-02").is_some());
+    assert_eq!(store.prune().unwrap(), vec!["sess-fresh-02".to_string()]);
+}
+
+#[test]
+fn rows_are_tagged_and_listed_by_project() {
+    let conn_path = tempfile::TempDir::new().unwrap();
+    let path = conn_path.path().join("atelier.db");
+    let value = baseline("head0", &[]);
+    SqliteBaselineStore::open(&path, DEFAULT_TTL)
+        .unwrap()
+        .with_project("/work/a")
+        .save("sess-project-a1", &value)
+        .unwrap();
+    let b = SqliteBaselineStore::open(&path, DEFAULT_TTL)
+        .unwrap()
+        .with_project("/work/b");
+    b.save("sess-project-b1", &value).unwrap();
+
+    let in_a = b.entries(Some("/work/a")).unwrap();
+    assert_eq!(in_a.len(), 1);
+    assert_eq!(in_a[0].session_id, "sess-project-a1");
+    assert_eq!(in_a[0].project.as_deref(), Some("/work/a"));
+    assert_eq!(b.entries(None).unwrap().len(), 2);
+}
+
+#[test]
+fn concurrent_first_writes_have_one_winner() {
+    // Each thread is its own connection, as each hook is its own process.
+    let tmp = tempfile::TempDir::new().unwrap();
+    let path = tmp.path().join("atelier.db");
+    drop(SqliteBaselineStore::open(&path, DEFAULT_TTL).unwrap());
+
+    let wins: usize = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|writer| {
+                let path = &path;
+                scope.spawn(move || {
+                    let store = SqliteBaselineStore::open(path, DEFAULT_TTL).unwrap();
+                    let value = baseline(&format!("head{writer}"), &[]);
+                    usize::from(store.save_if_absent(SESSION, &value).unwrap())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    });
+    assert_eq!(wins, 1);
+}
+
+#[test]
+fn import_moves_json_files_and_keeps_existing_rows() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path().join("sessions");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("sess-import-01.json"),
+        r#"{"head":"head0","dirty":["a.rs"],"notified":true}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join(format!("{SESSION}.json")), r#"{"head":"old"}"#).unwrap();
+    std::fs::write(dir.join(".sess.1.0.tmp"), "").unwrap();
+
+    let store = memory(DEFAULT_TTL);
+    store.save(SESSION, &baseline("head0", &[])).unwrap();
+
+    assert_eq!(store.import_dir(&dir), 1);
+    assert!(store.load("sess-import-01").unwrap().notified);
+    assert_eq!(
+        store.load(SESSION).unwrap().head.as_deref(),
+        Some("head0"),
+        "the row already stored wins"
+    );
+    assert!(!dir.exists());
+}
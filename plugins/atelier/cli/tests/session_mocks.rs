@@ -8,14 +8,20 @@
 #![allow(dead_code)]
 
 use atelier::session::core::baseline::{Baseline, BaselineStore};
-use atelier::session::core::repo::RepoReader;
+use atelier::session::core::repo::{CommitSummary, LineStat, RepoReader};
+use atelier::session::core::transcript::{ToolUse, Transcript, TranscriptSource};
 use std::cell::RefCell;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 
 /// A session id that passes `is_valid_session_id`, so tests exercise the rules
 /// rather than the id guard.
 pub const SESSION: &str = "sess-abc12345";
 
+/// Root of the `MemRepo` repository; transcript doubles use absolute paths
+/// under it, as real transcripts do.
+pub const REPO_ROOT: &str = "/p";
+
 pub fn paths(items: &[&str]) -> BTreeSet<String> {
     items.iter().map(|s| s.to_string()).collect()
 }
@@ -24,7 +30,24 @@ pub fn baseline(head: &str, dirty: &[&str]) -> Baseline {
     Baseline {
         head: Some(head.to_string()),
         dirty: paths(dirty),
+        dirty_blobs: BTreeMap::new(),
+        notified: false,
+        advised: BTreeSet::new(),
+    }
+}
+
+/// A baseline whose dirty paths were hashed at session start, as the
+/// `baseline` command records them now.
+pub fn hashed_baseline(head: &str, dirty: &[(&str, &str)]) -> Baseline {
+    Baseline {
+        head: Some(head.to_string()),
+        dirty: dirty.iter().map(|(p, _)| p.to_string()).collect(),
+        dirty_blobs: dirty
+            .iter()
+            .map(|(p, h)| (p.to_string(), Some(h.to_string())))
+            .collect(),
         notified: false,
+        advised: BTreeSet::new(),
     }
 }
 
@@ -66,6 +89,18 @@ pub struct MemRepo {
     pub dirty: BTreeSet<String>,
     /// Files each base commit reports as changed since, keyed by commit.
     pub committed: HashMap<String, BTreeSet<String>>,
+    /// Current blob hash per path; a path absent here has no file content.
+    pub blobs: HashMap<String, String>,
+    /// Commits each base commit reports as made since, keyed by commit.
+    pub commits: HashMap<String, Vec<CommitSummary>>,
+    /// Line counts against any base commit.
+    pub line_stats: BTreeMap<String, Option<LineStat>>,
+    /// Line counts from any recorded blob; a path absent here has no blob.
+    pub blob_line_stats: BTreeMap<String, Option<LineStat>>,
+    pub untracked: BTreeMap<String, Option<LineStat>>,
+    /// Paths `.gitattributes` marks generated or documentation.
+    pub non_code: BTreeSet<String>,
+    pub root: Option<PathBuf>,
 }
 
 impl Default for MemRepo {
@@ -75,6 +110,13 @@ impl Default for MemRepo {
             head: Some("head1".to_string()),
             dirty: BTreeSet::new(),
             committed: HashMap::new(),
+            blobs: HashMap::new(),
+            commits: HashMap::new(),
+            line_stats: BTreeMap::new(),
+            blob_line_stats: BTreeMap::new(),
+            untracked: BTreeMap::new(),
+            non_code: BTreeSet::new(),
+            root: Some(PathBuf::from(REPO_ROOT)),
         }
     }
 }
@@ -92,4 +134,72 @@ impl RepoReader for MemRepo {
     fn files_changed_since(&self, base_head: &str) -> BTreeSet<String> {
         self.committed.get(base_head).cloned().unwrap_or_default()
     }
+    fn blob_hashes(&self, paths: &BTreeSet<String>) -> BTreeMap<String, Option<String>> {
+        paths
+            .iter()
+            .map(|p| (p.clone(), self.blobs.get(p).cloned()))
+            .collect()
+    }
+    fn commits_since(&self, base_head: &str) -> Vec<CommitSummary> {
+        self.commits.get(base_head).cloned().unwrap_or_default()
+    }
+    fn line_stats_since(&self, _base_head: &str) -> BTreeMap<String, Option<LineStat>> {
+        self.line_stats.clone()
+    }
+    fn line_stats_from_blobs(
+        &self,
+        blobs: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, Option<LineStat>> {
+        blobs
+            .keys()
+            .filter_map(|p| Some((p.clone(), *self.blob_line_stats.get(p)?)))
+            .collect()
+    }
+    fn untracked_files(&self) -> BTreeMap<String, Option<LineStat>> {
+        self.untracked.clone()
+    }
+    fn non_code_attributed(&self, paths: &BTreeSet<String>) -> BTreeSet<String> {
+        paths.intersection(&self.non_code).cloned().collect()
+    }
+    fn root(&self) -> Option<PathBuf> {
+        self.root.clone()
+    }
+}
+
+/// Line counts for the repository doubles.
+pub fn lines(added: u64, removed: u64) -> Option<LineStat> {
+    Some(LineStat { added, removed })
+}
+
+/// In-memory `TranscriptSource`; `None` models a payload without a readable
+/// transcript.
+#[derive(Default)]
+pub struct MemTranscript(pub Option<Transcript>);
+
+impl MemTranscript {
+    pub fn of(tool_uses: Vec<ToolUse>) -> Self {
+        MemTranscript(Some(Transcript { tool_uses }))
+    }
+}
+
+impl TranscriptSource for MemTranscript {
+    fn transcript(&self) -> Option<Transcript> {
+        self.0.clone()
+    }
+}
+
+pub fn edit(file_path: &str) -> ToolUse {
+    ToolUse {
+        name: "Edit".to_string(),
+        file_path: Some(file_path.to_string()),
+        ..ToolUse::default()
+    }
+}
+
+pub fn bash(command: &str) -> ToolUse {
+    ToolUse {
+        name: "Bash".to_string(),
+        command: Some(command.to_string()),
+        ..ToolUse::default()
+    }
 }
@@ -0,0 +1,147 @@
+//! End-to-end black-box tests for `atelier state ...` against the real binary.
+//! `ATELIER_STATE_DIR` and `TMPDIR` point into a TempDir, so the import of
+//! old JSON baselines, the TTL sweep and the export run without touching the
+//! user's real state.
+
+use assert_cmd::Command;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+struct Fixture {
+    tmp: tempfile::TempDir,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let fx = Fixture {
+            tmp: tempfile::TempDir::new().unwrap(),
+        };
+        std::fs::create_dir_all(fx.tmp.path().join("tmp")).unwrap();
+        fx
+    }
+
+    /// Where the JSON-file store of the previous release wrote.
+    fn sessions(&self) -> PathBuf {
+        self.tmp.path().join("state/sessions")
+    }
+
+    /// Where releases before the XDG move wrote.
+    fn legacy(&self) -> PathBuf {
+        self.tmp.path().join("tmp/atelier-sessions")
+    }
+
+    fn atelier(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::cargo_bin("atelier").unwrap();
+        cmd.args(args)
+            .env("ATELIER_STATE_DIR", self.tmp.path().join("state"))
+            .env("TMPDIR", self.tmp.path().join("tmp"));
+        cmd
+    }
+
+    fn state(&self, args: &[&str]) -> Value {
+        let mut full = vec!["state"];
+        full.extend_from_slice(args);
+        let out = self
+            .atelier(&full)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&out).unwrap()
+    }
+}
+
+fn write_aged(path: &Path, age: Duration) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, r#"{"head":"head0","dirty":[]}"#).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+#[test]
+fn json_baselines_from_both_old_locations_are_imported() {
+    let fx = Fixture::new();
+    write_aged(&fx.legacy().join("sess-legacy-01.json"), Duration::ZERO);
+    write_aged(&fx.sessions().join("sess-xdg-0002.json"), Duration::ZERO);
+
+    let info = fx.state(&["info"]);
+    assert_eq!(info["sessions"], 2);
+    assert_eq!(info["legacy_dirs"], serde_json::json!([]));
+    assert!(!fx.legacy().exists());
+    assert!(!fx.sessions().exists());
+    assert!(fx.tmp.path().join("state/atelier.db").is_file());
+}
+
+#[test]
+fn gc_removes_only_expired_baselines() {
+    let fx = Fixture::new();
+    // Imported rows keep the file's mtime, so ages carry over.
+    write_aged(
+        &fx.sessions().join("sess-stale-01.json"),
+        Duration::from_secs(8 * 24 * 60 * 60),
+    );
+    write_aged(&fx.sessions().join("sess-fresh-02.json"), Duration::ZERO);
+
+    let info = fx.state(&["info"]);
+    assert_eq!(info["expired"], 1);
+
+    let dry = fx.state(&["gc", "--dry-run"]);
+    assert_eq!(dry["removed"], serde_json::json!(["sess-stale-01"]));
+    assert_eq!(fx.state(&["info"])["sessions"], 2);
+
+    let gc = fx.state(&["gc"]);
+    assert_eq!(gc["removed"], serde_json::json!(["sess-stale-01"]));
+    assert_eq!(gc["kept"], 1);
+    assert_eq!(fx.state(&["info"])["sessions"], 1);
+}
+
+#[test]
+fn export_filters_by_project_as_the_hooks_recorded_it() {
+    let fx = Fixture::new();
+    let project = fx.tmp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(&project)
+        .status()
+        .unwrap();
+    let project_arg = project.to_str().unwrap();
+    // The baseline hook records the (canonical) project dir with the row.
+    fx.atelier(&["session", "baseline", "--project-dir", project_arg])
+        .write_stdin(r#"{"session_id":"sess-hooked-01"}"#)
+        .assert()
+        .success();
+    write_aged(&fx.sessions().join("sess-orphan-02.json"), Duration::ZERO);
+
+    let all = fx.state(&["export"]);
+    assert_eq!(all.as_array().unwrap().len(), 2);
+
+    let mine = fx.state(&["export", "--project", project_arg]);
+    let ids: Vec<&str> = mine
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["session_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["sess-hooked-01"]);
+
+    let lines = fx
+        .atelier(&[
+            "state",
+            "export",
+            "--session-id",
+            "sess-orphan-02",
+            "--format",
+            "jsonl",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let lines = String::from_utf8(lines).unwrap();
+    assert_eq!(lines.lines().count(), 1);
+    assert!(lines.contains(r#""head":"head0""#));
+}
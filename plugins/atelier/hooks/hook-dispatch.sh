@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# hook-dispatch.sh — SessionStart · Stop hook shim
+# 이벤트 하나를 `atelier hook-dispatch` 한 프로세스로 넘깁니다. 라우터가 이벤트에
+# 맞는 handler 를 고릅니다:
+#   SessionStart → 세션 베이스라인 기록 (없을 때만 — resume/compact 안전)
+#   Stop         → /simplify 검토 등 Stop advisor 제안 (이 세션이 코드를 변경했을 때만)
+#
+# 판정·기록·출력은 전부 CLI 에 있습니다 (`.claude/rules/tool-layer-boundary.md`).
+# 이 shim 의 책임은 부트스트랩뿐입니다: atelier 미설치면 무음 종료.
+#
+# check-cli-version.sh 와 분리한 이유: 책임이 다르고, 그 스크립트의
+# `set -euo pipefail` 아래에서 dispatch 실패가 버전 경고를 삼킵니다.
+
+command -v atelier >/dev/null 2>&1 || exit 0
+
+exec atelier hook-dispatch --project-dir "${CLAUDE_PROJECT_DIR:-.}"
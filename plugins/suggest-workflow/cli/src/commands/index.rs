@@ -1,29 +1,195 @@
 use anyhow::{Context, Result};
-use std::path::Path;
-use std::time::UNIX_EPOCH;
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Instant, UNIX_EPOCH};
 
 use crate::analyzers::tool_classifier;
 use crate::db::repository::*;
 use crate::parsers;
 use crate::parsers::filters::{classify_prompt_role, strip_system_reminders, PromptRole};
-use crate::types::{Content, SessionEntry, ToolUse};
+use crate::types::{AssistantUsage, Content, MessageNode, SessionEntry, ToolResult, ToolUse};
 
-pub fn run(repo: &dyn IndexRepository, sessions_dir: &Path) -> Result<()> {
+/// Error output kept per failed tool result, in characters.
+const ERROR_TEXT_MAX_CHARS: usize = 500;
+
+/// Parsed sessions per write transaction; a batch is also flushed once its
+/// source files add up to `BATCH_MAX_BYTES`, which bounds memory held.
+const BATCH_MAX_SESSIONS: usize = 256;
+const BATCH_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Default)]
+pub struct IndexOptions {
+    /// Maximum parse threads; `None` means one per CPU.
+    pub jobs: Option<usize>,
+    /// Price table to load; falls back to `~/.claude/suggest-workflow/prices.json`.
+    pub prices: Option<PathBuf>,
+}
+
+/// Price file format: USD per million tokens, keyed by model id prefix.
+#[derive(Deserialize)]
+struct PriceConfig {
+    models: HashMap<String, PriceEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceEntry {
+    input: f64,
+    output: f64,
+    cache_read: f64,
+    cache_creation: f64,
+}
+
+/// One project's session directory, indexed under its encoded name.
+pub struct ProjectSessions {
+    pub project: String,
+    pub sessions_dir: PathBuf,
+}
+
+/// A changed or new session file waiting to be parsed.
+struct PendingSession<'a> {
+    project: &'a str,
+    path: PathBuf,
+    size: u64,
+    mtime: i64,
+    checkpoint: Option<SessionCheckpoint>,
+}
+
+/// Index `sessions_dir` as a single project.
+pub fn run(repo: &dyn IndexRepository, sessions_dir: &Path, options: &IndexOptions) -> Result<()> {
+    let project = sessions_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    run_projects(
+        repo,
+        &[ProjectSessions {
+            project,
+            sessions_dir: sessions_dir.to_path_buf(),
+        }],
+        options,
+    )
+}
+
+/// Index every project directory under `projects_dir` (~/.claude/projects)
+/// into one store.
+pub fn run_all(
+    repo: &dyn IndexRepository,
+    projects_dir: &Path,
+    options: &IndexOptions,
+) -> Result<()> {
+    let projects: Vec<ProjectSessions> =
+        parsers::projects::list_projects(Some(&projects_dir.to_string_lossy()))?
+            .into_iter()
+            .map(|project| ProjectSessions {
+                sessions_dir: projects_dir.join(&project),
+                project,
+            })
+            .collect();
+    eprintln!("Projects: {}", projects.len());
+    // The store mirrors the whole directory: sessions of projects that are
+    // gone from it are stale too.
+    index_projects(repo, &projects, None, options)
+}
+
+/// Index the given projects. Files are parsed on up to `options.jobs`
+/// threads while this thread stays the only SQLite writer. Sessions of
+/// `projects` whose files are gone are removed; other projects' sessions in
+/// the store (e.g. a global DB passed with `--db`) are left alone.
+pub fn run_projects(
+    repo: &dyn IndexRepository,
+    projects: &[ProjectSessions],
+    options: &IndexOptions,
+) -> Result<()> {
+    let names: Vec<&str> = projects.iter().map(|p| p.project.as_str()).collect();
+    index_projects(repo, projects, Some(&names), options)
+}
+
+/// `run_projects` with the stale-session cleanup limited to `scope`, or
+/// over the whole store when `None`.
+fn index_projects(
+    repo: &dyn IndexRepository,
+    projects: &[ProjectSessions],
+    scope: Option<&[&str]>,
+    options: &IndexOptions,
+) -> Result<()> {
     repo.initialize()?;
 
-    let session_files = parsers::list_sessions(sessions_dir)?;
+    if let Some(prices) = load_prices(options.prices.as_deref())? {
+        repo.replace_model_prices(&prices)?;
+    }
+
+    let mut session_files: Vec<(&str, PathBuf)> = Vec::new();
+    for p in projects {
+        let files = parsers::list_sessions(&p.sessions_dir)?
+            .into_iter()
+            .chain(parsers::list_subagent_sessions(&p.sessions_dir)?);
+        session_files.extend(files.map(|f| (p.project.as_str(), f)));
+    }
+
+    let mut summary = IndexSummary::default();
+    index_files(repo, &session_files, options, &mut summary)?;
+
+    // Remove sessions whose files no longer exist
+    let existing_paths: Vec<&Path> = session_files.iter().map(|(_, p)| p.as_path()).collect();
+    summary.deleted = repo.remove_stale_sessions(&existing_paths, scope)?;
+
+    finish(repo, &summary)
+}
 
-    let mut new_count: u64 = 0;
-    let mut updated_count: u64 = 0;
-    let mut unchanged_count: u64 = 0;
-    let mut error_count: u64 = 0;
+/// Re-index only `session_files` (e.g. the files a watcher saw change):
+/// changed ones go through `check_session` as in a full run, missing ones
+/// are removed. Other indexed sessions are left alone.
+pub fn reindex_files(
+    repo: &dyn IndexRepository,
+    session_files: &[(&str, PathBuf)],
+    options: &IndexOptions,
+) -> Result<()> {
+    let (existing, missing): (Vec<_>, Vec<_>) =
+        session_files.iter().cloned().partition(|(_, p)| p.exists());
 
-    for file_path in &session_files {
+    let mut summary = IndexSummary::default();
+    index_files(repo, &existing, options, &mut summary)?;
+
+    let missing_paths: Vec<&Path> = missing.iter().map(|(_, p)| p.as_path()).collect();
+    summary.deleted = repo.remove_sessions(&missing_paths)?;
+
+    finish(repo, &summary)
+}
+
+/// Counts of one index pass, reported by `finish`.
+#[derive(Default)]
+struct IndexSummary {
+    new: u64,
+    updated: u64,
+    appended: u64,
+    unchanged: u64,
+    deleted: u64,
+    errors: u64,
+    written: usize,
+    parsed_bytes: u64,
+    threads: usize,
+    /// Throughput line of the parse phase, when anything was parsed
+    throughput: Option<String>,
+}
+
+/// Check each file against the store and write the new and changed ones.
+fn index_files(
+    repo: &dyn IndexRepository,
+    session_files: &[(&str, PathBuf)],
+    options: &IndexOptions,
+    summary: &mut IndexSummary,
+) -> Result<()> {
+    let mut pending = Vec::new();
+    for (project, file_path) in session_files {
         let meta = match std::fs::metadata(file_path) {
             Ok(m) => m,
             Err(e) => {
                 eprintln!("Warning: Cannot read {}: {}", file_path.display(), e);
-                error_count += 1;
+                summary.errors += 1;
                 continue;
             }
         };
@@ -36,93 +202,308 @@ pub fn run(repo: &dyn IndexRepository, sessions_dir: &Path) -> Result<()> {
             .map(|d| d.as_millis() as i64)
             .unwrap_or(0);
 
-        let status = repo.check_session(file_path, size, mtime)?;
+        let checkpoint = match repo.check_session(file_path, size, mtime)? {
+            SessionStatus::Unchanged => {
+                summary.unchanged += 1;
+                continue;
+            }
+            SessionStatus::New => {
+                summary.new += 1;
+                None
+            }
+            SessionStatus::Changed(checkpoint) => {
+                summary.updated += 1;
+                checkpoint
+            }
+        };
+        pending.push(PendingSession {
+            project,
+            path: file_path.clone(),
+            size,
+            mtime,
+            checkpoint,
+        });
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()
+        .context("failed to start parser threads")?;
+    let total = pending.len();
+    let started = Instant::now();
+    let mut written = 0usize;
+    let mut parsed_bytes = 0u64;
 
-        if status == SessionStatus::Unchanged {
-            unchanged_count += 1;
-            continue;
-        }
+    std::thread::scope(|scope| -> Result<()> {
+        // Bounded so parsers cannot run arbitrarily far ahead of the writer.
+        let (tx, rx) = mpsc::sync_channel(pool.current_num_threads() * 4);
+        let pending = &pending;
+        let pool = &pool;
+        scope.spawn(move || {
+            pool.install(|| {
+                pending.par_iter().for_each_with(tx, |tx, session| {
+                    let _ = tx.send((session, parse_pending(session)));
+                })
+            })
+        });
 
-        match extract_session_data(file_path, size, mtime) {
-            Ok(session_data) => {
-                repo.upsert_session(&session_data)?;
-                match status {
-                    SessionStatus::New => new_count += 1,
-                    SessionStatus::Changed => updated_count += 1,
-                    SessionStatus::Unchanged => unreachable!(),
+        let mut batch = Vec::new();
+        let mut batch_bytes = 0u64;
+        for (session, parsed) in rx {
+            match parsed {
+                Ok((write, bytes)) => {
+                    if matches!(write, SessionWrite::Append(_)) {
+                        summary.appended += 1;
+                    }
+                    batch.push(write);
+                    batch_bytes += bytes;
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to parse {}: {}", session.path.display(), e);
+                    summary.errors += 1;
                 }
             }
-            Err(e) => {
-                eprintln!("Warning: Failed to parse {}: {}", file_path.display(), e);
-                error_count += 1;
+
+            if batch.len() >= BATCH_MAX_SESSIONS || batch_bytes >= BATCH_MAX_BYTES {
+                repo.write_sessions(&batch)?;
+                written += batch.len();
+                parsed_bytes += batch_bytes;
+                batch.clear();
+                batch_bytes = 0;
+                eprintln!(
+                    "Progress: {}/{} sessions, {}",
+                    written,
+                    total,
+                    throughput(written, parsed_bytes, started)
+                );
             }
         }
-    }
+        repo.write_sessions(&batch)?;
+        written += batch.len();
+        parsed_bytes += batch_bytes;
+        Ok(())
+    })?;
 
-    // Remove sessions whose files no longer exist
-    let existing_paths: Vec<&Path> = session_files.iter().map(|p| p.as_path()).collect();
-    let deleted_count = repo.remove_stale_sessions(&existing_paths)?;
+    summary.written = written;
+    summary.parsed_bytes = parsed_bytes;
+    summary.threads = pool.current_num_threads();
+    if written > 0 {
+        summary.throughput = Some(throughput(written, parsed_bytes, started));
+    }
+    Ok(())
+}
 
-    // Rebuild derived tables
-    repo.rebuild_derived_tables()?;
+/// Finish derived tables and meta after a pass, then print its summary.
+fn finish(repo: &dyn IndexRepository, summary: &IndexSummary) -> Result<()> {
+    // Finish derived tables (per-session deltas were applied above)
+    repo.refresh_derived_tables()?;
 
     // Update meta
     repo.update_meta("last_indexed_at", &chrono::Utc::now().to_rfc3339())?;
 
     // Summary to stderr
     eprintln!(
-        "Indexed: {} new, {} updated, {} unchanged, {} deleted",
-        new_count, updated_count, unchanged_count, deleted_count
+        "Indexed: {} new, {} updated ({} appended), {} unchanged, {} deleted",
+        summary.new, summary.updated, summary.appended, summary.unchanged, summary.deleted
     );
-    if error_count > 0 {
-        eprintln!("Warnings: {} sessions skipped due to errors", error_count);
+    if let Some(throughput) = &summary.throughput {
+        eprintln!(
+            "Parsed {} sessions on {} threads, {}",
+            summary.written, summary.threads, throughput
+        );
+    }
+    if summary.errors > 0 {
+        eprintln!(
+            "Warnings: {} sessions skipped due to errors",
+            summary.errors
+        );
     }
 
     Ok(())
 }
 
-fn extract_session_data(file_path: &Path, size: u64, mtime: i64) -> Result<SessionData> {
-    let entries = parsers::parse_session(file_path)?;
+/// Parse one pending session, resuming from its checkpoint when the indexed
+/// prefix is intact. Returns the write and how many file bytes it covered.
+fn parse_pending(session: &PendingSession) -> Result<(SessionWrite, u64)> {
+    let resume = session
+        .checkpoint
+        .as_ref()
+        .filter(|c| prefix_intact(&session.path, session.size, c));
+    let bytes = session.size - resume.map_or(0, |c| c.parsed_offset);
+    let data = extract_session_data(
+        session.project,
+        &session.path,
+        session.size,
+        session.mtime,
+        resume,
+    )?;
+    let write = match resume {
+        Some(_) => SessionWrite::Append(data),
+        None => SessionWrite::Upsert(data),
+    };
+    Ok((write, bytes))
+}
+
+/// Read the price table from `path`, or from the default location when that
+/// file exists. `None` leaves the stored table as it is.
+fn load_prices(path: Option<&Path>) -> Result<Option<Vec<ModelPrice>>> {
+    let path = match path {
+        Some(p) => p.to_path_buf(),
+        None => match default_prices_path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(None),
+        },
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read price table: {}", path.display()))?;
+    let config: PriceConfig = serde_json::from_str(&content)
+        .with_context(|| format!("invalid price table: {}", path.display()))?;
+
+    let mut prices: Vec<ModelPrice> = config
+        .models
+        .into_iter()
+        .map(|(model, p)| ModelPrice {
+            model,
+            input: p.input,
+            output: p.output,
+            cache_read: p.cache_read,
+            cache_creation: p.cache_creation,
+        })
+        .collect();
+    prices.sort_by(|a, b| a.model.cmp(&b.model));
+    Ok(Some(prices))
+}
+
+/// Default price table path: ~/.claude/suggest-workflow/prices.json
+fn default_prices_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(
+        PathBuf::from(home)
+            .join(".claude")
+            .join("suggest-workflow")
+            .join("prices.json"),
+    )
+}
+
+fn throughput(sessions: usize, bytes: u64, started: Instant) -> String {
+    let secs = started.elapsed().as_secs_f64().max(1e-3);
+    format!(
+        "{:.1} MiB in {:.2}s ({:.0} sessions/s, {:.1} MiB/s)",
+        bytes as f64 / (1024.0 * 1024.0),
+        secs,
+        sessions as f64 / secs,
+        bytes as f64 / (1024.0 * 1024.0) / secs
+    )
+}
+
+/// Whether the bytes indexed last time are still where they were: the file has
+/// not shrunk and the last indexed entry hashes the same. A rewritten or
+/// compacted file fails this and is re-parsed in full.
+fn prefix_intact(file_path: &Path, size: u64, checkpoint: &SessionCheckpoint) -> bool {
+    size >= checkpoint.parsed_offset
+        && parsers::hash_range(
+            file_path,
+            checkpoint.last_entry_offset,
+            checkpoint.parsed_offset,
+        )
+        .is_ok_and(|hash| hash == checkpoint.last_entry_hash)
+}
+
+/// Parse a session file, or with `resume` only the tail after its checkpoint.
+/// Rows are numbered to continue the indexed sequence and session-level
+/// fields come out as totals, ready for `append_session`.
+fn extract_session_data(
+    project: &str,
+    file_path: &Path,
+    size: u64,
+    mtime: i64,
+    resume: Option<&SessionCheckpoint>,
+) -> Result<SessionData> {
+    let chunk = parsers::parse_session_from(file_path, resume.map_or(0, |c| c.parsed_offset))?;
+    let entries = chunk.entries;
     let tools = parsers::extract_tool_sequence(&entries);
+    let results = parsers::extract_tool_results(&entries);
+    let usage = parsers::extract_assistant_usage(&entries);
+    let (nodes, roots) = parsers::extract_message_tree(&entries, resume.is_none());
 
     let session_id = file_path
         .file_stem()
         .and_then(|s| s.to_str())
         .context("invalid session filename")?
         .to_string();
+    // A subagent transcript carries its parent's session id on every line.
+    let parent_session_id = entries
+        .iter()
+        .find_map(|e| e.session_id.as_ref())
+        .filter(|id| **id != session_id)
+        .cloned();
+    let spawn_session_id = parent_session_id.as_ref().unwrap_or(&session_id);
+    let subagents = roots
+        .into_iter()
+        .map(|r| SubagentData {
+            agent_id: r.agent_id,
+            spawn_session_id: spawn_session_id.clone(),
+            prompt: r.prompt,
+        })
+        .collect();
 
+    let seq_base = resume.map_or(0, |c| c.tool_use_count);
     let prompts = extract_prompts(&entries);
-    let tool_uses = classify_tool_uses(&tools);
-    let file_edits = extract_file_edits(&tools);
-
-    let first_ts = prompts
-        .first()
-        .map(|p| p.timestamp)
-        .or_else(|| tool_uses.first().and_then(|t| t.timestamp));
-    let last_ts = prompts
-        .last()
-        .map(|p| p.timestamp)
-        .or_else(|| tool_uses.last().and_then(|t| t.timestamp));
-
-    let first_prompt_snippet = prompts
-        .iter()
-        .find(|p| p.role == PromptRole::Human)
-        .or_else(|| prompts.first())
-        .map(|p| p.text.chars().take(500).collect::<String>());
+    let tool_uses = classify_tool_uses(&tools, seq_base);
+    let tool_results = to_tool_result_data(&results);
+    let file_edits = extract_file_edits(&tools, seq_base);
+
+    // Session span: prompts when there are any, tool uses otherwise.
+    let prompt_first = prompts.first().map(|p| p.timestamp);
+    let prompt_last = prompts.last().map(|p| p.timestamp);
+    let tool_first = tool_uses.first().and_then(|t| t.timestamp);
+    let tool_last = tool_uses.last().and_then(|t| t.timestamp);
+    let (first_ts, last_ts) = match resume {
+        None => (prompt_first.or(tool_first), prompt_last.or(tool_last)),
+        Some(c) if c.prompt_count > 0 => (c.first_ts, prompt_last.or(c.last_ts)),
+        Some(c) => (
+            prompt_first.or(c.first_ts).or(tool_first),
+            prompt_last.or(tool_last).or(c.last_ts),
+        ),
+    };
+
+    let snippet = |p: &PromptData| p.text.chars().take(500).collect::<String>();
+    let first_human = prompts.iter().find(|p| p.role == PromptRole::Human);
+    let first_prompt_snippet = match resume {
+        Some(c) if c.has_human_prompt => c.first_prompt_snippet.clone(),
+        Some(c) => first_human
+            .map(snippet)
+            .or_else(|| c.first_prompt_snippet.clone())
+            .or_else(|| prompts.first().map(snippet)),
+        None => first_human.or_else(|| prompts.first()).map(snippet),
+    };
+
+    // An empty tail keeps the previous checkpoint entry.
+    let last_entry = chunk
+        .last_entry
+        .or_else(|| resume.map(|c| (c.last_entry_offset, c.last_entry_hash.clone())));
 
     Ok(SessionData {
         id: session_id,
+        project: project.to_string(),
         file_path: file_path.to_string_lossy().to_string(),
         file_size: size,
         file_mtime: mtime,
         first_ts,
         last_ts,
-        prompt_count: prompts.len(),
-        tool_use_count: tool_uses.len(),
+        prompt_count: resume.map_or(0, |c| c.prompt_count) + prompts.len(),
+        tool_use_count: seq_base + tool_uses.len(),
         first_prompt_snippet,
+        parent_session_id,
+        parsed_offset: chunk.end_offset,
+        last_entry,
         prompts,
         tool_uses,
+        tool_results,
         file_edits,
+        assistant_messages: to_assistant_message_data(&usage),
+        messages: nodes.into_iter().map(to_message_data).collect(),
+        subagents,
     })
 }
 
@@ -167,29 +548,82 @@ fn extract_prompts(entries: &[SessionEntry]) -> Vec<PromptData> {
                 text,
                 timestamp,
                 role,
+                cwd: e.cwd.clone(),
+                git_branch: e.git_branch.clone(),
             })
         })
         .collect()
 }
 
-fn classify_tool_uses(tools: &[ToolUse]) -> Vec<ToolUseData> {
+fn classify_tool_uses(tools: &[ToolUse], seq_base: usize) -> Vec<ToolUseData> {
     tools
         .iter()
         .enumerate()
         .map(|(i, tool)| {
             let classified = tool_classifier::classify_tool(&tool.name, tool.input.as_ref());
             ToolUseData {
-                seq_order: i,
+                seq_order: seq_base + i,
                 tool_name: tool.name.clone(),
                 classified_name: classified.classified_name,
                 timestamp: tool.timestamp,
                 input_json: tool.input.as_ref().map(|v| v.to_string()),
+                block_id: tool.id.clone(),
+                message_uuid: tool.message_uuid.clone(),
+                cwd: tool.cwd.clone(),
+                git_branch: tool.git_branch.clone(),
             }
         })
         .collect()
 }
 
-fn extract_file_edits(tools: &[ToolUse]) -> Vec<FileEditData> {
+fn to_tool_result_data(results: &[ToolResult]) -> Vec<ToolResultData> {
+    results
+        .iter()
+        .map(|r| ToolResultData {
+            tool_use_block_id: r.tool_use_id.clone(),
+            timestamp: r.timestamp,
+            is_error: r.is_error,
+            output_size: r.output_size,
+            error_text: r
+                .error_text
+                .as_ref()
+                .map(|t| t.chars().take(ERROR_TEXT_MAX_CHARS).collect()),
+            commit: r.commit.as_ref().map(|c| CommitData {
+                sha: c.sha.clone(),
+                branch: c.branch.clone(),
+                subject: c.subject.clone(),
+            }),
+        })
+        .collect()
+}
+
+fn to_assistant_message_data(messages: &[AssistantUsage]) -> Vec<AssistantMessageData> {
+    messages
+        .iter()
+        .map(|m| AssistantMessageData {
+            message_id: m.message_id.clone(),
+            model: m.model.clone(),
+            timestamp: m.timestamp,
+            input_tokens: m.usage.input_tokens,
+            output_tokens: m.usage.output_tokens,
+            cache_read_tokens: m.usage.cache_read_input_tokens,
+            cache_creation_tokens: m.usage.cache_creation_input_tokens,
+        })
+        .collect()
+}
+
+fn to_message_data(node: MessageNode) -> MessageData {
+    MessageData {
+        uuid: node.uuid,
+        parent_uuid: node.parent_uuid,
+        entry_type: node.entry_type,
+        is_sidechain: node.is_sidechain,
+        agent_id: node.agent_id,
+        timestamp: node.timestamp,
+    }
+}
+
+fn extract_file_edits(tools: &[ToolUse], seq_base: usize) -> Vec<FileEditData> {
     tools
         .iter()
         .enumerate()
@@ -209,7 +643,7 @@ fn extract_file_edits(tools: &[ToolUse]) -> Vec<FileEditData> {
             };
 
             file_path.map(|path| FileEditData {
-                tool_use_seq: i,
+                tool_use_seq: seq_base + i,
                 file_path: path.to_string(),
                 timestamp: tool.timestamp,
             })
@@ -2,3 +2,4 @@ pub mod analyze;
 pub mod cache;
 pub mod index;
 pub mod query;
+pub mod watch;
@@ -10,6 +10,7 @@ pub fn run(
     sql_file: Option<&Path>,
     params: QueryParams,
     session_filter: Option<&str>,
+    project_filter: Option<&str>,
 ) -> Result<()> {
     let result = match (perspective, sql_file) {
         // --sql-file takes priority
@@ -24,7 +25,7 @@ pub fn run(
             repo.execute_sql(trimmed)?
         }
         // Named perspective
-        (Some(name), None) => repo.query(name, &params, session_filter)?,
+        (Some(name), None) => repo.query(name, &params, session_filter, project_filter)?,
         // Neither
         (None, None) => anyhow::bail!("--perspective or --sql-file required"),
     };
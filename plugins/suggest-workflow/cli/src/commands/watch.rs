@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, PollWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use super::index::{self, IndexOptions};
+use crate::db::repository::IndexRepository;
+use crate::db::IndexLock;
+use crate::parsers;
+
+/// How often the polling fallback rescans the tree.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A pass runs at the latest this many debounce periods after the first
+/// change, so a session that is being written non-stop is still re-indexed.
+const MAX_WAIT_FACTOR: u32 = 10;
+
+pub struct WatchOptions {
+    /// Quiet period after the last change before a pass runs (at most
+    /// `MAX_WAIT_FACTOR` periods after the first).
+    pub debounce: Duration,
+    /// Poll instead of native notifications (inotify / FSEvents), e.g. on
+    /// network file systems where those are not delivered.
+    pub poll: bool,
+}
+
+/// Keep the index live: run a full pass, then watch `root` and re-index the
+/// session files that change, once they have been quiet for the debounce
+/// period or have kept changing for `MAX_WAIT_FACTOR` of them. `root` is a
+/// project's session directory, or with `all_projects` ~/.claude/projects.
+/// Every pass holds the exclusive lock on `db_path`. Runs until the process
+/// is stopped.
+pub fn run(
+    repo: &dyn IndexRepository,
+    root: &Path,
+    all_projects: bool,
+    db_path: &Path,
+    options: &IndexOptions,
+    watch: &WatchOptions,
+) -> Result<()> {
+    if !root.is_dir() {
+        anyhow::bail!("sessions directory not found: {}", root.display());
+    }
+
+    {
+        let _lock = IndexLock::exclusive(db_path)?;
+        if all_projects {
+            index::run_all(repo, root, options)?;
+        } else {
+            index::run(repo, root, options)?;
+        }
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let poll_config = notify::Config::default().with_poll_interval(POLL_INTERVAL);
+    let native = if watch.poll {
+        None
+    } else {
+        match notify::recommended_watcher(tx.clone()) {
+            Ok(mut w) => match w.watch(root, RecursiveMode::Recursive) {
+                Ok(()) => Some(w),
+                Err(e) => {
+                    eprintln!("Warning: Native file watching unavailable ({e}), polling instead");
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("Warning: Native file watching unavailable ({e}), polling instead");
+                None
+            }
+        }
+    };
+    // Kept alive for the rest of the loop; dropping a watcher stops it.
+    let _watcher: Box<dyn Watcher> = match native {
+        Some(w) => {
+            eprintln!("Watching {} (native)", root.display());
+            Box::new(w)
+        }
+        None => {
+            let mut w = PollWatcher::new(tx, poll_config).context("failed to start polling")?;
+            w.watch(root, RecursiveMode::Recursive)
+                .with_context(|| format!("failed to watch {}", root.display()))?;
+            eprintln!(
+                "Watching {} (polling every {}s)",
+                root.display(),
+                POLL_INTERVAL.as_secs()
+            );
+            Box::new(w)
+        }
+    };
+
+    // Paths from the watcher may be canonical (FSEvents); the store keys
+    // sessions by the path a full scan produces under `root`.
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    loop {
+        let mut touched = BTreeSet::new();
+        match rx.recv() {
+            Ok(event) => collect(event, root, &canonical_root, all_projects, &mut touched),
+            Err(_) => return Ok(()),
+        }
+        // Debounce: keep collecting until the tree has been quiet for a
+        // while, or until the first change has waited long enough
+        let deadline = Instant::now() + watch.debounce * MAX_WAIT_FACTOR;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            match rx.recv_timeout(watch.debounce.min(left)) {
+                Ok(event) => collect(event, root, &canonical_root, all_projects, &mut touched),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        if touched.is_empty() {
+            continue;
+        }
+
+        let files: Vec<(&str, PathBuf)> = touched
+            .iter()
+            .map(|(project, path)| (project.as_str(), path.clone()))
+            .collect();
+        eprintln!("Changed: {} session files", files.len());
+        let _lock = IndexLock::exclusive(db_path)?;
+        if let Err(e) = index::reindex_files(repo, &files, options) {
+            eprintln!("Warning: Re-index failed: {e:#}");
+        }
+    }
+}
+
+/// Add the session files an event touched, with their projects.
+fn collect(
+    event: notify::Result<Event>,
+    root: &Path,
+    canonical_root: &Path,
+    all_projects: bool,
+    touched: &mut BTreeSet<(String, PathBuf)>,
+) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("Warning: Watch error: {e}");
+            return;
+        }
+    };
+    // Reads (including our own parsing) are not changes
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    for path in &event.paths {
+        if let Some(session) = watched_session(root, canonical_root, all_projects, path) {
+            touched.insert(session);
+        }
+    }
+}
+
+/// The project and store path of `path` when it is a session file under
+/// `root`: `<session>.jsonl` or `<session id>/subagents/<agent>.jsonl`, one
+/// level deeper with `all_projects`.
+fn watched_session(
+    root: &Path,
+    canonical_root: &Path,
+    all_projects: bool,
+    path: &Path,
+) -> Option<(String, PathBuf)> {
+    if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+        return None;
+    }
+    let relative = path
+        .strip_prefix(root)
+        .or_else(|_| path.strip_prefix(canonical_root))
+        .ok()?;
+    let depth = relative.components().count() - usize::from(all_projects);
+    let in_subagents = relative
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|n| n == "subagents");
+    if !(depth == 1 || (depth == 3 && in_subagents)) {
+        return None;
+    }
+
+    let path = root.join(relative);
+    let project = parsers::project_of_session_file(&path)?;
+    Some((project, path))
+}
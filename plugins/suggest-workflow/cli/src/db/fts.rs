@@ -0,0 +1,132 @@
+//! Full-text index over prompt text (`prompts_fts`)
+//!
+//! The default build uses FTS5's trigram tokenizer, which matches any
+//! substring of 3+ characters and so needs no Korean word segmentation.
+//! With `lindera-korean`, prompts are segmented into morphemes before
+//! indexing and matched with `unicode61`, which also finds 2-character
+//! words such as "커밋".
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+
+#[cfg(feature = "lindera-korean")]
+use crate::tokenizer::KoreanTokenizer;
+
+/// Tokenizer the index is built with; recorded in `meta.fts_tokenizer`.
+#[cfg(not(feature = "lindera-korean"))]
+pub const TOKENIZER: &str = "trigram";
+#[cfg(feature = "lindera-korean")]
+pub const TOKENIZER: &str = "lindera";
+
+#[cfg(not(feature = "lindera-korean"))]
+const TOKENIZE_CLAUSE: &str = "trigram";
+#[cfg(feature = "lindera-korean")]
+const TOKENIZE_CLAUSE: &str = "unicode61";
+
+#[cfg(feature = "lindera-korean")]
+thread_local! {
+    static SEGMENTER: KoreanTokenizer = KoreanTokenizer::default();
+}
+
+/// Create `prompts_fts` if it is missing or was built with another
+/// tokenizer, and fill it from `prompts`.
+pub fn ensure_index(conn: &Connection) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE name = 'prompts_fts'",
+        [],
+        |row| row.get(0),
+    )?;
+    let built_with: Option<String> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'fts_tokenizer'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if exists && built_with.as_deref() == Some(TOKENIZER) {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(&format!(
+        "DROP TRIGGER IF EXISTS prompts_fts_delete;
+         DROP TABLE IF EXISTS prompts_fts;
+         CREATE VIRTUAL TABLE prompts_fts USING fts5(text, tokenize = '{TOKENIZE_CLAUSE}');
+         CREATE TRIGGER prompts_fts_delete AFTER DELETE ON prompts BEGIN
+             DELETE FROM prompts_fts WHERE rowid = old.id;
+         END;"
+    ))?;
+    {
+        let mut select = tx.prepare("SELECT id, text FROM prompts")?;
+        let mut insert = tx.prepare("INSERT INTO prompts_fts (rowid, text) VALUES (?1, ?2)")?;
+        let mut rows = select.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let text: String = row.get(1)?;
+            insert.execute(params![id, index_text(&text)])?;
+        }
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('fts_tokenizer', ?1)",
+        params![TOKENIZER],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Text stored in `prompts_fts` for a prompt.
+#[cfg(not(feature = "lindera-korean"))]
+pub fn index_text(text: &str) -> std::borrow::Cow<'_, str> {
+    std::borrow::Cow::Borrowed(text)
+}
+
+#[cfg(feature = "lindera-korean")]
+pub fn index_text(text: &str) -> std::borrow::Cow<'_, str> {
+    std::borrow::Cow::Owned(SEGMENTER.with(|t| t.tokenize(text).join(" ")))
+}
+
+/// Turn a user's search string into an FTS5 MATCH expression: every term
+/// must appear, each quoted so FTS5 operators are taken literally.
+///
+/// Trigram search cannot match terms under 3 characters; when the query has
+/// one, the whole query is searched as a single phrase instead.
+#[cfg(not(feature = "lindera-korean"))]
+pub fn match_query(raw: &str) -> Result<String> {
+    let terms: Vec<&str> = raw.split_whitespace().collect();
+    if terms.is_empty() {
+        anyhow::bail!("search query is empty");
+    }
+    if terms.iter().all(|t| t.chars().count() >= 3) {
+        return Ok(join_phrases(&terms));
+    }
+    let phrase = terms.join(" ");
+    if phrase.chars().count() < 3 {
+        anyhow::bail!(
+            "search query '{}' is too short: trigram search needs at least 3 characters",
+            raw
+        );
+    }
+    Ok(join_phrases(&[&phrase]))
+}
+
+#[cfg(feature = "lindera-korean")]
+pub fn match_query(raw: &str) -> Result<String> {
+    let tokens: Vec<String> = SEGMENTER.with(|t| t.tokenize(raw));
+    let terms: Vec<&str> = tokens
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if terms.is_empty() {
+        anyhow::bail!("search query is empty");
+    }
+    Ok(join_phrases(&terms))
+}
+
+fn join_phrases(terms: &[&str]) -> String {
+    terms
+        .iter()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+/// Advisory lock on `<db>.lock` next to the index DB. An index run commits in
+/// batches, so readers hold it shared while writers hold it exclusive for the
+/// whole run; a query never sees a run half-applied. Released on drop (or by
+/// the OS when the process dies).
+pub struct IndexLock {
+    _file: File,
+}
+
+impl IndexLock {
+    /// Lock for an index run, waiting for queries and other runs to finish.
+    pub fn exclusive(db_path: &Path) -> Result<Self> {
+        Self::acquire(db_path, true)
+    }
+
+    /// Lock for a query, waiting for a running index pass to finish.
+    pub fn shared(db_path: &Path) -> Result<Self> {
+        Self::acquire(db_path, false)
+    }
+
+    fn acquire(db_path: &Path, exclusive: bool) -> Result<Self> {
+        let path = lock_path(db_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("failed to open lock file: {}", path.display()))?;
+
+        let attempt = if exclusive {
+            file.try_lock()
+        } else {
+            file.try_lock_shared()
+        };
+        match attempt {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                eprintln!("Waiting for index lock: {}", path.display());
+                if exclusive {
+                    file.lock()
+                } else {
+                    file.lock_shared()
+                }
+                .with_context(|| format!("failed to lock {}", path.display()))?;
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("failed to lock {}", path.display()));
+            }
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+fn lock_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    db_path.with_file_name(name)
+}
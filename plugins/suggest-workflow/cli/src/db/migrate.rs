@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
+use std::path::Path;
 
 use super::schema;
 
@@ -133,6 +134,225 @@ fn migrate_step(conn: &Connection, from: u32, to: u32) -> Result<()> {
             )?;
             Ok(())
         }
+        (5, 6) => {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS tool_results (
+                    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id   TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    tool_use_id  INTEGER NOT NULL REFERENCES tool_uses(id) ON DELETE CASCADE,
+                    is_error     INTEGER NOT NULL,
+                    output_size  INTEGER NOT NULL,
+                    error_text   TEXT,
+                    duration_ms  INTEGER
+                );
+                CREATE INDEX IF NOT EXISTS idx_tool_results_session ON tool_results(session_id);
+                CREATE INDEX IF NOT EXISTS idx_tool_results_tool_use ON tool_results(tool_use_id);",
+            )?;
+            // Results live only in the JSONL files. Invalidate the change-detection
+            // stamp so the index run that follows re-parses every session.
+            conn.execute_batch("UPDATE sessions SET file_mtime = -1;")?;
+            Ok(())
+        }
+        (6, 7) => {
+            conn.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN parsed_offset INTEGER NOT NULL DEFAULT 0;
+                 ALTER TABLE sessions ADD COLUMN last_entry_offset INTEGER;
+                 ALTER TABLE sessions ADD COLUMN last_entry_hash TEXT;
+                 ALTER TABLE tool_uses ADD COLUMN block_id TEXT;
+                 CREATE INDEX IF NOT EXISTS idx_tool_uses_block ON tool_uses(session_id, block_id);",
+            )?;
+            // No checkpoint yet: each session is re-parsed in full the next
+            // time it changes, which records one.
+            Ok(())
+        }
+        (7, 8) => {
+            // Derived tables are now maintained per session; the ones on disk
+            // were fully rebuilt by the last run, so they are a valid start.
+            conn.execute_batch(
+                "CREATE INDEX IF NOT EXISTS idx_session_links_b ON session_links(session_b);",
+            )?;
+            Ok(())
+        }
+        (8, 9) => {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS assistant_messages (
+                    id                    INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id            TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    message_id            TEXT,
+                    model                 TEXT,
+                    timestamp             INTEGER,
+                    input_tokens          INTEGER NOT NULL DEFAULT 0,
+                    output_tokens         INTEGER NOT NULL DEFAULT 0,
+                    cache_read_tokens     INTEGER NOT NULL DEFAULT 0,
+                    cache_creation_tokens INTEGER NOT NULL DEFAULT 0
+                );
+                CREATE UNIQUE INDEX IF NOT EXISTS idx_assistant_messages_msg ON assistant_messages(session_id, message_id);
+                CREATE INDEX IF NOT EXISTS idx_assistant_messages_ts ON assistant_messages(timestamp);
+                CREATE TABLE IF NOT EXISTS model_prices (
+                    model                   TEXT PRIMARY KEY,
+                    input_per_mtok          REAL NOT NULL,
+                    output_per_mtok         REAL NOT NULL,
+                    cache_read_per_mtok     REAL NOT NULL,
+                    cache_creation_per_mtok REAL NOT NULL
+                );
+                CREATE VIEW IF NOT EXISTS message_costs AS
+                SELECT m.*,
+                       (m.input_tokens * p.input_per_mtok
+                        + m.output_tokens * p.output_per_mtok
+                        + m.cache_read_tokens * p.cache_read_per_mtok
+                        + m.cache_creation_tokens * p.cache_creation_per_mtok) / 1000000.0 AS cost_usd
+                FROM assistant_messages m
+                LEFT JOIN model_prices p ON p.model = (
+                    SELECT p2.model FROM model_prices p2
+                    WHERE SUBSTR(m.model, 1, LENGTH(p2.model)) = p2.model
+                    ORDER BY LENGTH(p2.model) DESC
+                    LIMIT 1
+                );",
+            )?;
+            // Usage lives only in the JSONL files. Drop the change stamp and the
+            // append checkpoint so the next run re-parses every session in full.
+            conn.execute_batch(
+                "UPDATE sessions SET file_mtime = -1, last_entry_offset = NULL, last_entry_hash = NULL;",
+            )?;
+            Ok(())
+        }
+        (9, 10) => {
+            conn.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN parent_session_id TEXT;
+                ALTER TABLE tool_uses ADD COLUMN message_uuid TEXT;
+                ALTER TABLE tool_uses ADD COLUMN agent_id TEXT;
+                CREATE TABLE IF NOT EXISTS messages (
+                    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id   TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    uuid         TEXT NOT NULL,
+                    parent_uuid  TEXT,
+                    entry_type   TEXT NOT NULL,
+                    is_sidechain INTEGER NOT NULL DEFAULT 0,
+                    agent_id     TEXT,
+                    timestamp    INTEGER
+                );
+                CREATE INDEX IF NOT EXISTS idx_messages_uuid ON messages(session_id, uuid);
+                CREATE INDEX IF NOT EXISTS idx_messages_parent ON messages(session_id, parent_uuid);
+                CREATE INDEX IF NOT EXISTS idx_messages_agent ON messages(session_id, agent_id);
+                CREATE TABLE IF NOT EXISTS subagents (
+                    session_id       TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    agent_id         TEXT NOT NULL,
+                    spawn_session_id TEXT NOT NULL,
+                    prompt           TEXT,
+                    PRIMARY KEY (session_id, agent_id)
+                );
+                CREATE VIEW IF NOT EXISTS subagent_runs AS
+                SELECT a.session_id, a.agent_id, a.spawn_session_id,
+                       (SELECT json_extract(u.input_json, '$.subagent_type') FROM tool_uses u
+                        WHERE u.session_id = a.spawn_session_id
+                          AND u.tool_name IN ('Task', 'Agent')
+                          AND json_extract(u.input_json, '$.prompt') = a.prompt
+                        ORDER BY u.seq_order
+                        LIMIT 1) AS agent_type,
+                       (SELECT COUNT(*) FROM tool_uses u
+                        WHERE u.session_id = a.session_id AND u.agent_id = a.agent_id) AS tool_use_count,
+                       (SELECT MIN(m.timestamp) FROM messages m
+                        WHERE m.session_id = a.session_id AND m.agent_id = a.agent_id) AS first_ts,
+                       (SELECT MAX(m.timestamp) FROM messages m
+                        WHERE m.session_id = a.session_id AND m.agent_id = a.agent_id) AS last_ts
+                FROM subagents a;",
+            )?;
+            // The message tree lives only in the JSONL files; re-parse every
+            // session in full so tool uses get their agents.
+            conn.execute_batch(
+                "UPDATE sessions SET file_mtime = -1, last_entry_offset = NULL, last_entry_hash = NULL;",
+            )?;
+            Ok(())
+        }
+        (10, 11) => {
+            // prompts_fts: backfilled from the existing prompts
+            super::fts::ensure_index(conn)
+        }
+        (11, 12) => {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN project TEXT NOT NULL DEFAULT '';
+                CREATE INDEX IF NOT EXISTS idx_sessions_project ON sessions(project);
+                ALTER TABLE session_links ADD COLUMN project TEXT NOT NULL DEFAULT '';
+                DROP TABLE tool_transitions;
+                DROP TABLE weekly_buckets;
+                DROP TABLE file_hotspots;
+                CREATE TABLE tool_transitions (
+                    project     TEXT NOT NULL DEFAULT '',
+                    from_tool   TEXT NOT NULL,
+                    to_tool     TEXT NOT NULL,
+                    count       INTEGER NOT NULL,
+                    probability REAL NOT NULL,
+                    PRIMARY KEY (project, from_tool, to_tool)
+                );
+                CREATE TABLE weekly_buckets (
+                    project        TEXT NOT NULL DEFAULT '',
+                    week_start     TEXT NOT NULL,
+                    tool_name      TEXT NOT NULL,
+                    count          INTEGER NOT NULL,
+                    session_count  INTEGER NOT NULL,
+                    PRIMARY KEY (project, week_start, tool_name)
+                );
+                CREATE INDEX IF NOT EXISTS idx_weekly_week ON weekly_buckets(week_start);
+                CREATE TABLE file_hotspots (
+                    project       TEXT NOT NULL DEFAULT '',
+                    file_path     TEXT NOT NULL,
+                    edit_count    INTEGER NOT NULL,
+                    session_count INTEGER NOT NULL,
+                    PRIMARY KEY (project, file_path)
+                );",
+            )?;
+
+            // Backfill each session's project from where its file lives
+            let sessions: Vec<(String, String)> = tx
+                .prepare("SELECT id, file_path FROM sessions")?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            for (id, file_path) in &sessions {
+                let project = crate::parsers::project_of_session_file(Path::new(file_path));
+                tx.execute(
+                    "UPDATE sessions SET project = ?2 WHERE id = ?1",
+                    rusqlite::params![id, project.unwrap_or_default()],
+                )?;
+            }
+            tx.execute_batch(
+                "UPDATE session_links SET project = COALESCE(
+                    (SELECT project FROM sessions WHERE id = session_links.session_a), '');",
+            )?;
+
+            // Re-key the derived tables by adding every session back in;
+            // probabilities are renormalized at the end of the index run
+            for (id, _) in &sessions {
+                super::sqlite::add_session_aggregates(&tx, id, 1)?;
+            }
+            tx.commit()?;
+            Ok(())
+        }
+        (12, 13) => {
+            conn.execute_batch(
+                "ALTER TABLE prompts ADD COLUMN cwd TEXT;
+                ALTER TABLE prompts ADD COLUMN git_branch TEXT;
+                ALTER TABLE tool_uses ADD COLUMN cwd TEXT;
+                ALTER TABLE tool_uses ADD COLUMN git_branch TEXT;
+                CREATE TABLE IF NOT EXISTS commits (
+                    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id  TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    tool_use_id INTEGER NOT NULL REFERENCES tool_uses(id) ON DELETE CASCADE,
+                    sha         TEXT NOT NULL,
+                    branch      TEXT NOT NULL,
+                    subject     TEXT NOT NULL,
+                    timestamp   INTEGER
+                );
+                CREATE INDEX IF NOT EXISTS idx_commits_session ON commits(session_id);
+                CREATE INDEX IF NOT EXISTS idx_commits_sha ON commits(sha);",
+            )?;
+            // Branches and commit outputs live only in the JSONL files;
+            // re-parse every session in full.
+            conn.execute_batch(
+                "UPDATE sessions SET file_mtime = -1, last_entry_offset = NULL, last_entry_hash = NULL;",
+            )?;
+            Ok(())
+        }
         _ => {
             anyhow::bail!(
                 "no migration path from v{} to v{}. Run with --full to rebuild.",
@@ -1,9 +1,12 @@
+pub mod fts;
+pub mod lock;
 pub mod migrate;
 pub mod perspectives;
 pub mod repository;
 pub mod schema;
 pub mod sqlite;
 
+pub use lock::IndexLock;
 #[allow(unused_imports)]
 pub use repository::{IndexRepository, QueryRepository, SessionData, SessionStatus};
 pub use sqlite::SqliteStore;
@@ -26,7 +26,8 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 .into(),
         },
         // transitions: Tools that follow a specific tool
-        // Derived table — session filter not applicable
+        // Derived table — session filter not applicable; project filter via {PF:project}.
+        // Rows are per project, so counts are summed and probabilities recomputed
         PerspectiveInfo {
             name: "transitions".into(),
             description: "특정 도구 이후 전이 확률".into(),
@@ -38,14 +39,16 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 description: "기준 도구 (예: Bash:git, Edit)".into(),
             }],
             sql: "\
-                SELECT to_tool, count, probability \
+                SELECT to_tool, SUM(count) AS count, \
+                       CAST(SUM(count) AS REAL) / SUM(SUM(count)) OVER () AS probability \
                 FROM tool_transitions \
-                WHERE from_tool = :tool \
+                WHERE from_tool = :tool {PF:project} \
+                GROUP BY to_tool \
                 ORDER BY probability DESC"
                 .into(),
         },
         // trends: Weekly tool usage trends
-        // Derived table — session filter not applicable
+        // Derived table — session filter not applicable; project filter via {PF:project}
         PerspectiveInfo {
             name: "trends".into(),
             description: "주간 도구 사용 트렌드".into(),
@@ -57,14 +60,16 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 description: "시작 날짜 (YYYY-MM-DD)".into(),
             }],
             sql: "\
-                SELECT week_start, tool_name, count, session_count \
+                SELECT week_start, tool_name, \
+                       SUM(count) AS count, SUM(session_count) AS session_count \
                 FROM weekly_buckets \
-                WHERE week_start >= :since \
+                WHERE week_start >= :since {PF:project} \
+                GROUP BY week_start, tool_name \
                 ORDER BY week_start, count DESC"
                 .into(),
         },
         // hotfiles: Most frequently edited files
-        // Derived table — session filter not applicable
+        // Derived table — session filter not applicable; project filter via {PF:project}
         PerspectiveInfo {
             name: "hotfiles".into(),
             description: "자주 편집되는 파일 핫스팟".into(),
@@ -76,8 +81,11 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 description: "상위 N개".into(),
             }],
             sql: "\
-                SELECT file_path, edit_count, session_count \
+                SELECT file_path, \
+                       SUM(edit_count) AS edit_count, SUM(session_count) AS session_count \
                 FROM file_hotspots \
+                WHERE 1=1 {PF:project} \
+                GROUP BY file_path \
                 ORDER BY edit_count DESC \
                 LIMIT :top"
                 .into(),
@@ -115,6 +123,288 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 ORDER BY ABS(deviation_score) DESC"
                 .into(),
         },
+        // tool-failures: Failure rate per classified tool
+        // Supports --session-filter via {SF:u.session_id}
+        PerspectiveInfo {
+            name: "tool-failures".into(),
+            description: "도구별 실패율 (분류명 기준)".into(),
+            params: vec![ParamDef {
+                name: "min_count".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("1".into()),
+                description: "최소 결과 수".into(),
+            }],
+            sql: "\
+                SELECT u.classified_name AS tool, \
+                       COUNT(*) AS results, \
+                       SUM(r.is_error) AS failures, \
+                       ROUND(CAST(SUM(r.is_error) AS REAL) / COUNT(*), 3) AS failure_rate, \
+                       ROUND(AVG(r.duration_ms)) AS avg_duration_ms \
+                FROM tool_uses u \
+                JOIN tool_results r ON r.tool_use_id = u.id \
+                WHERE 1=1 {SF:u.session_id} \
+                GROUP BY u.classified_name \
+                HAVING results >= :min_count \
+                ORDER BY failure_rate DESC, failures DESC"
+                .into(),
+        },
+        // failing-commands: Most common failing Bash commands
+        // Supports --session-filter via {SF:u.session_id}
+        PerspectiveInfo {
+            name: "failing-commands".into(),
+            description: "자주 실패하는 Bash 명령".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("20".into()),
+                description: "상위 N개".into(),
+            }],
+            sql: "\
+                SELECT json_extract(u.input_json, '$.command') AS command, \
+                       u.classified_name AS tool, \
+                       COUNT(*) AS failures, \
+                       COUNT(DISTINCT u.session_id) AS sessions, \
+                       SUBSTR(MAX(r.error_text), 1, 200) AS sample_error \
+                FROM tool_uses u \
+                JOIN tool_results r ON r.tool_use_id = u.id \
+                WHERE u.tool_name = 'Bash' AND r.is_error = 1 {SF:u.session_id} \
+                GROUP BY command \
+                ORDER BY failures DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // retry-loops: Re-running a tool after it failed, per session
+        // "retries" counts failures followed by another use of the same
+        // classified tool; "recovered" counts those whose next use succeeded.
+        // Supports --session-filter via {SF:u.session_id}
+        PerspectiveInfo {
+            name: "retry-loops".into(),
+            description: "실패 후 재시도 루프 (세션·도구별)".into(),
+            params: vec![
+                ParamDef {
+                    name: "min_retries".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("2".into()),
+                    description: "최소 재시도 횟수".into(),
+                },
+                ParamDef {
+                    name: "top".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("20".into()),
+                    description: "상위 N개".into(),
+                },
+            ],
+            sql: "\
+                SELECT session_id, classified_name AS tool, \
+                       COUNT(*) AS attempts, \
+                       SUM(is_error) AS failures, \
+                       SUM(CASE WHEN is_error = 1 AND next_is_error IS NOT NULL THEN 1 ELSE 0 END) AS retries, \
+                       SUM(CASE WHEN is_error = 1 AND next_is_error = 0 THEN 1 ELSE 0 END) AS recovered \
+                FROM ( \
+                    SELECT u.session_id, u.classified_name, r.is_error, \
+                           LEAD(r.is_error) OVER ( \
+                               PARTITION BY u.session_id, u.agent_id, u.classified_name \
+                               ORDER BY u.seq_order \
+                           ) AS next_is_error \
+                    FROM tool_uses u \
+                    JOIN tool_results r ON r.tool_use_id = u.id \
+                    WHERE 1=1 {SF:u.session_id} \
+                ) attempts \
+                GROUP BY session_id, classified_name \
+                HAVING retries >= :min_retries \
+                ORDER BY retries DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // session-tokens: Token usage and cost per session
+        // cost_usd covers priced messages only; unpriced_messages counts the rest.
+        // Supports --session-filter via {SF:m.session_id}
+        PerspectiveInfo {
+            name: "session-tokens".into(),
+            description: "세션별 토큰 사용량과 비용".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("20".into()),
+                description: "상위 N개".into(),
+            }],
+            sql: "\
+                SELECT s.id, SUBSTR(s.first_prompt_snippet, 1, 100) AS first_prompt, \
+                       COUNT(*) AS messages, \
+                       SUM(m.input_tokens) AS input_tokens, \
+                       SUM(m.output_tokens) AS output_tokens, \
+                       SUM(m.cache_read_tokens) AS cache_read_tokens, \
+                       SUM(m.cache_creation_tokens) AS cache_creation_tokens, \
+                       SUM(m.input_tokens + m.output_tokens + m.cache_read_tokens + m.cache_creation_tokens) AS total_tokens, \
+                       ROUND(SUM(m.cost_usd), 4) AS cost_usd, \
+                       COUNT(*) - COUNT(m.cost_usd) AS unpriced_messages \
+                FROM message_costs m \
+                JOIN sessions s ON s.id = m.session_id \
+                WHERE 1=1 {SF:m.session_id} \
+                GROUP BY s.id \
+                ORDER BY total_tokens DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // daily-tokens: Token usage and cost per day
+        // Supports --session-filter via {SF:m.session_id}
+        PerspectiveInfo {
+            name: "daily-tokens".into(),
+            description: "일별 토큰 사용량과 비용".into(),
+            params: vec![ParamDef {
+                name: "since".into(),
+                param_type: ParamType::Date,
+                required: false,
+                default: Some("2020-01-01".into()),
+                description: "시작 날짜 (YYYY-MM-DD)".into(),
+            }],
+            sql: "\
+                SELECT date(m.timestamp / 1000, 'unixepoch', 'localtime') AS day, \
+                       COUNT(DISTINCT m.session_id) AS sessions, \
+                       COUNT(*) AS messages, \
+                       SUM(m.input_tokens) AS input_tokens, \
+                       SUM(m.output_tokens) AS output_tokens, \
+                       SUM(m.cache_read_tokens) AS cache_read_tokens, \
+                       SUM(m.cache_creation_tokens) AS cache_creation_tokens, \
+                       SUM(m.input_tokens + m.output_tokens + m.cache_read_tokens + m.cache_creation_tokens) AS total_tokens, \
+                       ROUND(SUM(m.cost_usd), 4) AS cost_usd, \
+                       COUNT(*) - COUNT(m.cost_usd) AS unpriced_messages \
+                FROM message_costs m \
+                WHERE m.timestamp IS NOT NULL \
+                  AND date(m.timestamp / 1000, 'unixepoch', 'localtime') >= :since {SF:m.session_id} \
+                GROUP BY day \
+                ORDER BY day"
+                .into(),
+        },
+        // model-tokens: Token usage and cost per model
+        // Supports --session-filter via {SF:m.session_id}
+        PerspectiveInfo {
+            name: "model-tokens".into(),
+            description: "모델별 토큰 사용량과 비용".into(),
+            params: vec![],
+            sql: "\
+                SELECT COALESCE(m.model, 'unknown') AS model, \
+                       COUNT(DISTINCT m.session_id) AS sessions, \
+                       COUNT(*) AS messages, \
+                       SUM(m.input_tokens) AS input_tokens, \
+                       SUM(m.output_tokens) AS output_tokens, \
+                       SUM(m.cache_read_tokens) AS cache_read_tokens, \
+                       SUM(m.cache_creation_tokens) AS cache_creation_tokens, \
+                       SUM(m.input_tokens + m.output_tokens + m.cache_read_tokens + m.cache_creation_tokens) AS total_tokens, \
+                       ROUND(SUM(m.cost_usd), 4) AS cost_usd, \
+                       COUNT(*) - COUNT(m.cost_usd) AS unpriced_messages \
+                FROM message_costs m \
+                WHERE 1=1 {SF:m.session_id} \
+                GROUP BY m.model \
+                ORDER BY total_tokens DESC"
+                .into(),
+        },
+        // workflow-cost: Session cost grouped by first prompt pattern
+        // The pattern is a leading [tag] when the first prompt has one
+        // (e.g. "[autodev]"), otherwise its first :prefix_len characters.
+        // Supports --session-filter via {SF:s.id}
+        PerspectiveInfo {
+            name: "workflow-cost".into(),
+            description: "첫 프롬프트 패턴별 세션 비용".into(),
+            params: vec![
+                ParamDef {
+                    name: "prefix_len".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("30".into()),
+                    description: "[태그]가 없을 때 패턴으로 쓸 앞부분 글자 수".into(),
+                },
+                ParamDef {
+                    name: "top".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("20".into()),
+                    description: "상위 N개".into(),
+                },
+            ],
+            sql: "\
+                SELECT CASE \
+                           WHEN s.first_prompt_snippet LIKE '[%]%' \
+                           THEN SUBSTR(s.first_prompt_snippet, 1, INSTR(s.first_prompt_snippet, ']')) \
+                           ELSE SUBSTR(COALESCE(s.first_prompt_snippet, ''), 1, :prefix_len) \
+                       END AS pattern, \
+                       COUNT(DISTINCT s.id) AS sessions, \
+                       SUM(m.input_tokens) AS input_tokens, \
+                       SUM(m.output_tokens) AS output_tokens, \
+                       SUM(m.cache_read_tokens) AS cache_read_tokens, \
+                       SUM(m.cache_creation_tokens) AS cache_creation_tokens, \
+                       SUM(m.input_tokens + m.output_tokens + m.cache_read_tokens + m.cache_creation_tokens) AS total_tokens, \
+                       ROUND(SUM(m.cost_usd), 4) AS cost_usd, \
+                       COUNT(*) - COUNT(m.cost_usd) AS unpriced_messages \
+                FROM message_costs m \
+                JOIN sessions s ON s.id = m.session_id \
+                WHERE 1=1 {SF:s.id} \
+                GROUP BY pattern \
+                ORDER BY cost_usd DESC, total_tokens DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // subagents: Subagent runs per agent type
+        // Supports --session-filter via {SF:r.spawn_session_id}
+        PerspectiveInfo {
+            name: "subagents".into(),
+            description: "서브에이전트 타입별 실행 수와 규모".into(),
+            params: vec![],
+            sql: "\
+                SELECT COALESCE(r.agent_type, 'unknown') AS agent_type, \
+                       COUNT(*) AS runs, \
+                       COUNT(DISTINCT r.spawn_session_id) AS sessions, \
+                       SUM(r.tool_use_count) AS tool_uses, \
+                       ROUND(AVG(r.tool_use_count), 1) AS avg_tool_uses, \
+                       ROUND(AVG((r.last_ts - r.first_ts) / 60000.0), 1) AS avg_minutes \
+                FROM subagent_runs r \
+                WHERE 1=1 {SF:r.spawn_session_id} \
+                GROUP BY agent_type \
+                ORDER BY runs DESC"
+                .into(),
+        },
+        // agent-tools: Tool profile per agent ("main" is the top-level thread)
+        // Supports --session-filter via {SF:session_id}
+        PerspectiveInfo {
+            name: "agent-tools".into(),
+            description: "에이전트별 도구 사용 프로필".into(),
+            params: vec![ParamDef {
+                name: "min_count".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("1".into()),
+                description: "최소 사용 횟수".into(),
+            }],
+            sql: "\
+                SELECT agent, tool, uses, share \
+                FROM ( \
+                    SELECT agent, tool, uses, \
+                           ROUND(CAST(uses AS REAL) / SUM(uses) OVER (PARTITION BY agent), 3) AS share \
+                    FROM ( \
+                        SELECT CASE WHEN u.agent_id IS NULL THEN 'main' \
+                                    ELSE COALESCE(r.agent_type, 'unknown') END AS agent, \
+                               u.classified_name AS tool, \
+                               SUM(u.uses) AS uses \
+                        FROM ( \
+                            SELECT session_id, agent_id, classified_name, COUNT(*) AS uses \
+                            FROM tool_uses \
+                            WHERE 1=1 {SF:session_id} \
+                            GROUP BY session_id, agent_id, classified_name \
+                        ) u \
+                        LEFT JOIN subagent_runs r \
+                          ON r.session_id = u.session_id AND r.agent_id = u.agent_id \
+                        GROUP BY agent, tool \
+                    ) \
+                ) \
+                WHERE uses >= :min_count \
+                ORDER BY agent = 'main' DESC, agent, uses DESC"
+                .into(),
+        },
         // prompts: Search prompts by keyword with role filtering
         // Supports --session-filter via {SF:p.session_id}
         PerspectiveInfo {
@@ -153,8 +443,47 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 LIMIT :top"
                 .into(),
         },
+        // search: Full-text prompt search, bm25-ranked
+        PerspectiveInfo {
+            name: "search".into(),
+            description: "프롬프트 전문 검색 (bm25 순위, 일치 부분 스니펫)".into(),
+            params: vec![
+                ParamDef {
+                    name: "query".into(),
+                    param_type: ParamType::Search,
+                    required: true,
+                    default: None,
+                    description: "검색어 (공백으로 나눈 단어가 모두 포함된 프롬프트)".into(),
+                },
+                ParamDef {
+                    name: "top".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("20".into()),
+                    description: "상위 N개".into(),
+                },
+                ParamDef {
+                    name: "role".into(),
+                    param_type: ParamType::Text,
+                    required: false,
+                    default: Some("human".into()),
+                    description: "역할 필터 (human, system, all)".into(),
+                },
+            ],
+            sql: "\
+                SELECT p.session_id, p.timestamp, p.role, \
+                       snippet(prompts_fts, 0, '[', ']', '…', 16) AS snippet, \
+                       ROUND(bm25(prompts_fts), 4) AS score \
+                FROM prompts_fts \
+                JOIN prompts p ON p.id = prompts_fts.rowid \
+                WHERE prompts_fts MATCH :query \
+                  AND (:role = 'all' OR p.role = :role) {SF:p.session_id} \
+                ORDER BY bm25(prompts_fts), p.timestamp DESC \
+                LIMIT :top"
+                .into(),
+        },
         // session-links: Sessions sharing edited files
-        // Derived table — session filter not applicable
+        // Derived table — session filter not applicable; project filter via {PF:project}
         PerspectiveInfo {
             name: "session-links".into(),
             description: "파일 공유 기반 세션 연결".into(),
@@ -170,12 +499,12 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                        ROUND(overlap_ratio, 2) AS overlap_ratio, \
                        time_gap_minutes \
                 FROM session_links \
-                WHERE overlap_ratio >= :min_overlap \
+                WHERE overlap_ratio >= :min_overlap {PF:project} \
                 ORDER BY overlap_ratio DESC"
                 .into(),
         },
         // sequences: Common tool sequences (bigrams)
-        // Derived table — session filter not applicable
+        // Derived table — session filter not applicable; project filter via {PF:project}
         PerspectiveInfo {
             name: "sequences".into(),
             description: "자주 등장하는 도구 시퀀스 (2-gram)".into(),
@@ -187,9 +516,14 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 description: "최소 등장 횟수".into(),
             }],
             sql: "\
-                SELECT from_tool || ' → ' || to_tool AS sequence, \
-                       count, ROUND(probability, 3) AS probability \
-                FROM tool_transitions \
+                SELECT from_tool || ' → ' || to_tool AS sequence, count, \
+                       ROUND(CAST(count AS REAL) / SUM(count) OVER (PARTITION BY from_tool), 3) AS probability \
+                FROM ( \
+                    SELECT from_tool, to_tool, SUM(count) AS count \
+                    FROM tool_transitions \
+                    WHERE 1=1 {PF:project} \
+                    GROUP BY from_tool, to_tool \
+                ) \
                 WHERE count >= :min_count \
                 ORDER BY count DESC"
                 .into(),
@@ -218,6 +552,7 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                 .into(),
         },
         // filtered-sessions: Find sessions by first prompt pattern
+        // Supports --session-filter via {SF:id}
         PerspectiveInfo {
             name: "filtered-sessions".into(),
             description: "첫 프롬프트 패턴으로 세션 검색".into(),
@@ -252,10 +587,201 @@ pub fn register_perspectives() -> Vec<PerspectiveInfo> {
                        ROUND((last_ts - first_ts) / 60000.0, 1) AS duration_minutes \
                 FROM sessions \
                 WHERE first_prompt_snippet LIKE '%' || :prompt_pattern || '%' \
-                  AND datetime(first_ts / 1000, 'unixepoch') >= :since \
+                  AND datetime(first_ts / 1000, 'unixepoch') >= :since {SF:id} \
                 ORDER BY first_ts DESC \
                 LIMIT :top"
                 .into(),
         },
+        // projects: Indexed projects (one row in a per-project DB)
+        // Supports --session-filter via {SF:id}
+        PerspectiveInfo {
+            name: "projects".into(),
+            description: "인덱싱된 프로젝트별 세션 요약".into(),
+            params: vec![],
+            sql: "\
+                SELECT project, COUNT(*) AS sessions, \
+                       SUM(prompt_count) AS prompts, \
+                       SUM(tool_use_count) AS tool_uses, \
+                       datetime(MIN(first_ts) / 1000, 'unixepoch', 'localtime') AS first_active, \
+                       datetime(MAX(last_ts) / 1000, 'unixepoch', 'localtime') AS last_active \
+                FROM sessions \
+                WHERE parent_session_id IS NULL {SF:id} \
+                GROUP BY project \
+                ORDER BY MAX(last_ts) DESC"
+                .into(),
+        },
+        // project-tools: Top tools of each project
+        // Supports --session-filter via {SF:u.session_id}
+        PerspectiveInfo {
+            name: "project-tools".into(),
+            description: "프로젝트별 도구 사용 빈도".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("5".into()),
+                description: "프로젝트당 상위 N개".into(),
+            }],
+            sql: "\
+                SELECT project, tool, frequency, sessions, share \
+                FROM ( \
+                    SELECT project, tool, frequency, sessions, \
+                           ROUND(CAST(frequency AS REAL) / SUM(frequency) OVER (PARTITION BY project), 3) AS share, \
+                           ROW_NUMBER() OVER (PARTITION BY project ORDER BY frequency DESC, tool) AS rank \
+                    FROM ( \
+                        SELECT s.project, u.classified_name AS tool, \
+                               COUNT(*) AS frequency, \
+                               COUNT(DISTINCT u.session_id) AS sessions \
+                        FROM tool_uses u \
+                        JOIN sessions s ON s.id = u.session_id \
+                        WHERE 1=1 {SF:u.session_id} \
+                        GROUP BY s.project, u.classified_name \
+                    ) \
+                ) \
+                WHERE rank <= :top \
+                ORDER BY project, rank"
+                .into(),
+        },
+        // shared-hotfiles: Files edited in several projects, by project-relative path
+        // A file under its project's directory is compared by the path below it
+        // (matched against the encoded project name), anything else by its
+        // absolute path.
+        // Derived table — session filter not applicable; project filter via {PF:project}
+        PerspectiveInfo {
+            name: "shared-hotfiles".into(),
+            description: "여러 프로젝트에서 자주 편집되는 파일 (프로젝트 기준 상대 경로)".into(),
+            params: vec![
+                ParamDef {
+                    name: "min_projects".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("2".into()),
+                    description: "최소 프로젝트 수".into(),
+                },
+                ParamDef {
+                    name: "top".into(),
+                    param_type: ParamType::Integer,
+                    required: false,
+                    default: Some("20".into()),
+                    description: "상위 N개".into(),
+                },
+            ],
+            sql: "\
+                SELECT file, COUNT(DISTINCT project) AS projects, \
+                       SUM(edit_count) AS edit_count, SUM(session_count) AS session_count, \
+                       GROUP_CONCAT(DISTINCT project) AS project_names \
+                FROM ( \
+                    SELECT project, edit_count, session_count, \
+                           CASE WHEN SUBSTR(file_path, LENGTH(project) + 1, 1) = '/' \
+                                 AND REPLACE(REPLACE(REPLACE(SUBSTR(file_path, 1, LENGTH(project)), '/', '-'), '.', '-'), '_', '-') \
+                                   = REPLACE(REPLACE(project, '.', '-'), '_', '-') \
+                                THEN SUBSTR(file_path, LENGTH(project) + 2) \
+                                ELSE file_path END AS file \
+                    FROM file_hotspots \
+                    WHERE 1=1 {PF:project} \
+                ) \
+                GROUP BY file \
+                HAVING projects >= :min_projects \
+                ORDER BY projects DESC, edit_count DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // branches: Sessions per git branch and project, from prompts and tool uses
+        // Branch names are only compared within a project (`main` of two
+        // repos stays two rows in the global DB).
+        // Supports --session-filter via {SF:b.session_id} / {SF:c.session_id}
+        PerspectiveInfo {
+            name: "branches".into(),
+            description: "프로젝트 · git 브랜치별 세션 / 커밋 수".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("20".into()),
+                description: "상위 N개".into(),
+            }],
+            sql: "\
+                SELECT s.project, b.branch, COUNT(DISTINCT b.session_id) AS sessions, \
+                       SUM(b.is_prompt) AS prompts, \
+                       SUM(1 - b.is_prompt) AS tool_uses, \
+                       COALESCE(c.commits, 0) AS commits, \
+                       datetime(MIN(b.timestamp) / 1000, 'unixepoch', 'localtime') AS first_seen, \
+                       datetime(MAX(b.timestamp) / 1000, 'unixepoch', 'localtime') AS last_seen \
+                FROM ( \
+                    SELECT session_id, git_branch AS branch, timestamp, 1 AS is_prompt \
+                    FROM prompts WHERE git_branch IS NOT NULL AND git_branch != '' \
+                    UNION ALL \
+                    SELECT session_id, git_branch, timestamp, 0 \
+                    FROM tool_uses WHERE git_branch IS NOT NULL AND git_branch != '' \
+                ) b \
+                JOIN sessions s ON s.id = b.session_id \
+                LEFT JOIN ( \
+                    SELECT cs.project, c.branch, COUNT(DISTINCT c.sha) AS commits \
+                    FROM commits c \
+                    JOIN sessions cs ON cs.id = c.session_id \
+                    WHERE 1=1 {SF:c.session_id} \
+                    GROUP BY cs.project, c.branch \
+                ) c ON c.project = s.project AND c.branch = b.branch \
+                WHERE 1=1 {SF:b.session_id} \
+                GROUP BY s.project, b.branch \
+                ORDER BY sessions DESC, MAX(b.timestamp) DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // session-commits: Commits each session produced via Bash `git commit`
+        // Supports --session-filter via {SF:c.session_id}
+        PerspectiveInfo {
+            name: "session-commits".into(),
+            description: "세션별로 만든 git 커밋".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("20".into()),
+                description: "상위 N개".into(),
+            }],
+            sql: "\
+                SELECT c.session_id, COUNT(*) AS commits, \
+                       GROUP_CONCAT(DISTINCT c.branch) AS branches, \
+                       GROUP_CONCAT(SUBSTR(c.sha, 1, 7), ',') AS shas, \
+                       datetime(MIN(c.timestamp) / 1000, 'unixepoch', 'localtime') AS first_commit_at, \
+                       datetime(MAX(c.timestamp) / 1000, 'unixepoch', 'localtime') AS last_commit_at \
+                FROM (SELECT * FROM commits ORDER BY timestamp, id) c \
+                WHERE 1=1 {SF:c.session_id} \
+                GROUP BY c.session_id \
+                ORDER BY MAX(c.timestamp) DESC \
+                LIMIT :top"
+                .into(),
+        },
+        // time-to-commit: Minutes from a session's first prompt to its first commit
+        // Supports --session-filter via {SF:s.id}
+        PerspectiveInfo {
+            name: "time-to-commit".into(),
+            description: "세션 첫 프롬프트부터 첫 커밋까지 걸린 시간".into(),
+            params: vec![ParamDef {
+                name: "top".into(),
+                param_type: ParamType::Integer,
+                required: false,
+                default: Some("20".into()),
+                description: "상위 N개".into(),
+            }],
+            sql: "\
+                SELECT s.id AS session_id, \
+                       SUBSTR(s.first_prompt_snippet, 1, 100) AS first_prompt, \
+                       datetime(s.first_ts / 1000, 'unixepoch', 'localtime') AS started_at, \
+                       datetime(f.first_commit_ts / 1000, 'unixepoch', 'localtime') AS first_commit_at, \
+                       ROUND((f.first_commit_ts - s.first_ts) / 60000.0, 1) AS minutes_to_first_commit, \
+                       f.commits \
+                FROM sessions s \
+                JOIN ( \
+                    SELECT session_id, MIN(timestamp) AS first_commit_ts, COUNT(*) AS commits \
+                    FROM commits \
+                    GROUP BY session_id \
+                ) f ON f.session_id = s.id \
+                WHERE s.first_ts IS NOT NULL {SF:s.id} \
+                ORDER BY s.first_ts DESC \
+                LIMIT :top"
+                .into(),
+        },
     ]
 }
@@ -6,6 +6,8 @@ use std::path::Path;
 
 pub struct SessionData {
     pub id: String,
+    /// Encoded project directory the file lives in (`-home-user-repo`)
+    pub project: String,
     pub file_path: String,
     pub file_size: u64,
     pub file_mtime: i64,
@@ -14,9 +16,20 @@ pub struct SessionData {
     pub prompt_count: usize,
     pub tool_use_count: usize,
     pub first_prompt_snippet: Option<String>,
+    /// Session that spawned this one, for a subagent transcript file
+    pub parent_session_id: Option<String>,
+    /// Byte offset just past the last parsed line
+    pub parsed_offset: u64,
+    /// Start offset and hash of the last parsed entry, to verify the prefix
+    /// before appending
+    pub last_entry: Option<(u64, String)>,
     pub prompts: Vec<PromptData>,
     pub tool_uses: Vec<ToolUseData>,
+    pub tool_results: Vec<ToolResultData>,
     pub file_edits: Vec<FileEditData>,
+    pub assistant_messages: Vec<AssistantMessageData>,
+    pub messages: Vec<MessageData>,
+    pub subagents: Vec<SubagentData>,
 }
 
 pub struct PromptData {
@@ -24,6 +37,8 @@ pub struct PromptData {
     pub timestamp: i64,
     pub char_count: usize,
     pub role: crate::parsers::filters::PromptRole,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
 }
 
 pub struct ToolUseData {
@@ -32,6 +47,72 @@ pub struct ToolUseData {
     pub classified_name: String,
     pub timestamp: Option<i64>,
     pub input_json: Option<String>,
+    /// `tool_use` block id that results refer back to
+    pub block_id: Option<String>,
+    /// Entry the block came from; the tool use takes that entry's agent
+    pub message_uuid: Option<String>,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+/// Linked to its tool use by block id when stored, so a result appended later
+/// can still reach a tool use indexed in an earlier run.
+pub struct ToolResultData {
+    pub tool_use_block_id: String,
+    pub timestamp: Option<i64>,
+    pub is_error: bool,
+    pub output_size: usize,
+    pub error_text: Option<String>,
+    /// Commit the output reports; stored only when the tool use is a Bash
+    /// `git commit`
+    pub commit: Option<CommitData>,
+}
+
+pub struct CommitData {
+    pub sha: String,
+    pub branch: String,
+    pub subject: String,
+}
+
+/// Stored keyed by message id, so a message split across an append boundary
+/// ends up as one row.
+pub struct AssistantMessageData {
+    pub message_id: Option<String>,
+    pub model: Option<String>,
+    pub timestamp: Option<i64>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+}
+
+/// A transcript entry in the uuid / parentUuid tree. Sidechain entries with
+/// no `agent_id` inherit their parent's when stored.
+pub struct MessageData {
+    pub uuid: String,
+    pub parent_uuid: Option<String>,
+    pub entry_type: String,
+    pub is_sidechain: bool,
+    pub agent_id: Option<String>,
+    pub timestamp: Option<i64>,
+}
+
+/// A subagent run starting in this session's file. Its type is looked up by
+/// matching `prompt` against the `Task` calls of `spawn_session_id`.
+pub struct SubagentData {
+    pub agent_id: String,
+    pub spawn_session_id: String,
+    pub prompt: Option<String>,
+}
+
+/// USD per million tokens for models whose id starts with `model`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelPrice {
+    pub model: String,
+    pub input: f64,
+    pub output: f64,
+    pub cache_read: f64,
+    pub cache_creation: f64,
 }
 
 pub struct FileEditData {
@@ -40,10 +121,32 @@ pub struct FileEditData {
     pub timestamp: Option<i64>,
 }
 
+/// What an indexed session looked like after its last run — where to resume
+/// parsing, and the totals the appended rows add to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionCheckpoint {
+    pub parsed_offset: u64,
+    pub last_entry_offset: u64,
+    pub last_entry_hash: String,
+    pub prompt_count: usize,
+    pub tool_use_count: usize,
+    pub first_ts: Option<i64>,
+    pub last_ts: Option<i64>,
+    pub first_prompt_snippet: Option<String>,
+    pub has_human_prompt: bool,
+}
+
+/// One parsed session on its way to the store, as decided by `check_session`.
+pub enum SessionWrite {
+    Upsert(SessionData),
+    Append(SessionData),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SessionStatus {
     New,
-    Changed,
+    /// Size or mtime differ; carries the checkpoint when one was recorded.
+    Changed(Option<SessionCheckpoint>),
     Unchanged,
 }
 
@@ -72,6 +175,8 @@ pub enum ParamType {
     Float,
     Text,
     Date,
+    /// Free text turned into an FTS5 MATCH expression
+    Search,
 }
 
 pub type QueryParams = HashMap<String, String>;
@@ -83,9 +188,27 @@ pub trait IndexRepository {
     fn initialize(&self) -> Result<()>;
     fn check_session(&self, file_path: &Path, size: u64, mtime: i64) -> Result<SessionStatus>;
     fn upsert_session(&self, session: &SessionData) -> Result<()>;
-    fn remove_stale_sessions(&self, existing_paths: &[&Path]) -> Result<u64>;
-    fn rebuild_derived_tables(&self) -> Result<()>;
+    /// Adds the rows of a parsed tail to an indexed session. Session-level
+    /// fields in `session` are the new totals; `seq_order`s continue the
+    /// existing sequence.
+    fn append_session(&self, session: &SessionData) -> Result<()>;
+    /// Applies a batch of upserts and appends in a single transaction.
+    fn write_sessions(&self, batch: &[SessionWrite]) -> Result<()>;
+    /// Removes the sessions of `projects` (every project when `None`) whose
+    /// files are not in `existing_paths`.
+    fn remove_stale_sessions(
+        &self,
+        existing_paths: &[&Path],
+        projects: Option<&[&str]>,
+    ) -> Result<u64>;
+    /// Removes the sessions indexed from exactly these files.
+    fn remove_sessions(&self, file_paths: &[&Path]) -> Result<u64>;
+    /// Finishes derived-table maintenance after sessions were upserted,
+    /// appended or removed; those calls already applied their own deltas.
+    fn refresh_derived_tables(&self) -> Result<()>;
     fn update_meta(&self, key: &str, value: &str) -> Result<()>;
+    /// Replaces the price table that cost columns are computed from.
+    fn replace_model_prices(&self, prices: &[ModelPrice]) -> Result<()>;
     fn schema_version(&self) -> Result<Option<u32>>;
 }
 
@@ -96,6 +219,7 @@ pub trait QueryRepository {
         perspective: &str,
         params: &QueryParams,
         session_filter: Option<&str>,
+        project_filter: Option<&str>,
     ) -> Result<serde_json::Value>;
     fn execute_sql(&self, sql: &str) -> Result<serde_json::Value>;
 }
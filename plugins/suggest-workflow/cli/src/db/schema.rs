@@ -1,4 +1,4 @@
-pub const SCHEMA_VERSION: u32 = 5;
+pub const SCHEMA_VERSION: u32 = 13;
 
 pub const DDL: &str = "
 -- 메타 정보
@@ -7,7 +7,7 @@ CREATE TABLE IF NOT EXISTS meta (
     value TEXT NOT NULL
 );
 
--- 세션 목록
+-- 세션 목록. project: ~/.claude/projects 아래 인코딩된 프로젝트 디렉토리 이름
 CREATE TABLE IF NOT EXISTS sessions (
     id             TEXT PRIMARY KEY,
     file_path      TEXT NOT NULL,
@@ -18,21 +18,31 @@ CREATE TABLE IF NOT EXISTS sessions (
     prompt_count   INTEGER NOT NULL DEFAULT 0,
     tool_use_count INTEGER NOT NULL DEFAULT 0,
     first_prompt_snippet TEXT,
-    indexed_at     TEXT NOT NULL
+    indexed_at     TEXT NOT NULL,
+    parsed_offset     INTEGER NOT NULL DEFAULT 0,
+    last_entry_offset INTEGER,
+    last_entry_hash   TEXT,
+    parent_session_id TEXT,
+    project           TEXT NOT NULL DEFAULT ''
 );
+CREATE INDEX IF NOT EXISTS idx_sessions_project ON sessions(project);
 
--- 프롬프트
+-- 프롬프트. cwd / git_branch: 엔트리 기록 시점의 작업 디렉토리 / 브랜치
 CREATE TABLE IF NOT EXISTS prompts (
     id         INTEGER PRIMARY KEY AUTOINCREMENT,
     session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
     text       TEXT NOT NULL,
     timestamp  INTEGER NOT NULL,
     char_count INTEGER NOT NULL,
-    role       TEXT NOT NULL DEFAULT 'human'
+    role       TEXT NOT NULL DEFAULT 'human',
+    cwd        TEXT,
+    git_branch TEXT
 );
 CREATE INDEX IF NOT EXISTS idx_prompts_session ON prompts(session_id);
 CREATE INDEX IF NOT EXISTS idx_prompts_ts ON prompts(timestamp);
 CREATE INDEX IF NOT EXISTS idx_prompts_role ON prompts(role);
+-- 프롬프트 전문 검색: prompts_fts (FTS5, rowid = prompts.id)는 빌드에 따라 토크나이저가
+-- 달라 여기가 아니라 fts::ensure_index 가 만든다 (trigram, lindera-korean 이면 형태소 분리 + unicode61)
 
 -- 도구 사용
 CREATE TABLE IF NOT EXISTS tool_uses (
@@ -42,12 +52,85 @@ CREATE TABLE IF NOT EXISTS tool_uses (
     tool_name       TEXT NOT NULL,
     classified_name TEXT NOT NULL,
     timestamp       INTEGER,
-    input_json      TEXT
+    input_json      TEXT,
+    block_id        TEXT,
+    message_uuid    TEXT,
+    agent_id        TEXT,
+    cwd             TEXT,
+    git_branch      TEXT
 );
 CREATE INDEX IF NOT EXISTS idx_tool_uses_session ON tool_uses(session_id);
+CREATE INDEX IF NOT EXISTS idx_tool_uses_block ON tool_uses(session_id, block_id);
 CREATE INDEX IF NOT EXISTS idx_tool_uses_tool ON tool_uses(classified_name);
 CREATE INDEX IF NOT EXISTS idx_tool_uses_ts ON tool_uses(timestamp);
 
+-- 메시지 트리 (uuid / parentUuid). agent_id: 사이드체인(서브에이전트) 실행 id, 메인은 NULL
+CREATE TABLE IF NOT EXISTS messages (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id   TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    uuid         TEXT NOT NULL,
+    parent_uuid  TEXT,
+    entry_type   TEXT NOT NULL,
+    is_sidechain INTEGER NOT NULL DEFAULT 0,
+    agent_id     TEXT,
+    timestamp    INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_messages_uuid ON messages(session_id, uuid);
+CREATE INDEX IF NOT EXISTS idx_messages_parent ON messages(session_id, parent_uuid);
+CREATE INDEX IF NOT EXISTS idx_messages_agent ON messages(session_id, agent_id);
+
+-- 서브에이전트 실행: 이 세션 파일에서 시작된 실행과 생성 프롬프트
+CREATE TABLE IF NOT EXISTS subagents (
+    session_id       TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    agent_id         TEXT NOT NULL,
+    spawn_session_id TEXT NOT NULL,
+    prompt           TEXT,
+    PRIMARY KEY (session_id, agent_id)
+);
+
+-- 서브에이전트 실행별 타입 / 규모: 타입은 생성 세션의 Task 호출 중 prompt 가 같은 것의 subagent_type
+CREATE VIEW IF NOT EXISTS subagent_runs AS
+SELECT a.session_id, a.agent_id, a.spawn_session_id,
+       (SELECT json_extract(u.input_json, '$.subagent_type') FROM tool_uses u
+        WHERE u.session_id = a.spawn_session_id
+          AND u.tool_name IN ('Task', 'Agent')
+          AND json_extract(u.input_json, '$.prompt') = a.prompt
+        ORDER BY u.seq_order
+        LIMIT 1) AS agent_type,
+       (SELECT COUNT(*) FROM tool_uses u
+        WHERE u.session_id = a.session_id AND u.agent_id = a.agent_id) AS tool_use_count,
+       (SELECT MIN(m.timestamp) FROM messages m
+        WHERE m.session_id = a.session_id AND m.agent_id = a.agent_id) AS first_ts,
+       (SELECT MAX(m.timestamp) FROM messages m
+        WHERE m.session_id = a.session_id AND m.agent_id = a.agent_id) AS last_ts
+FROM subagents a;
+
+-- 도구 결과
+CREATE TABLE IF NOT EXISTS tool_results (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id   TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    tool_use_id  INTEGER NOT NULL REFERENCES tool_uses(id) ON DELETE CASCADE,
+    is_error     INTEGER NOT NULL,
+    output_size  INTEGER NOT NULL,
+    error_text   TEXT,
+    duration_ms  INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_tool_results_session ON tool_results(session_id);
+CREATE INDEX IF NOT EXISTS idx_tool_results_tool_use ON tool_results(tool_use_id);
+
+-- 커밋: Bash `git commit` 결과 출력의 `[branch sha] subject` 줄에서 추출
+CREATE TABLE IF NOT EXISTS commits (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id  TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    tool_use_id INTEGER NOT NULL REFERENCES tool_uses(id) ON DELETE CASCADE,
+    sha         TEXT NOT NULL,
+    branch      TEXT NOT NULL,
+    subject     TEXT NOT NULL,
+    timestamp   INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_commits_session ON commits(session_id);
+CREATE INDEX IF NOT EXISTS idx_commits_sha ON commits(sha);
+
 -- 파일 편집
 CREATE TABLE IF NOT EXISTS file_edits (
     id           INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -59,39 +142,86 @@ CREATE TABLE IF NOT EXISTS file_edits (
 CREATE INDEX IF NOT EXISTS idx_file_edits_session ON file_edits(session_id);
 CREATE INDEX IF NOT EXISTS idx_file_edits_path ON file_edits(file_path);
 
+-- 어시스턴트 메시지별 모델 / 토큰 사용량
+CREATE TABLE IF NOT EXISTS assistant_messages (
+    id                    INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id            TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    message_id            TEXT,
+    model                 TEXT,
+    timestamp             INTEGER,
+    input_tokens          INTEGER NOT NULL DEFAULT 0,
+    output_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens     INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens INTEGER NOT NULL DEFAULT 0
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_assistant_messages_msg ON assistant_messages(session_id, message_id);
+CREATE INDEX IF NOT EXISTS idx_assistant_messages_ts ON assistant_messages(timestamp);
+
+-- 모델 가격표 (USD / 1M 토큰, 사용자 제공 — index --prices)
+CREATE TABLE IF NOT EXISTS model_prices (
+    model                   TEXT PRIMARY KEY,
+    input_per_mtok          REAL NOT NULL,
+    output_per_mtok         REAL NOT NULL,
+    cache_read_per_mtok     REAL NOT NULL,
+    cache_creation_per_mtok REAL NOT NULL
+);
+
+-- 메시지별 비용: 모델 id 가 가격표 키로 시작하는 항목 중 가장 긴 것, 없으면 cost_usd = NULL
+CREATE VIEW IF NOT EXISTS message_costs AS
+SELECT m.*,
+       (m.input_tokens * p.input_per_mtok
+        + m.output_tokens * p.output_per_mtok
+        + m.cache_read_tokens * p.cache_read_per_mtok
+        + m.cache_creation_tokens * p.cache_creation_per_mtok) / 1000000.0 AS cost_usd
+FROM assistant_messages m
+LEFT JOIN model_prices p ON p.model = (
+    SELECT p2.model FROM model_prices p2
+    WHERE SUBSTR(m.model, 1, LENGTH(p2.model)) = p2.model
+    ORDER BY LENGTH(p2.model) DESC
+    LIMIT 1
+);
+
+-- 파생 테이블은 프로젝트별로 집계한다 (프로젝트별 DB 에서는 project 가 하나)
+
 -- 도구 전이 (파생)
 CREATE TABLE IF NOT EXISTS tool_transitions (
+    project     TEXT NOT NULL DEFAULT '',
     from_tool   TEXT NOT NULL,
     to_tool     TEXT NOT NULL,
     count       INTEGER NOT NULL,
     probability REAL NOT NULL,
-    PRIMARY KEY (from_tool, to_tool)
+    PRIMARY KEY (project, from_tool, to_tool)
 );
 
 -- 주간 트렌드 (파생)
 CREATE TABLE IF NOT EXISTS weekly_buckets (
+    project        TEXT NOT NULL DEFAULT '',
     week_start     TEXT NOT NULL,
     tool_name      TEXT NOT NULL,
     count          INTEGER NOT NULL,
     session_count  INTEGER NOT NULL,
-    PRIMARY KEY (week_start, tool_name)
+    PRIMARY KEY (project, week_start, tool_name)
 );
 CREATE INDEX IF NOT EXISTS idx_weekly_week ON weekly_buckets(week_start);
 
 -- 파일 핫스팟 (파생)
 CREATE TABLE IF NOT EXISTS file_hotspots (
-    file_path     TEXT PRIMARY KEY,
+    project       TEXT NOT NULL DEFAULT '',
+    file_path     TEXT NOT NULL,
     edit_count    INTEGER NOT NULL,
-    session_count INTEGER NOT NULL
+    session_count INTEGER NOT NULL,
+    PRIMARY KEY (project, file_path)
 );
 
--- 세션 간 연결 (파생)
+-- 세션 간 연결 (파생). 같은 프로젝트의 세션끼리만 연결한다
 CREATE TABLE IF NOT EXISTS session_links (
     session_a       TEXT NOT NULL,
     session_b       TEXT NOT NULL,
     shared_files    INTEGER NOT NULL,
     overlap_ratio   REAL NOT NULL,
     time_gap_minutes INTEGER,
+    project         TEXT NOT NULL DEFAULT '',
     PRIMARY KEY (session_a, session_b)
 );
+CREATE INDEX IF NOT EXISTS idx_session_links_b ON session_links(session_b);
 ";
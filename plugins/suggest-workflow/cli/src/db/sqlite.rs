@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
 
+use super::fts;
 use super::migrate;
 use super::perspectives;
 use super::repository::*;
@@ -52,21 +53,44 @@ impl IndexRepository for SqliteStore {
             }
         }
 
+        // The FTS table's tokenizer depends on the build, so it is created here
+        // rather than in the DDL — and rebuilt when the build changed.
+        fts::ensure_index(&self.conn)?;
+
         Ok(())
     }
 
     fn check_session(&self, file_path: &Path, size: u64, mtime: i64) -> Result<SessionStatus> {
         let path_str = file_path.to_string_lossy();
         match self.conn.query_row(
-            "SELECT file_size, file_mtime FROM sessions WHERE file_path = ?1",
+            "SELECT file_size, file_mtime, parsed_offset, last_entry_offset, last_entry_hash,
+                    prompt_count, tool_use_count, first_ts, last_ts, first_prompt_snippet,
+                    EXISTS(SELECT 1 FROM prompts p WHERE p.session_id = sessions.id AND p.role = 'human')
+             FROM sessions WHERE file_path = ?1",
             params![path_str.as_ref()],
-            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            |row| {
+                let checkpoint = match (row.get::<_, Option<i64>>(3)?, row.get::<_, Option<String>>(4)?) {
+                    (Some(last_entry_offset), Some(last_entry_hash)) => Some(SessionCheckpoint {
+                        parsed_offset: row.get::<_, i64>(2)? as u64,
+                        last_entry_offset: last_entry_offset as u64,
+                        last_entry_hash,
+                        prompt_count: row.get::<_, i64>(5)? as usize,
+                        tool_use_count: row.get::<_, i64>(6)? as usize,
+                        first_ts: row.get(7)?,
+                        last_ts: row.get(8)?,
+                        first_prompt_snippet: row.get(9)?,
+                        has_human_prompt: row.get(10)?,
+                    }),
+                    _ => None,
+                };
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, checkpoint))
+            },
         ) {
-            Ok((saved_size, saved_mtime)) => {
+            Ok((saved_size, saved_mtime, checkpoint)) => {
                 if size as i64 == saved_size && mtime == saved_mtime {
                     Ok(SessionStatus::Unchanged)
                 } else {
-                    Ok(SessionStatus::Changed)
+                    Ok(SessionStatus::Changed(checkpoint))
                 }
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(SessionStatus::New),
@@ -76,188 +100,101 @@ impl IndexRepository for SqliteStore {
 
     fn upsert_session(&self, session: &SessionData) -> Result<()> {
         let tx = self.conn.unchecked_transaction()?;
+        upsert_in(&tx, session)?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        // Delete existing data (CASCADE deletes prompts, tool_uses, file_edits)
-        tx.execute("DELETE FROM sessions WHERE id = ?1", params![&session.id])?;
-
-        let now = chrono::Utc::now().to_rfc3339();
-
-        // Insert session
-        tx.execute(
-            "INSERT INTO sessions (id, file_path, file_size, file_mtime, first_ts, last_ts, prompt_count, tool_use_count, first_prompt_snippet, indexed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                &session.id,
-                &session.file_path,
-                session.file_size as i64,
-                session.file_mtime,
-                session.first_ts,
-                session.last_ts,
-                session.prompt_count as i64,
-                session.tool_use_count as i64,
-                &session.first_prompt_snippet,
-                &now,
-            ],
-        )?;
-
-        // Insert prompts
-        {
-            let mut stmt = tx.prepare(
-                "INSERT INTO prompts (session_id, text, timestamp, char_count, role) VALUES (?1, ?2, ?3, ?4, ?5)",
-            )?;
-            for p in &session.prompts {
-                stmt.execute(params![
-                    &session.id,
-                    &p.text,
-                    p.timestamp,
-                    p.char_count as i64,
-                    p.role.as_str()
-                ])?;
-            }
-        }
-
-        // Insert tool_uses
-        {
-            let mut stmt = tx.prepare(
-                "INSERT INTO tool_uses (session_id, seq_order, tool_name, classified_name, timestamp, input_json)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            )?;
-            for t in &session.tool_uses {
-                stmt.execute(params![
-                    &session.id,
-                    t.seq_order as i64,
-                    &t.tool_name,
-                    &t.classified_name,
-                    t.timestamp,
-                    &t.input_json,
-                ])?;
-            }
-        }
+    fn append_session(&self, session: &SessionData) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        append_in(&tx, session)?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        // Insert file_edits (link to tool_uses by matching seq_order)
-        {
-            let mut stmt = tx.prepare(
-                "INSERT INTO file_edits (session_id, tool_use_id, file_path, timestamp)
-                 VALUES (?1, (SELECT id FROM tool_uses WHERE session_id = ?1 AND seq_order = ?2), ?3, ?4)",
-            )?;
-            for f in &session.file_edits {
-                stmt.execute(params![
-                    &session.id,
-                    f.tool_use_seq as i64,
-                    &f.file_path,
-                    f.timestamp,
-                ])?;
+    fn write_sessions(&self, batch: &[SessionWrite]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for write in batch {
+            match write {
+                SessionWrite::Upsert(session) => upsert_in(&tx, session)?,
+                SessionWrite::Append(session) => append_in(&tx, session)?,
             }
         }
-
         tx.commit()?;
         Ok(())
     }
 
-    fn remove_stale_sessions(&self, existing_paths: &[&Path]) -> Result<u64> {
-        // Get all session file_paths from DB
-        let mut stmt = self.conn.prepare("SELECT id, file_path FROM sessions")?;
-
-        let db_sessions: Vec<(String, String)> = stmt
-            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+    fn remove_stale_sessions(
+        &self,
+        existing_paths: &[&Path],
+        projects: Option<&[&str]>,
+    ) -> Result<u64> {
+        // Get the session file_paths of the projects in scope from DB
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, file_path, project FROM sessions")?;
+
+        let db_sessions: Vec<(String, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
             .collect::<Result<Vec<_>, _>>()?;
+        let in_scope = |project: &str| projects.is_none_or(|ps| ps.contains(&project));
 
         let existing_set: std::collections::HashSet<String> = existing_paths
             .iter()
             .map(|p| p.to_string_lossy().to_string())
             .collect();
 
+        let tx = self.conn.unchecked_transaction()?;
         let mut deleted = 0u64;
-        for (id, path) in &db_sessions {
-            if !existing_set.contains(path) {
-                self.conn
-                    .execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+        for (id, path, project) in &db_sessions {
+            if in_scope(project) && !existing_set.contains(path) {
+                retract_session(&tx, id)?;
+                tx.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
                 deleted += 1;
             }
         }
+        tx.commit()?;
 
         Ok(deleted)
     }
 
-    fn rebuild_derived_tables(&self) -> Result<()> {
+    fn remove_sessions(&self, file_paths: &[&Path]) -> Result<u64> {
         let tx = self.conn.unchecked_transaction()?;
+        let mut deleted = 0u64;
+        for path in file_paths {
+            let id: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM sessions WHERE file_path = ?1",
+                    params![path.to_string_lossy().as_ref()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(id) = id {
+                retract_session(&tx, &id)?;
+                tx.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+                deleted += 1;
+            }
+        }
+        tx.commit()?;
 
-        // Clear derived tables
-        tx.execute_batch(
-            "DELETE FROM tool_transitions;
-             DELETE FROM weekly_buckets;
-             DELETE FROM file_hotspots;
-             DELETE FROM session_links;",
-        )?;
-
-        // tool_transitions: count consecutive tool pairs across all sessions
-        tx.execute_batch(
-            "INSERT INTO tool_transitions (from_tool, to_tool, count, probability)
-             SELECT from_tool, to_tool, pair_count,
-                    CAST(pair_count AS REAL) / from_total
-             FROM (
-                 SELECT t1.classified_name AS from_tool,
-                        t2.classified_name AS to_tool,
-                        COUNT(*) AS pair_count
-                 FROM tool_uses t1
-                 JOIN tool_uses t2
-                   ON t1.session_id = t2.session_id
-                  AND t2.seq_order = t1.seq_order + 1
-                 GROUP BY t1.classified_name, t2.classified_name
-             ) pairs
-             JOIN (
-                 SELECT t1.classified_name AS tool,
-                        COUNT(*) AS from_total
-                 FROM tool_uses t1
-                 JOIN tool_uses t2
-                   ON t1.session_id = t2.session_id
-                  AND t2.seq_order = t1.seq_order + 1
-                 GROUP BY t1.classified_name
-             ) totals ON pairs.from_tool = totals.tool;",
-        )?;
-
-        // file_hotspots: aggregate file_edits
-        tx.execute_batch(
-            "INSERT INTO file_hotspots (file_path, edit_count, session_count)
-             SELECT file_path,
-                    COUNT(*) AS edit_count,
-                    COUNT(DISTINCT session_id) AS session_count
-             FROM file_edits
-             GROUP BY file_path;",
-        )?;
-
-        // weekly_buckets: aggregate tool_uses by ISO week
-        tx.execute_batch(
-            "INSERT INTO weekly_buckets (week_start, tool_name, count, session_count)
-             SELECT strftime('%Y-%m-%d', datetime(timestamp / 1000, 'unixepoch'), 'weekday 0', '-6 days') AS week_start,
-                    classified_name AS tool_name,
-                    COUNT(*) AS count,
-                    COUNT(DISTINCT session_id) AS session_count
-             FROM tool_uses
-             WHERE timestamp IS NOT NULL
-             GROUP BY week_start, classified_name;",
-        )?;
+        Ok(deleted)
+    }
 
-        // session_links: sessions sharing edited files
-        tx.execute_batch(
-            "INSERT INTO session_links (session_a, session_b, shared_files, overlap_ratio, time_gap_minutes)
-             SELECT a.session_id, b.session_id,
-                    COUNT(DISTINCT a.file_path) AS shared_files,
-                    CAST(COUNT(DISTINCT a.file_path) AS REAL) /
-                        MAX(
-                            (SELECT COUNT(DISTINCT file_path) FROM file_edits WHERE session_id = a.session_id),
-                            (SELECT COUNT(DISTINCT file_path) FROM file_edits WHERE session_id = b.session_id)
-                        ) AS overlap_ratio,
-                    ABS(COALESCE(sa.first_ts, 0) - COALESCE(sb.first_ts, 0)) / 60000 AS time_gap_minutes
-             FROM file_edits a
-             JOIN file_edits b ON a.file_path = b.file_path AND a.session_id < b.session_id
-             JOIN sessions sa ON sa.id = a.session_id
-             JOIN sessions sb ON sb.id = b.session_id
-             GROUP BY a.session_id, b.session_id
-             HAVING shared_files >= 1;",
+    fn refresh_derived_tables(&self) -> Result<()> {
+        // Counts are kept current per session (see `contribute_session`); what
+        // is left is dropping emptied rows and renormalizing probabilities,
+        // both bounded by the number of distinct tools and files, not sessions.
+        self.conn.execute_batch(
+            "DELETE FROM tool_transitions WHERE count <= 0;
+             DELETE FROM weekly_buckets WHERE count <= 0;
+             DELETE FROM file_hotspots WHERE edit_count <= 0;
+             UPDATE tool_transitions
+                SET probability = CAST(count AS REAL) / (
+                    SELECT SUM(t.count) FROM tool_transitions t
+                    WHERE t.project = tool_transitions.project
+                      AND t.from_tool = tool_transitions.from_tool
+                );",
         )?;
-
-        tx.commit()?;
         Ok(())
     }
 
@@ -269,6 +206,28 @@ impl IndexRepository for SqliteStore {
         Ok(())
     }
 
+    fn replace_model_prices(&self, prices: &[ModelPrice]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM model_prices", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO model_prices (model, input_per_mtok, output_per_mtok, cache_read_per_mtok, cache_creation_per_mtok)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for p in prices {
+                stmt.execute(params![
+                    &p.model,
+                    p.input,
+                    p.output,
+                    p.cache_read,
+                    p.cache_creation
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     fn schema_version(&self) -> Result<Option<u32>> {
         match self.conn.query_row(
             "SELECT value FROM meta WHERE key = 'schema_version'",
@@ -282,6 +241,360 @@ impl IndexRepository for SqliteStore {
     }
 }
 
+/// Replace a session's rows with `session`, keeping derived tables in step.
+fn upsert_in(tx: &rusqlite::Transaction, session: &SessionData) -> Result<()> {
+    // Delete existing data (CASCADE deletes prompts, tool_uses, tool_results, file_edits)
+    retract_session(tx, &session.id)?;
+    tx.execute("DELETE FROM sessions WHERE id = ?1", params![&session.id])?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let (last_entry_offset, last_entry_hash) = split_last_entry(session);
+
+    // Insert session
+    tx.execute(
+        "INSERT INTO sessions (id, file_path, file_size, file_mtime, first_ts, last_ts, prompt_count, tool_use_count, first_prompt_snippet, indexed_at, parsed_offset, last_entry_offset, last_entry_hash, parent_session_id, project)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            &session.id,
+            &session.file_path,
+            session.file_size as i64,
+            session.file_mtime,
+            session.first_ts,
+            session.last_ts,
+            session.prompt_count as i64,
+            session.tool_use_count as i64,
+            &session.first_prompt_snippet,
+            &now,
+            session.parsed_offset as i64,
+            last_entry_offset,
+            last_entry_hash,
+            &session.parent_session_id,
+            &session.project,
+        ],
+    )?;
+
+    insert_rows(tx, session)?;
+    contribute_session(tx, &session.id)?;
+    Ok(())
+}
+
+/// Add a parsed tail's rows to an indexed session, keeping derived tables in step.
+fn append_in(tx: &rusqlite::Transaction, session: &SessionData) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let (last_entry_offset, last_entry_hash) = split_last_entry(session);
+
+    retract_session(tx, &session.id)?;
+    tx.execute(
+        "UPDATE sessions SET file_size = ?2, file_mtime = ?3, first_ts = ?4, last_ts = ?5,
+                prompt_count = ?6, tool_use_count = ?7, first_prompt_snippet = ?8,
+                indexed_at = ?9, parsed_offset = ?10, last_entry_offset = ?11, last_entry_hash = ?12
+         WHERE id = ?1",
+        params![
+            &session.id,
+            session.file_size as i64,
+            session.file_mtime,
+            session.first_ts,
+            session.last_ts,
+            session.prompt_count as i64,
+            session.tool_use_count as i64,
+            &session.first_prompt_snippet,
+            &now,
+            session.parsed_offset as i64,
+            last_entry_offset,
+            last_entry_hash,
+        ],
+    )?;
+
+    insert_rows(tx, session)?;
+    contribute_session(tx, &session.id)?;
+    Ok(())
+}
+
+/// Remove one session's share of the derived tables — call before its raw rows
+/// change or disappear.
+fn retract_session(tx: &rusqlite::Transaction, session_id: &str) -> Result<()> {
+    add_session_aggregates(tx, session_id, -1)?;
+    tx.execute(
+        "DELETE FROM session_links WHERE session_a = ?1 OR session_b = ?1",
+        params![session_id],
+    )?;
+    Ok(())
+}
+
+/// Add one session's share of the derived tables from its current raw rows.
+fn contribute_session(tx: &rusqlite::Transaction, session_id: &str) -> Result<()> {
+    add_session_aggregates(tx, session_id, 1)?;
+
+    // session_links: this session against every session of its project
+    // sharing an edited file
+    tx.execute(
+        "INSERT INTO session_links (session_a, session_b, shared_files, overlap_ratio, time_gap_minutes, project)
+         SELECT MIN(?1, o.session_id), MAX(?1, o.session_id),
+                COUNT(DISTINCT f.file_path) AS shared_files,
+                CAST(COUNT(DISTINCT f.file_path) AS REAL) /
+                    MAX(
+                        (SELECT COUNT(DISTINCT file_path) FROM file_edits WHERE session_id = ?1),
+                        (SELECT COUNT(DISTINCT file_path) FROM file_edits WHERE session_id = o.session_id)
+                    ) AS overlap_ratio,
+                ABS(COALESCE(s.first_ts, 0) - COALESCE(os.first_ts, 0)) / 60000 AS time_gap_minutes,
+                s.project
+         FROM (SELECT DISTINCT file_path FROM file_edits WHERE session_id = ?1) f
+         JOIN file_edits o ON o.file_path = f.file_path AND o.session_id != ?1
+         JOIN sessions s ON s.id = ?1
+         JOIN sessions os ON os.id = o.session_id AND os.project = s.project
+         GROUP BY o.session_id",
+        params![session_id],
+    )?;
+    Ok(())
+}
+
+/// Add `sign` times one session's counts to its project's rows of the additive
+/// derived tables. Session counts are additive too: a session adds 1 to each
+/// row it touches.
+pub(super) fn add_session_aggregates(
+    tx: &rusqlite::Transaction,
+    session_id: &str,
+    sign: i64,
+) -> Result<()> {
+    // tool_transitions: consecutive tool pairs within one agent, so a
+    // subagent's calls do not break up the main flow; probability is
+    // renormalized in `refresh_derived_tables`
+    tx.execute(
+        "INSERT INTO tool_transitions (project, from_tool, to_tool, count, probability)
+         SELECT (SELECT project FROM sessions WHERE id = ?1), from_tool, to_tool, ?2 * COUNT(*), 0
+         FROM (
+             SELECT classified_name AS from_tool,
+                    LEAD(classified_name) OVER (PARTITION BY agent_id ORDER BY seq_order) AS to_tool
+             FROM tool_uses
+             WHERE session_id = ?1
+         )
+         WHERE to_tool IS NOT NULL
+         GROUP BY from_tool, to_tool
+         ON CONFLICT (project, from_tool, to_tool) DO UPDATE SET count = count + excluded.count",
+        params![session_id, sign],
+    )?;
+
+    // file_hotspots: file_edits per file
+    tx.execute(
+        "INSERT INTO file_hotspots (project, file_path, edit_count, session_count)
+         SELECT (SELECT project FROM sessions WHERE id = ?1), file_path, ?2 * COUNT(*), ?2
+         FROM file_edits
+         WHERE session_id = ?1
+         GROUP BY file_path
+         ON CONFLICT (project, file_path) DO UPDATE SET
+             edit_count = edit_count + excluded.edit_count,
+             session_count = session_count + excluded.session_count",
+        params![session_id, sign],
+    )?;
+
+    // weekly_buckets: tool_uses by ISO week
+    tx.execute(
+        "INSERT INTO weekly_buckets (project, week_start, tool_name, count, session_count)
+         SELECT (SELECT project FROM sessions WHERE id = ?1),
+                strftime('%Y-%m-%d', datetime(timestamp / 1000, 'unixepoch'), 'weekday 0', '-6 days') AS week_start,
+                classified_name, ?2 * COUNT(*), ?2
+         FROM tool_uses
+         WHERE session_id = ?1 AND timestamp IS NOT NULL
+         GROUP BY week_start, classified_name
+         ON CONFLICT (project, week_start, tool_name) DO UPDATE SET
+             count = count + excluded.count,
+             session_count = session_count + excluded.session_count",
+        params![session_id, sign],
+    )?;
+    Ok(())
+}
+
+/// `session.last_entry` as the two nullable columns it is stored in.
+fn split_last_entry(session: &SessionData) -> (Option<i64>, Option<&str>) {
+    match &session.last_entry {
+        Some((offset, hash)) => (Some(*offset as i64), Some(hash.as_str())),
+        None => (None, None),
+    }
+}
+
+/// Insert a session's prompts, message tree, tool uses, tool results, file
+/// edits and assistant messages.
+fn insert_rows(tx: &rusqlite::Transaction, session: &SessionData) -> Result<()> {
+    // Insert prompts and their full-text entries (deleted by trigger with the prompt)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO prompts (session_id, text, timestamp, char_count, role, cwd, git_branch)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        let mut fts_stmt = tx.prepare("INSERT INTO prompts_fts (rowid, text) VALUES (?1, ?2)")?;
+        for p in &session.prompts {
+            stmt.execute(params![
+                &session.id,
+                &p.text,
+                p.timestamp,
+                p.char_count as i64,
+                p.role.as_str(),
+                &p.cwd,
+                &p.git_branch,
+            ])?;
+            fts_stmt.execute(params![tx.last_insert_rowid(), fts::index_text(&p.text)])?;
+        }
+    }
+
+    // Insert messages (the uuid / parentUuid tree) and the subagent runs rooted in them
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO messages (session_id, uuid, parent_uuid, entry_type, is_sidechain, agent_id, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for m in &session.messages {
+            stmt.execute(params![
+                &session.id,
+                &m.uuid,
+                &m.parent_uuid,
+                &m.entry_type,
+                m.is_sidechain,
+                &m.agent_id,
+                m.timestamp,
+            ])?;
+        }
+    }
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR IGNORE INTO subagents (session_id, agent_id, spawn_session_id, prompt)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for a in &session.subagents {
+            stmt.execute(params![
+                &session.id,
+                &a.agent_id,
+                &a.spawn_session_id,
+                &a.prompt
+            ])?;
+        }
+    }
+    // Sidechain entries continuing a run take its agent, including runs that
+    // started in an earlier chunk.
+    tx.execute(
+        "WITH RECURSIVE resolved(uuid, agent_id) AS (
+             SELECT uuid, agent_id FROM messages
+             WHERE session_id = ?1 AND is_sidechain = 1 AND agent_id IS NOT NULL
+             UNION
+             SELECT m.uuid, r.agent_id
+             FROM messages m JOIN resolved r ON m.parent_uuid = r.uuid
+             WHERE m.session_id = ?1 AND m.is_sidechain = 1 AND m.agent_id IS NULL
+         )
+         UPDATE messages SET agent_id = (SELECT r.agent_id FROM resolved r WHERE r.uuid = messages.uuid)
+         WHERE session_id = ?1 AND is_sidechain = 1 AND agent_id IS NULL",
+        params![&session.id],
+    )?;
+
+    // Insert tool_uses (attributed to the agent of the entry they came from)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO tool_uses (session_id, seq_order, tool_name, classified_name, timestamp, input_json, block_id, message_uuid, agent_id, cwd, git_branch)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                     (SELECT agent_id FROM messages WHERE session_id = ?1 AND uuid = ?8), ?9, ?10)",
+        )?;
+        for t in &session.tool_uses {
+            stmt.execute(params![
+                &session.id,
+                t.seq_order as i64,
+                &t.tool_name,
+                &t.classified_name,
+                t.timestamp,
+                &t.input_json,
+                &t.block_id,
+                &t.message_uuid,
+                &t.cwd,
+                &t.git_branch,
+            ])?;
+        }
+    }
+
+    // Insert tool_results (link to tool_uses by block id; unmatched results are dropped)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO tool_results (session_id, tool_use_id, is_error, output_size, error_text, duration_ms)
+             SELECT ?1, u.id, ?3, ?4, ?5, MAX(?6 - u.timestamp, 0)
+             FROM tool_uses u
+             WHERE u.session_id = ?1 AND u.block_id = ?2",
+        )?;
+        for r in &session.tool_results {
+            stmt.execute(params![
+                &session.id,
+                &r.tool_use_block_id,
+                r.is_error,
+                r.output_size as i64,
+                &r.error_text,
+                r.timestamp,
+            ])?;
+        }
+    }
+
+    // Insert commits reported by Bash `git commit` runs (linked like tool_results)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO commits (session_id, tool_use_id, sha, branch, subject, timestamp)
+             SELECT ?1, u.id, ?3, ?4, ?5, ?6
+             FROM tool_uses u
+             WHERE u.session_id = ?1 AND u.block_id = ?2 AND u.tool_name = 'Bash'
+               AND json_extract(u.input_json, '$.command') LIKE '%git%commit%'",
+        )?;
+        for r in &session.tool_results {
+            if let Some(c) = &r.commit {
+                stmt.execute(params![
+                    &session.id,
+                    &r.tool_use_block_id,
+                    &c.sha,
+                    &c.branch,
+                    &c.subject,
+                    r.timestamp,
+                ])?;
+            }
+        }
+    }
+
+    // Insert file_edits (link to tool_uses by matching seq_order)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO file_edits (session_id, tool_use_id, file_path, timestamp)
+             VALUES (?1, (SELECT id FROM tool_uses WHERE session_id = ?1 AND seq_order = ?2), ?3, ?4)",
+        )?;
+        for f in &session.file_edits {
+            stmt.execute(params![
+                &session.id,
+                f.tool_use_seq as i64,
+                &f.file_path,
+                f.timestamp,
+            ])?;
+        }
+    }
+
+    // Insert assistant_messages (a message id seen in an earlier run takes the newer counts)
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO assistant_messages (session_id, message_id, model, timestamp, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (session_id, message_id) DO UPDATE SET
+                 model = excluded.model,
+                 input_tokens = excluded.input_tokens,
+                 output_tokens = excluded.output_tokens,
+                 cache_read_tokens = excluded.cache_read_tokens,
+                 cache_creation_tokens = excluded.cache_creation_tokens",
+        )?;
+        for m in &session.assistant_messages {
+            stmt.execute(params![
+                &session.id,
+                &m.message_id,
+                &m.model,
+                m.timestamp,
+                m.input_tokens as i64,
+                m.output_tokens as i64,
+                m.cache_read_tokens as i64,
+                m.cache_creation_tokens as i64,
+            ])?;
+        }
+    }
+
+    Ok(())
+}
+
 // --- QueryRepository implementation ---
 
 impl QueryRepository for SqliteStore {
@@ -294,6 +607,7 @@ impl QueryRepository for SqliteStore {
         perspective: &str,
         params: &QueryParams,
         session_filter: Option<&str>,
+        project_filter: Option<&str>,
     ) -> Result<serde_json::Value> {
         let info = self
             .perspectives
@@ -313,8 +627,24 @@ impl QueryRepository for SqliteStore {
             validate_session_filter(filter)?;
         }
 
-        // Apply session filter: expand {SF:column} placeholders
-        let filtered_sql = apply_session_filter(&info.sql, session_filter);
+        // A project filter narrows raw rows through their sessions and
+        // derived rows through their own project column
+        let project_clause = project_filter.map(|p| format!("project = {}", sql_quote(p)));
+        let session_filter = match (&project_clause, session_filter) {
+            (Some(p), Some(f)) => Some(format!("{} AND ({})", p, f)),
+            (Some(p), None) => Some(p.clone()),
+            (None, f) => f.map(str::to_string),
+        };
+
+        // Apply filters: expand {SF:column} and {PF:column} placeholders
+        let filtered_sql = expand_markers(&info.sql, "SF", |column| match &session_filter {
+            Some(f) => format!("AND {} IN (SELECT id FROM sessions WHERE {})", column, f),
+            None => String::new(),
+        });
+        let filtered_sql = expand_markers(&filtered_sql, "PF", |column| match project_filter {
+            Some(p) => format!("AND {} = {}", column, sql_quote(p)),
+            None => String::new(),
+        });
 
         // Replace :name with ?N and build bind values
         let (bound_sql, bind_values) = bind_named_params(&filtered_sql, &info.params, params)?;
@@ -355,27 +685,39 @@ fn validate_session_filter(filter: &str) -> Result<()> {
     Ok(())
 }
 
-/// Expand `{SF:column}` placeholders in perspective SQL.
+/// Expand `{<marker>:column}` placeholders in perspective SQL, replacing each
+/// with `replacement(column)`.
 ///
-/// - If `filter` is None: remove all `{SF:...}` markers.
-/// - If `filter` is Some: replace `{SF:col}` with `AND col IN (SELECT id FROM sessions WHERE <filter>)`.
-fn apply_session_filter(sql: &str, filter: Option<&str>) -> String {
+/// - `{SF:col}` narrows raw rows through their session:
+///   `AND col IN (SELECT id FROM sessions WHERE <filter>)`.
+/// - `{PF:col}` narrows derived rows by their own project column:
+///   `AND col = '<project>'`.
+///
+/// Without a filter the replacement is empty, which removes the markers.
+/// Scanning resumes after each replacement, so a marker inside a filter value
+/// is never expanded again.
+fn expand_markers(sql: &str, marker: &str, replacement: impl Fn(&str) -> String) -> String {
+    let open = format!("{{{}:", marker);
     let mut result = sql.to_string();
-    while let Some(start) = result.find("{SF:") {
+    let mut from = 0;
+    while let Some(offset) = result[from..].find(&open) {
+        let start = from + offset;
         let end = match result[start..].find('}') {
             Some(offset) => start + offset + 1,
             None => break,
         };
-        let column = result[start + 4..end - 1].to_string();
-        let replacement = match filter {
-            Some(f) => format!("AND {} IN (SELECT id FROM sessions WHERE {})", column, f),
-            None => String::new(),
-        };
-        result = format!("{}{}{}", &result[..start], replacement, &result[end..]);
+        let expanded = replacement(&result[start + open.len()..end - 1]);
+        from = start + expanded.len();
+        result.replace_range(start..end, &expanded);
     }
     result
 }
 
+/// Quote `value` as an SQL string literal.
+fn sql_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
 /// Replace `:name` placeholders with `?N` positional params and build bind values array.
 fn bind_named_params(
     sql: &str,
@@ -422,6 +764,7 @@ fn coerce_value(raw: &str, param_type: &ParamType) -> Result<rusqlite::types::Va
                 .with_context(|| format!("expected date YYYY-MM-DD, got '{}'", raw))?;
             Ok(rusqlite::types::Value::Text(raw.to_string()))
         }
+        ParamType::Search => Ok(rusqlite::types::Value::Text(fts::match_query(raw)?)),
     }
 }
 
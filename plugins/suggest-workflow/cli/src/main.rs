@@ -41,19 +41,43 @@ struct IndexArgs {
     /// Project path (defaults to current directory)
     #[arg(long)]
     project: Option<String>,
+    /// Index every project under ~/.claude/projects into the global DB
+    #[arg(long, conflicts_with = "project")]
+    all_projects: bool,
     /// Direct DB file path (overrides --project based resolution)
     #[arg(long)]
     db: Option<PathBuf>,
     /// Full rebuild: delete existing DB and re-index everything
     #[arg(long)]
     full: bool,
+    /// Maximum number of session files parsed in parallel (defaults to one per CPU)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+    /// Price table (JSON, USD per million tokens per model) for cost columns.
+    /// Defaults to ~/.claude/suggest-workflow/prices.json when present
+    #[arg(long)]
+    prices: Option<PathBuf>,
+    /// Keep running and re-index session files as they change
+    #[arg(long)]
+    watch: bool,
+    /// With --watch: quiet period before changed files are re-indexed
+    /// (at most 10 periods after the first change)
+    #[arg(long, value_name = "MS", default_value_t = 1000, requires = "watch")]
+    debounce_ms: u64,
+    /// With --watch: poll for changes instead of native notifications
+    #[arg(long, requires = "watch")]
+    poll: bool,
 }
 
 #[derive(clap::Args)]
 struct QueryArgs {
-    /// Project path (defaults to current directory)
+    /// Project path (defaults to current directory).
+    /// With --all-projects, limits perspectives to this project instead
     #[arg(long)]
     project: Option<String>,
+    /// Query the global DB built by `index --all-projects`
+    #[arg(long)]
+    all_projects: bool,
     /// Direct DB file path (overrides --project based resolution)
     #[arg(long)]
     db: Option<PathBuf>,
@@ -170,6 +194,31 @@ fn main() -> Result<()> {
 // --- v3: index subcommand ---
 
 fn run_index(args: IndexArgs) -> Result<()> {
+    let options = commands::index::IndexOptions {
+        jobs: args.jobs.map(usize::from),
+        prices: args.prices,
+    };
+    let watch = args.watch.then(|| commands::watch::WatchOptions {
+        debounce: std::time::Duration::from_millis(args.debounce_ms),
+        poll: args.poll,
+    });
+
+    if args.all_projects {
+        let db_path = resolve_global_db_path(args.db.as_deref())?;
+        let projects_dir = resolve_projects_dir()?;
+        let lock = db::IndexLock::exclusive(&db_path)?;
+        if args.full {
+            let _ = std::fs::remove_file(&db_path);
+        }
+        let store = db::SqliteStore::open(&db_path)?;
+        eprintln!("DB: {}", db_path.display());
+        if let Some(watch) = &watch {
+            drop(lock);
+            return commands::watch::run(&store, &projects_dir, true, &db_path, &options, watch);
+        }
+        return commands::index::run_all(&store, &projects_dir, &options);
+    }
+
     let project_path = match &args.project {
         Some(p) => p.clone(),
         None => std::env::current_dir()
@@ -179,6 +228,7 @@ fn run_index(args: IndexArgs) -> Result<()> {
     };
 
     let db_path = resolve_db_path(args.db.as_deref(), &project_path)?;
+    let lock = db::IndexLock::exclusive(&db_path)?;
 
     // --full: delete existing DB
     if args.full {
@@ -189,29 +239,49 @@ fn run_index(args: IndexArgs) -> Result<()> {
     let store = db::SqliteStore::open(&db_path)?;
 
     eprintln!("DB: {}", db_path.display());
-    commands::index::run(&store, &sessions_dir)
+    if let Some(watch) = &watch {
+        // Each watch pass takes the lock itself, so queries can run in between
+        drop(lock);
+        return commands::watch::run(&store, &sessions_dir, false, &db_path, &options, watch);
+    }
+    commands::index::run(&store, &sessions_dir, &options)
 }
 
 // --- v3: query subcommand ---
 
 fn run_query(args: QueryArgs) -> Result<()> {
-    let project_path = match &args.project {
-        Some(p) => p.clone(),
-        None => std::env::current_dir()
-            .context("failed to get current directory")?
-            .to_string_lossy()
-            .to_string(),
+    let (db_path, project_filter) = if args.all_projects {
+        let filter = args
+            .project
+            .as_deref()
+            .map(encode_project_path)
+            .transpose()?;
+        (resolve_global_db_path(args.db.as_deref())?, filter)
+    } else {
+        let project_path = match &args.project {
+            Some(p) => p.clone(),
+            None => std::env::current_dir()
+                .context("failed to get current directory")?
+                .to_string_lossy()
+                .to_string(),
+        };
+        (resolve_db_path(args.db.as_deref(), &project_path)?, None)
     };
 
-    let db_path = resolve_db_path(args.db.as_deref(), &project_path)?;
-
     if !db_path.exists() {
+        let index_cmd = if args.all_projects {
+            "suggest-workflow index --all-projects"
+        } else {
+            "suggest-workflow index"
+        };
         anyhow::bail!(
-            "index DB not found: {}\nRun 'suggest-workflow index' first.",
-            db_path.display()
+            "index DB not found: {}\nRun '{}' first.",
+            db_path.display(),
+            index_cmd
         );
     }
 
+    let _lock = db::IndexLock::shared(&db_path)?;
     let store = db::SqliteStore::open(&db_path)?;
 
     if args.list_perspectives {
@@ -226,6 +296,7 @@ fn run_query(args: QueryArgs) -> Result<()> {
         args.sql_file.as_deref(),
         params,
         args.session_filter.as_deref(),
+        project_filter.as_deref(),
     )
 }
 
@@ -243,6 +314,26 @@ fn resolve_db_path(db: Option<&std::path::Path>, project_path: &str) -> Result<P
         .join("index.db"))
 }
 
+/// Global DB for `--all-projects`. Encoded project names start with '-',
+/// so `global` cannot collide with a per-project directory.
+fn resolve_global_db_path(db: Option<&std::path::Path>) -> Result<PathBuf> {
+    if let Some(db_path) = db {
+        return Ok(db_path.to_path_buf());
+    }
+
+    let home = std::env::var("HOME").context("HOME not set")?;
+    Ok(PathBuf::from(home)
+        .join(".claude")
+        .join("suggest-workflow-index")
+        .join("global")
+        .join("index.db"))
+}
+
+fn resolve_projects_dir() -> Result<PathBuf> {
+    let home = std::env::var("HOME").context("HOME not set")?;
+    Ok(PathBuf::from(home).join(".claude").join("projects"))
+}
+
 fn resolve_sessions_dir(project_path: &str) -> Result<PathBuf> {
     let encoded = encode_project_path(project_path)?;
     let home = std::env::var("HOME").context("HOME not set")?;
@@ -344,7 +435,10 @@ fn run_legacy(cli: LegacyArgs) -> Result<()> {
 
         if sessions_dir.exists() {
             let store = db::SqliteStore::open(&db_path)?;
-            commands::index::run(&store, &sessions_dir)?;
+            {
+                let _lock = db::IndexLock::exclusive(&db_path)?;
+                commands::index::run(&store, &sessions_dir, &Default::default())?;
+            }
             eprintln!("Index DB: {}", db_path.display());
 
             return commands::cache::run(
@@ -376,8 +470,9 @@ fn run_legacy(cli: LegacyArgs) -> Result<()> {
     let sessions_dir = resolve_sessions_dir(&project_path)?;
 
     if sessions_dir.exists() {
+        let _lock = db::IndexLock::exclusive(&db_path)?;
         let store = db::SqliteStore::open(&db_path)?;
-        commands::index::run(&store, &sessions_dir)?;
+        commands::index::run(&store, &sessions_dir, &Default::default())?;
     }
 
     let scope: AnalysisScope = cli.scope.parse().map_err(|e: String| anyhow::anyhow!(e))?;
@@ -2,6 +2,7 @@ pub mod filters;
 pub mod projects;
 
 pub use projects::{
-    adapt_to_history_entries, extract_tool_sequence, list_sessions, parse_session,
-    resolve_project_path,
+    adapt_to_history_entries, extract_assistant_usage, extract_message_tree, extract_tool_results,
+    extract_tool_sequence, hash_range, list_sessions, list_subagent_sessions, parse_session,
+    parse_session_from, project_of_session_file, resolve_project_path,
 };
@@ -1,13 +1,25 @@
 use super::filters::{is_system_meta_message, strip_system_reminders};
-use crate::types::{Content, HistoryEntry, SessionEntry, ToolUse};
+use crate::types::{
+    AgentRoot, AssistantUsage, Content, GitCommit, HistoryEntry, MessageNode, SessionEntry,
+    ToolResult, ToolUse,
+};
 use anyhow::{Context, Result};
 use chrono::DateTime;
+use regex::Regex;
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
 const DEFAULT_PROJECTS_PATH: &str = ".claude/projects";
 
+/// The summary line `git commit` prints: `[main 1a2b3c4] subject`, also
+/// `[main (root-commit) 1a2b3c4] ...` and `[detached HEAD 1a2b3c4] ...`.
+static COMMIT_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\[([^\]\n]+?)(?: \(root-commit\))? ([0-9a-f]{7,40})\] (.*)$").unwrap()
+});
+
 /// List all projects in ~/.claude/projects
 pub fn list_projects(base_path: Option<&str>) -> Result<Vec<String>> {
     let projects_path = if let Some(p) = base_path {
@@ -55,6 +67,41 @@ pub fn list_sessions(project_path: &Path) -> Result<Vec<PathBuf>> {
     Ok(sessions)
 }
 
+/// List subagent transcripts that newer versions write next to a session,
+/// as `<session id>/subagents/*.jsonl`.
+pub fn list_subagent_sessions(project_path: &Path) -> Result<Vec<PathBuf>> {
+    if !project_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(project_path)? {
+        let subagents = entry?.path().join("subagents");
+        if !subagents.is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(&subagents)? {
+            let path = file?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                sessions.push(path);
+            }
+        }
+    }
+
+    sessions.sort();
+    Ok(sessions)
+}
+
+/// Project directory (the encoded name under ~/.claude/projects) a session
+/// file belongs to, looking past `<session id>/subagents/` for transcripts.
+pub fn project_of_session_file(session_path: &Path) -> Option<String> {
+    let mut dir = session_path.parent()?;
+    if dir.file_name().is_some_and(|n| n == "subagents") {
+        dir = dir.parent()?.parent()?;
+    }
+    dir.file_name()?.to_str().map(str::to_string)
+}
+
 /// Parse a single session file
 pub fn parse_session(session_path: &Path) -> Result<Vec<SessionEntry>> {
     let file = File::open(session_path)
@@ -86,6 +133,84 @@ pub fn parse_session(session_path: &Path) -> Result<Vec<SessionEntry>> {
     Ok(entries)
 }
 
+/// Entries parsed from a byte range of a session file, plus the checkpoint to
+/// resume from next time.
+pub struct SessionChunk {
+    pub entries: Vec<SessionEntry>,
+    /// Byte offset just past the last line consumed
+    pub end_offset: u64,
+    /// Start offset and hash of the last non-blank line consumed
+    pub last_entry: Option<(u64, String)>,
+}
+
+/// Parse a session file from byte `offset` to its end.
+///
+/// A trailing line without a newline is consumed only if it already parses —
+/// otherwise it is still being written, and `end_offset` stops before it so the
+/// next run picks it up whole.
+pub fn parse_session_from(session_path: &Path, offset: u64) -> Result<SessionChunk> {
+    let mut file = File::open(session_path)
+        .with_context(|| format!("Failed to open session: {}", session_path.display()))?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    let mut entries = Vec::new();
+    let mut last_entry = None;
+    let mut pos = 0usize;
+
+    while pos < bytes.len() {
+        let (line, next, complete) = match bytes[pos..].iter().position(|&b| b == b'\n') {
+            Some(n) => (&bytes[pos..pos + n], pos + n + 1, true),
+            None => (&bytes[pos..], bytes.len(), false),
+        };
+        let text = String::from_utf8_lossy(line);
+
+        if !text.trim().is_empty() {
+            match serde_json::from_str::<SessionEntry>(&text) {
+                Ok(entry) => entries.push(entry),
+                Err(_) if !complete => break,
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Skipping line at byte {} in {}: {}",
+                        offset + pos as u64,
+                        session_path.display(),
+                        e
+                    );
+                }
+            }
+            last_entry = Some((offset + pos as u64, entry_hash(line)));
+        }
+        pos = next;
+    }
+
+    Ok(SessionChunk {
+        entries,
+        end_offset: offset + pos as u64,
+        last_entry,
+    })
+}
+
+/// Hash of the line starting at `start` and ending before `end` (or at the first
+/// newline before it), comparable with a `last_entry` hash from
+/// `parse_session_from`.
+pub fn hash_range(session_path: &Path, start: u64, end: u64) -> Result<String> {
+    let mut file = File::open(session_path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = vec![0; end.saturating_sub(start) as usize];
+    file.read_exact(&mut bytes)?;
+    let line = bytes.split(|&b| b == b'\n').next().unwrap_or_default();
+    Ok(entry_hash(line))
+}
+
+/// FNV-1a (64-bit): stable across builds, unlike `DefaultHasher`.
+fn entry_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
 /// Extract tool usage sequence from session entries
 pub fn extract_tool_sequence(entries: &[SessionEntry]) -> Vec<ToolUse> {
     let mut tools = Vec::new();
@@ -101,9 +226,13 @@ pub fn extract_tool_sequence(entries: &[SessionEntry]) -> Vec<ToolUse> {
                     .map(|dt| dt.timestamp_millis());
 
                 tools.push(ToolUse {
+                    id: None,
+                    message_uuid: entry.uuid.clone(),
                     name: name.clone(),
                     timestamp,
                     input: entry.input.clone(),
+                    cwd: entry.cwd.clone(),
+                    git_branch: entry.git_branch.clone(),
                 });
             }
             continue;
@@ -123,9 +252,13 @@ pub fn extract_tool_sequence(entries: &[SessionEntry]) -> Vec<ToolUse> {
                                     .map(|dt| dt.timestamp_millis());
 
                                 tools.push(ToolUse {
+                                    id: item.id.clone(),
+                                    message_uuid: entry.uuid.clone(),
                                     name: name.clone(),
                                     timestamp,
                                     input: item.input.clone(),
+                                    cwd: entry.cwd.clone(),
+                                    git_branch: entry.git_branch.clone(),
                                 });
                             }
                         }
@@ -138,6 +271,194 @@ pub fn extract_tool_sequence(entries: &[SessionEntry]) -> Vec<ToolUse> {
     tools
 }
 
+/// Extract tool results from session entries.
+/// Results arrive as `tool_result` blocks in the user message that follows the
+/// assistant's `tool_use`; a result without a `tool_use_id` cannot be linked and
+/// is dropped.
+pub fn extract_tool_results(entries: &[SessionEntry]) -> Vec<ToolResult> {
+    let mut results = Vec::new();
+
+    for entry in entries.iter().filter(|e| e.entry_type == "user") {
+        let Some(Content::Array(items)) = entry.message.as_ref().map(|m| &m.content) else {
+            continue;
+        };
+        let timestamp = entry
+            .timestamp
+            .as_ref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.timestamp_millis());
+
+        for item in items.iter().filter(|i| i.item_type == "tool_result") {
+            let Some(tool_use_id) = &item.tool_use_id else {
+                continue;
+            };
+            let output = tool_result_text(item.content.as_ref());
+            let is_error = item.is_error.unwrap_or(false);
+            let commit = if is_error {
+                None
+            } else {
+                parse_commit_line(&output)
+            };
+            results.push(ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                timestamp,
+                is_error,
+                output_size: output.len(),
+                error_text: is_error.then_some(output),
+                commit,
+            });
+        }
+    }
+
+    results
+}
+
+/// Find the commit a `git commit` run reports in its output. Any tool output
+/// is checked; whether it came from such a command is decided when it is
+/// linked to its tool use.
+pub fn parse_commit_line(output: &str) -> Option<GitCommit> {
+    let caps = COMMIT_LINE.captures(output)?;
+    Some(GitCommit {
+        branch: caps[1].to_string(),
+        sha: caps[2].to_string(),
+        subject: caps[3].trim_end().to_string(),
+    })
+}
+
+/// Extract the uuid / parentUuid tree and the subagent runs that start in it.
+///
+/// A sidechain entry starts a run when it has no parent, or its parent is a
+/// main-thread entry; the run is named by `agentId`, or by the root's uuid in
+/// older transcripts. With `complete`, `entries` is the whole file, so a
+/// sidechain entry whose parent is missing also starts a run; otherwise the
+/// parent may sit in an earlier chunk and the agent is inherited when stored.
+pub fn extract_message_tree(
+    entries: &[SessionEntry],
+    complete: bool,
+) -> (Vec<MessageNode>, Vec<AgentRoot>) {
+    let sidechain: HashMap<&str, bool> = entries
+        .iter()
+        .filter_map(|e| Some((e.uuid.as_deref()?, e.is_sidechain)))
+        .collect();
+
+    let mut nodes = Vec::new();
+    let mut roots = Vec::new();
+    for entry in entries {
+        let Some(uuid) = &entry.uuid else {
+            continue;
+        };
+        let is_root = entry.is_sidechain
+            && match entry.parent_uuid.as_deref() {
+                None => true,
+                Some(parent) => match sidechain.get(parent) {
+                    Some(parent_is_sidechain) => !parent_is_sidechain,
+                    None => complete,
+                },
+            };
+        let agent_id = entry
+            .agent_id
+            .clone()
+            .or_else(|| is_root.then(|| uuid.clone()));
+
+        if let (true, Some(agent_id)) = (is_root, &agent_id) {
+            roots.push(AgentRoot {
+                agent_id: agent_id.clone(),
+                prompt: entry_text(entry),
+            });
+        }
+        nodes.push(MessageNode {
+            uuid: uuid.clone(),
+            parent_uuid: entry.parent_uuid.clone(),
+            entry_type: entry.entry_type.clone(),
+            is_sidechain: entry.is_sidechain,
+            agent_id: if entry.is_sidechain { agent_id } else { None },
+            timestamp: entry
+                .timestamp
+                .as_ref()
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|dt| dt.timestamp_millis()),
+        });
+    }
+
+    (nodes, roots)
+}
+
+/// Text of a user entry: the string content, or its text blocks joined.
+fn entry_text(entry: &SessionEntry) -> Option<String> {
+    if entry.entry_type != "user" {
+        return None;
+    }
+    match &entry.message.as_ref()?.content {
+        Content::Text(t) => Some(t.clone()),
+        Content::Array(items) => {
+            let texts: Vec<&str> = items
+                .iter()
+                .filter(|i| i.item_type == "text")
+                .filter_map(|i| i.text.as_deref())
+                .collect();
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+    }
+}
+
+/// Extract model and token usage from assistant messages.
+///
+/// A message split into one line per content block repeats its id and usage on
+/// each line; those collapse to one entry, keeping the last line's counts.
+pub fn extract_assistant_usage(entries: &[SessionEntry]) -> Vec<AssistantUsage> {
+    let mut messages: Vec<AssistantUsage> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries.iter().filter(|e| e.entry_type == "assistant") {
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let timestamp = entry
+            .timestamp
+            .as_ref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.timestamp_millis());
+        let item = AssistantUsage {
+            message_id: message.id.clone(),
+            model: message.model.clone(),
+            timestamp,
+            usage: usage.clone(),
+        };
+
+        match message.id.as_ref().and_then(|id| by_id.get(id)) {
+            Some(&i) => {
+                // Keep the first line's timestamp: the message started there.
+                let timestamp = messages[i].timestamp.or(item.timestamp);
+                messages[i] = AssistantUsage { timestamp, ..item };
+            }
+            None => {
+                if let Some(id) = &message.id {
+                    by_id.insert(id.clone(), messages.len());
+                }
+                messages.push(item);
+            }
+        }
+    }
+
+    messages
+}
+
+/// Flatten `tool_result` content (a string or an array of blocks) to its text.
+fn tool_result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
 /// Resolve project path to ~/.claude/projects/encoded-name
 pub fn resolve_project_path(raw_path: &str) -> Option<PathBuf> {
     let normalized = Path::new(raw_path)
@@ -11,15 +11,56 @@ pub struct SessionEntry {
     #[serde(default)]
     pub input: Option<serde_json::Value>,
     /// Skipped during deserialization — never accessed, avoids parsing large assistant outputs.
+    /// Token counts come from `message.usage` instead.
     #[serde(skip_deserializing)]
     pub output: Option<String>,
     #[serde(default)]
     pub timestamp: Option<String>,
+    #[serde(default)]
+    pub uuid: Option<String>,
+    #[serde(default, rename = "parentUuid")]
+    pub parent_uuid: Option<String>,
+    /// Set on subagent (Task) traffic, whether inline or in its own file.
+    #[serde(default, rename = "isSidechain")]
+    pub is_sidechain: bool,
+    /// Subagent id; newer transcripts set it on every subagent line.
+    #[serde(default, rename = "agentId")]
+    pub agent_id: Option<String>,
+    /// Session the line belongs to — the parent's id in a subagent file.
+    #[serde(default, rename = "sessionId")]
+    pub session_id: Option<String>,
+    /// Working directory when the line was written
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Checked-out branch when the line was written, if in a git repo
+    #[serde(default, rename = "gitBranch")]
+    pub git_branch: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub content: Content,
+    /// Assistant only: API message id, repeated on every line of a message
+    /// that was split into one line per content block.
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// Token counts the API reported for one assistant message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,13 +80,81 @@ pub struct ContentItem {
     pub name: Option<String>,
     #[serde(default)]
     pub input: Option<serde_json::Value>,
+    /// `tool_use` block id, referenced by the matching `tool_result`.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// `tool_result` only: the `tool_use` block this result answers.
+    #[serde(default)]
+    pub tool_use_id: Option<String>,
+    /// `tool_result` only: a string or an array of content blocks.
+    #[serde(default)]
+    pub content: Option<serde_json::Value>,
+    #[serde(default)]
+    pub is_error: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ToolUse {
+    /// `tool_use` block id; `None` for the legacy top-level format.
+    pub id: Option<String>,
+    /// uuid of the entry carrying the block
+    pub message_uuid: Option<String>,
     pub name: String,
     pub timestamp: Option<i64>,
     pub input: Option<serde_json::Value>,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+/// A `tool_result` block from a user message, keyed by the `tool_use` it answers.
+#[derive(Debug, Clone)]
+pub struct ToolResult {
+    pub tool_use_id: String,
+    pub timestamp: Option<i64>,
+    pub is_error: bool,
+    /// Output text size in bytes
+    pub output_size: usize,
+    /// Output text, kept only for errors
+    pub error_text: Option<String>,
+    /// Commit reported in the output (`[main 1a2b3c4] subject`)
+    pub commit: Option<GitCommit>,
+}
+
+/// A commit as `git commit` reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub sha: String,
+    pub branch: String,
+    pub subject: String,
+}
+
+/// One transcript entry as a node of the uuid / parentUuid tree.
+#[derive(Debug, Clone)]
+pub struct MessageNode {
+    pub uuid: String,
+    pub parent_uuid: Option<String>,
+    pub entry_type: String,
+    pub is_sidechain: bool,
+    /// Known when the line names its agent or starts a sidechain; otherwise
+    /// inherited from the parent when stored.
+    pub agent_id: Option<String>,
+    pub timestamp: Option<i64>,
+}
+
+/// First entry of a subagent run, with the prompt it was spawned with.
+#[derive(Debug, Clone)]
+pub struct AgentRoot {
+    pub agent_id: String,
+    pub prompt: Option<String>,
+}
+
+/// Model and token usage of one assistant message.
+#[derive(Debug, Clone)]
+pub struct AssistantUsage {
+    pub message_id: Option<String>,
+    pub model: Option<String>,
+    pub timestamp: Option<i64>,
+    pub usage: Usage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,130 @@
+/// Git context: branch / cwd per prompt and tool use, commits parsed from Bash `git commit` runs.
+mod helpers;
+
+use helpers::{cli_with_home, db_path, query, setup_project};
+
+const FIXTURES: &[&str] = &["git_commits.jsonl", "git_main.jsonl", "minimal.jsonl"];
+
+fn indexed() -> (tempfile::TempDir, std::path::PathBuf) {
+    let (tmp, project) = setup_project(FIXTURES);
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    (tmp, project)
+}
+
+#[test]
+fn session_commits_come_from_successful_git_commit_runs_only() {
+    let (tmp, project) = indexed();
+    let rows = query(&tmp, &project, "session-commits", &[]);
+
+    // `cat` output that looks like a commit line and the failed commit are ignored
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["session_id"], "git_commits");
+    assert_eq!(rows[0]["commits"], 2);
+    assert_eq!(rows[0]["shas"], "1a2b3c4,7c8d9e0");
+    assert_eq!(rows[0]["branches"], "feature/login");
+}
+
+#[test]
+fn branches_count_sessions_and_commits() {
+    let (tmp, project) = indexed();
+    let rows = query(&tmp, &project, "branches", &[]);
+
+    // minimal.jsonl has no branch and is left out
+    assert_eq!(rows.len(), 2);
+    let row = |branch: &str| {
+        rows.iter()
+            .find(|r| r["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing branch {branch}"))
+            .clone()
+    };
+    let feature = row("feature/login");
+    assert_eq!(feature["sessions"], 1);
+    assert_eq!(feature["prompts"], 2);
+    assert_eq!(feature["tool_uses"], 5);
+    assert_eq!(feature["commits"], 2);
+    let main = row("main");
+    assert_eq!(main["sessions"], 1);
+    assert_eq!(main["commits"], 0);
+}
+
+#[test]
+fn time_to_commit_measures_from_first_prompt() {
+    let (tmp, project) = indexed();
+    let rows = query(&tmp, &project, "time-to-commit", &[]);
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["session_id"], "git_commits");
+    assert_eq!(rows[0]["minutes_to_first_commit"], 5.0);
+    assert_eq!(rows[0]["commits"], 2);
+}
+
+#[test]
+fn cwd_and_branch_are_stored_per_prompt_and_tool_use() {
+    let (tmp, project) = indexed();
+    let conn = rusqlite::Connection::open(db_path(&tmp, &project)).unwrap();
+    let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap();
+
+    assert_eq!(
+        count(
+            "SELECT COUNT(*) FROM prompts WHERE cwd = '/home/user/project' AND git_branch = 'main'"
+        ),
+        1
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM tool_uses WHERE git_branch = 'feature/login'"),
+        5
+    );
+    assert!(count("SELECT COUNT(*) FROM prompts WHERE git_branch IS NULL") > 0);
+}
+
+#[test]
+fn commits_are_replaced_when_a_session_is_reindexed() {
+    let (tmp, project) = indexed();
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap(), "--full"])
+        .assert()
+        .success();
+    let rows = query(&tmp, &project, "session-commits", &[]);
+    assert_eq!(rows[0]["commits"], 2);
+}
+
+#[test]
+fn branches_are_kept_apart_per_project_in_the_global_db() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sessions");
+    for (project, session) in [("-home-user-alpha", "alpha"), ("-home-user-beta", "beta")] {
+        let dir = tmp.path().join(".claude/projects").join(project);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(
+            fixtures.join("git_main.jsonl"),
+            dir.join(format!("{session}.jsonl")),
+        )
+        .unwrap();
+    }
+    cli_with_home(&tmp)
+        .args(["index", "--all-projects"])
+        .assert()
+        .success();
+
+    let output = cli_with_home(&tmp)
+        .args(["query", "--all-projects", "--perspective", "branches"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let rows: Vec<serde_json::Value> = serde_json::from_slice(&output).unwrap();
+    let mut projects: Vec<&str> = rows
+        .iter()
+        .filter(|r| r["branch"] == "main")
+        .map(|r| {
+            assert_eq!(r["sessions"], 1);
+            r["project"].as_str().unwrap()
+        })
+        .collect();
+    projects.sort();
+    assert_eq!(projects, ["-home-user-alpha", "-home-user-beta"]);
+}
@@ -0,0 +1,333 @@
+/// Global index: `index --all-projects`, the project column, and cross-project perspectives.
+mod helpers;
+
+use helpers::{cli_with_home, db_path, encode_project, query, setup_project};
+
+/// A session editing `files` with one user prompt, as a JSONL transcript.
+fn edit_session(prompt: &str, day: u32, files: &[&str]) -> String {
+    let mut lines = vec![format!(
+        r#"{{"type":"user","message":{{"content":"{prompt}"}},"timestamp":"2026-03-{day:02}T10:00:00+00:00"}}"#
+    )];
+    for (i, file) in files.iter().enumerate() {
+        lines.push(format!(
+            r#"{{"type":"assistant","message":{{"content":[{{"type":"tool_use","name":"Read","input":{{"file_path":"{file}"}}}},{{"type":"tool_use","name":"Edit","input":{{"file_path":"{file}"}}}}]}},"timestamp":"2026-03-{day:02}T10:0{i}:30+00:00"}}"#
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Write a session file into `~/.claude/projects/<project>/`.
+fn write_session(tmp: &tempfile::TempDir, project: &str, name: &str, content: &str) {
+    let dir = tmp.path().join(".claude/projects").join(project);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(name), content).unwrap();
+}
+
+/// Two repos, `/home/user/alpha` and `/home/user/beta`, both editing a
+/// `src/lib.rs` and a shared dotfile.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempfile::TempDir::new().unwrap();
+    write_session(
+        &tmp,
+        "-home-user-alpha",
+        "a1.jsonl",
+        &edit_session(
+            "alpha work",
+            2,
+            &["/home/user/alpha/src/lib.rs", "/home/user/.gitconfig"],
+        ),
+    );
+    write_session(
+        &tmp,
+        "-home-user-alpha",
+        "a2.jsonl",
+        &edit_session("alpha again", 3, &["/home/user/alpha/src/lib.rs"]),
+    );
+    write_session(
+        &tmp,
+        "-home-user-beta",
+        "b1.jsonl",
+        &edit_session(
+            "beta work",
+            4,
+            &[
+                "/home/user/beta/src/lib.rs",
+                "/home/user/beta/README.md",
+                "/home/user/.gitconfig",
+            ],
+        ),
+    );
+    tmp
+}
+
+fn index_all(tmp: &tempfile::TempDir) -> String {
+    let output = cli_with_home(tmp)
+        .args(["index", "--all-projects"])
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+fn query_all(tmp: &tempfile::TempDir, perspective: &str, extra: &[&str]) -> Vec<serde_json::Value> {
+    let output = cli_with_home(tmp)
+        .args(["query", "--all-projects", "--perspective", perspective])
+        .args(extra)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    json.as_array().unwrap().clone()
+}
+
+#[test]
+fn all_projects_index_into_the_global_db() {
+    let tmp = setup();
+    let stderr = index_all(&tmp);
+    assert!(stderr.contains("Projects: 2"), "{stderr}");
+    assert!(stderr.contains("Indexed: 3 new"), "{stderr}");
+    assert!(tmp
+        .path()
+        .join(".claude/suggest-workflow-index/global/index.db")
+        .exists());
+
+    let projects = query_all(&tmp, "projects", &[]);
+    let names: Vec<&str> = projects
+        .iter()
+        .map(|p| p["project"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["-home-user-beta", "-home-user-alpha"]);
+    assert_eq!(projects[1]["sessions"], 2);
+
+    // Re-running finds nothing new; a removed project's sessions go away
+    assert!(index_all(&tmp).contains("0 new, 0 updated (0 appended), 3 unchanged"));
+    std::fs::remove_dir_all(tmp.path().join(".claude/projects/-home-user-beta")).unwrap();
+    assert!(index_all(&tmp).contains("1 deleted"));
+    assert_eq!(query_all(&tmp, "projects", &[]).len(), 1);
+}
+
+#[test]
+fn project_index_into_the_global_db_keeps_other_projects() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    write_session(
+        &tmp,
+        "-home-user-beta",
+        "b1.jsonl",
+        &edit_session("beta work", 4, &["/home/user/beta/README.md"]),
+    );
+    index_all(&tmp);
+    assert_eq!(query_all(&tmp, "projects", &[]).len(), 2);
+
+    // Refreshing one project in the global DB only prunes that project
+    let db = tmp
+        .path()
+        .join(".claude/suggest-workflow-index/global/index.db");
+    let output = cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .args(["--db", db.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert!(stderr.contains("1 unchanged, 0 deleted"), "{stderr}");
+    assert_eq!(query_all(&tmp, "projects", &[]).len(), 2);
+}
+
+#[test]
+fn project_tools_ranks_tools_per_project() {
+    let tmp = setup();
+    index_all(&tmp);
+
+    let rows = query_all(&tmp, "project-tools", &["--param", "top=1"]);
+    assert_eq!(rows.len(), 2, "{rows:?}");
+    assert_eq!(rows[0]["project"], "-home-user-alpha");
+    assert_eq!(rows[0]["frequency"], 3);
+    assert_eq!(rows[0]["sessions"], 2);
+    assert_eq!(rows[1]["project"], "-home-user-beta");
+    assert_eq!(rows[1]["share"], 0.5);
+}
+
+#[test]
+fn shared_hotfiles_compare_project_relative_paths() {
+    let tmp = setup();
+    index_all(&tmp);
+
+    let rows = query_all(&tmp, "shared-hotfiles", &[]);
+    let files: Vec<&str> = rows.iter().map(|r| r["file"].as_str().unwrap()).collect();
+    // README.md is edited in beta only; the dotfile is outside both repos
+    assert_eq!(files, ["src/lib.rs", "/home/user/.gitconfig"]);
+    assert_eq!(rows[0]["projects"], 2);
+    assert_eq!(rows[0]["edit_count"], 3);
+    assert_eq!(rows[0]["session_count"], 3);
+}
+
+#[test]
+fn derived_perspectives_merge_projects() {
+    let tmp = setup();
+    index_all(&tmp);
+
+    let hot = query_all(&tmp, "hotfiles", &[]);
+    assert_eq!(hot[0]["file_path"], "/home/user/alpha/src/lib.rs");
+    let gitconfig = hot
+        .iter()
+        .find(|r| r["file_path"] == "/home/user/.gitconfig")
+        .unwrap();
+    assert_eq!(gitconfig["session_count"], 2);
+
+    // Read → Edit in every session, summed over both projects
+    let rows = query_all(&tmp, "transitions", &["--param", "tool=Read"]);
+    assert_eq!(rows[0]["to_tool"], "Edit");
+    assert_eq!(rows[0]["count"], 6);
+    assert_eq!(rows[0]["probability"], 1.0);
+
+    // The shared dotfile does not link sessions across projects
+    let links = query_all(&tmp, "session-links", &["--param", "min_overlap=0"]);
+    assert_eq!(links.len(), 1, "{links:?}");
+    assert_eq!(links[0]["session_a"], "a1");
+    assert_eq!(links[0]["session_b"], "a2");
+}
+
+#[test]
+fn project_filter_narrows_global_queries() {
+    let (tmp, project) = setup_project(&["minimal.jsonl", "multi_tool.jsonl"]);
+    write_session(
+        &tmp,
+        "-home-user-beta",
+        "b1.jsonl",
+        &edit_session("beta work", 4, &["/home/user/beta/src/lib.rs"]),
+    );
+    index_all(&tmp);
+    let project = project.to_str().unwrap();
+
+    let filtered = query_all(&tmp, "tool-frequency", &["--project", project]);
+    let reads = |rows: &[serde_json::Value]| {
+        rows.iter().find(|r| r["tool"] == "Read").unwrap()["frequency"].clone()
+    };
+    assert_eq!(reads(&filtered), 4);
+    assert_eq!(reads(&query_all(&tmp, "tool-frequency", &[])), 5);
+    assert!(query_all(&tmp, "hotfiles", &["--project", project])
+        .iter()
+        .all(|r| r["file_path"] != "/home/user/beta/src/lib.rs"));
+    assert_eq!(
+        query_all(&tmp, "sessions", &["--project", project]).len(),
+        2
+    );
+
+    // Same counts as the project's own DB
+    cli_with_home(&tmp)
+        .args(["index", "--project", project])
+        .assert()
+        .success();
+    let output = cli_with_home(&tmp)
+        .args([
+            "query",
+            "--project",
+            project,
+            "--perspective",
+            "tool-frequency",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let own: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let by_tool = |rows: &[serde_json::Value]| {
+        let mut rows = rows.to_vec();
+        rows.sort_by_key(|r| r["tool"].as_str().unwrap().to_string());
+        rows
+    };
+    assert_eq!(by_tool(own.as_array().unwrap()), by_tool(&filtered));
+}
+
+#[test]
+fn project_filter_values_are_never_expanded_as_markers() {
+    let tmp = setup();
+    index_all(&tmp);
+    // The quoted project lands inside the `{SF:}` expansion; rescanning it
+    // would expand the marker forever.
+    let project = tmp.path().join("{SF:id}");
+    std::fs::create_dir(&project).unwrap();
+    let output = cli_with_home(&tmp)
+        .args(["query", "--all-projects", "--perspective", "tool-frequency"])
+        .args(["--project", project.to_str().unwrap()])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json, serde_json::json!([]));
+}
+
+#[test]
+fn all_projects_conflicts_with_project_for_index() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    cli_with_home(&tmp)
+        .args([
+            "index",
+            "--all-projects",
+            "--project",
+            project.to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+    cli_with_home(&tmp)
+        .args(["query", "--all-projects", "--perspective", "projects"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("index --all-projects"));
+}
+
+#[test]
+fn v11_database_backfills_projects_and_rekeys_derived_tables() {
+    let (tmp, project) = setup_project(&["multi_tool.jsonl", "file_edits.jsonl"]);
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    let query = |perspective: &str| query(&tmp, &project, perspective, &[]);
+    let hotfiles = query("hotfiles");
+    assert!(!hotfiles.is_empty());
+    let sequences = query("sequences");
+
+    // Roll back to the v11 layout: no project columns, derived tables keyed without them
+    let conn = rusqlite::Connection::open(db_path(&tmp, &project)).unwrap();
+    conn.execute_batch(
+        "DROP TABLE commits;
+         ALTER TABLE prompts DROP COLUMN cwd;
+         ALTER TABLE prompts DROP COLUMN git_branch;
+         ALTER TABLE tool_uses DROP COLUMN cwd;
+         ALTER TABLE tool_uses DROP COLUMN git_branch;
+         DROP INDEX idx_sessions_project;
+         ALTER TABLE sessions DROP COLUMN project;
+         ALTER TABLE session_links DROP COLUMN project;
+         CREATE TABLE old_hotspots AS SELECT file_path, edit_count, session_count FROM file_hotspots;
+         DROP TABLE file_hotspots;
+         ALTER TABLE old_hotspots RENAME TO file_hotspots;
+         UPDATE meta SET value = '11' WHERE key = 'schema_version';",
+    )
+    .unwrap();
+    drop(conn);
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Schema migrated: v11 → v13"))
+        // The v13 step re-parses every session for branches and commits
+        .stderr(predicates::str::contains("2 updated"));
+    assert_eq!(
+        query("projects")[0]["project"],
+        encode_project(&project).as_str()
+    );
+    assert_eq!(query("hotfiles"), hotfiles);
+    assert_eq!(query("sequences"), sequences);
+}
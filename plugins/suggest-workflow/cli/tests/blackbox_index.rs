@@ -1,6 +1,6 @@
 mod helpers;
 
-use helpers::{cli_with_home, setup_project};
+use helpers::{cli_with_home, sessions_dir, setup_project};
 use predicates::prelude::*;
 
 // --- A1: First indexing creates DB and reports new sessions ---
@@ -180,3 +180,272 @@ fn a8_malformed_jsonl_handled() {
         .assert()
         .success();
 }
+
+/// Everything indexing derives from a session, in a comparable form.
+fn snapshot(tmp: &tempfile::TempDir, project: &std::path::Path) -> serde_json::Value {
+    let sql = tmp.path().join("snapshot.sql");
+    std::fs::write(
+        &sql,
+        "SELECT s.id, s.first_ts, s.last_ts, s.prompt_count, s.tool_use_count, \
+                s.first_prompt_snippet, s.parsed_offset, s.last_entry_hash, \
+                (SELECT group_concat(text || '@' || timestamp, '|') FROM prompts WHERE session_id = s.id) AS prompts, \
+                (SELECT group_concat(seq_order || ':' || classified_name, '|') FROM tool_uses WHERE session_id = s.id) AS tools, \
+                (SELECT group_concat(u.seq_order || ':' || r.is_error || ':' || r.duration_ms, '|') \
+                   FROM tool_results r JOIN tool_uses u ON u.id = r.tool_use_id WHERE r.session_id = s.id) AS results, \
+                (SELECT group_concat(u.seq_order || ':' || f.file_path, '|') \
+                   FROM file_edits f JOIN tool_uses u ON u.id = f.tool_use_id WHERE f.session_id = s.id) AS edits \
+         FROM sessions s ORDER BY s.id",
+    )
+    .unwrap();
+    let output = cli_with_home(tmp)
+        .args(["query", "--project", project.to_str().unwrap()])
+        .args(["--sql-file", sql.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).unwrap()
+}
+
+const APPENDED_TAIL: &str = concat!(
+    "{\"type\":\"user\",\"message\":{\"content\":\"run the tests again\"},\"timestamp\":\"2026-02-12T11:00:00+00:00\"}\n",
+    "{\"type\":\"assistant\",\"message\":{\"content\":[{\"type\":\"tool_use\",\"id\":\"toolu_07\",\"name\":\"Edit\",\"input\":{\"file_path\":\"/home/user/project/src/auth.rs\"}},{\"type\":\"tool_use\",\"id\":\"toolu_08\",\"name\":\"Bash\",\"input\":{\"command\":\"cargo test\"}}]},\"timestamp\":\"2026-02-12T11:00:01+00:00\"}\n",
+    "{\"type\":\"user\",\"message\":{\"content\":[{\"type\":\"tool_result\",\"tool_use_id\":\"toolu_08\",\"is_error\":true,\"content\":\"1 failed\"}]},\"timestamp\":\"2026-02-12T11:00:09+00:00\"}\n",
+);
+
+// --- A9: Appended lines are indexed from the checkpoint, same as a full parse ---
+#[test]
+fn a9_appended_tail_matches_full_reparse() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl", "minimal.jsonl"]);
+    index_quiet(&tmp, &project);
+
+    let target = sessions_dir(&tmp, &project).join("tool_results.jsonl");
+    let mut content = std::fs::read_to_string(&target).unwrap();
+    content.push_str(APPENDED_TAIL);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, content).unwrap();
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 updated (1 appended)"))
+        .stderr(predicate::str::contains("1 unchanged"));
+    let appended = snapshot(&tmp, &project);
+
+    cli_with_home(&tmp)
+        .args(["index", "--full", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(appended, snapshot(&tmp, &project));
+    assert_eq!(appended[1]["tool_use_count"], 8);
+}
+
+// --- A10: A rewritten prefix forces a full re-parse ---
+#[test]
+fn a10_rewritten_session_is_reparsed_in_full() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl"]);
+    index_quiet(&tmp, &project);
+
+    // Compaction-style rewrite: the file keeps growing but its history changed.
+    let target = sessions_dir(&tmp, &project).join("tool_results.jsonl");
+    let content = std::fs::read_to_string(&target).unwrap();
+    let rewritten = content.replace("cargo test login", "cargo test signin") + APPENDED_TAIL;
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, rewritten).unwrap();
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 updated (0 appended)"));
+    let reparsed = snapshot(&tmp, &project);
+    assert_eq!(reparsed[0]["tool_use_count"], 8);
+
+    cli_with_home(&tmp)
+        .args(["index", "--full", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(reparsed, snapshot(&tmp, &project));
+}
+
+// --- A11: A line still being written is left for the next run ---
+#[test]
+fn a11_partial_trailing_line_waits_for_completion() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    index_quiet(&tmp, &project);
+
+    let target = sessions_dir(&tmp, &project).join("minimal.jsonl");
+    let original = std::fs::read_to_string(&target).unwrap();
+    let line = "{\"type\":\"user\",\"message\":{\"content\":\"late prompt\"},\"timestamp\":\"2026-02-14T12:00:00+00:00\"}\n";
+    let (head, rest) = line.split_at(20);
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, format!("{original}{head}")).unwrap();
+    index_quiet(&tmp, &project);
+    let partial = snapshot(&tmp, &project);
+    assert_eq!(partial[0]["parsed_offset"], original.len());
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, format!("{original}{head}{rest}")).unwrap();
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 updated (1 appended)"));
+    let complete = snapshot(&tmp, &project);
+    assert!(complete[0]["prompts"]
+        .as_str()
+        .unwrap()
+        .contains("late prompt"));
+    assert_eq!(
+        complete[0]["prompt_count"].as_i64().unwrap(),
+        partial[0]["prompt_count"].as_i64().unwrap() + 1
+    );
+}
+
+fn index_quiet(tmp: &tempfile::TempDir, project: &std::path::Path) {
+    cli_with_home(tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+/// The derived tables, ordered so two indexes of the same sessions compare equal.
+fn derived_snapshot(tmp: &tempfile::TempDir, project: &std::path::Path) -> serde_json::Value {
+    let sql = tmp.path().join("derived.sql");
+    std::fs::write(
+        &sql,
+        "SELECT 'transition' AS t, from_tool || '>' || to_tool || ':' || count || ':' || probability AS v FROM tool_transitions \
+         UNION ALL SELECT 'week', week_start || ':' || tool_name || ':' || count || ':' || session_count FROM weekly_buckets \
+         UNION ALL SELECT 'hotspot', file_path || ':' || edit_count || ':' || session_count FROM file_hotspots \
+         UNION ALL SELECT 'link', session_a || '~' || session_b || ':' || shared_files || ':' || overlap_ratio || ':' || time_gap_minutes FROM session_links \
+         ORDER BY t, v",
+    )
+    .unwrap();
+    let output = cli_with_home(tmp)
+        .args(["query", "--project", project.to_str().unwrap()])
+        .args(["--sql-file", sql.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).unwrap()
+}
+
+// --- A12: Per-session upkeep of derived tables matches a full rebuild ---
+#[test]
+fn a12_derived_tables_track_changed_sessions() {
+    let (tmp, project) = setup_project(&[
+        "autodev_session.jsonl",
+        "multi_tool.jsonl",
+        "minimal.jsonl",
+        "tool_results.jsonl",
+    ]);
+    index_quiet(&tmp, &project);
+
+    let dir = sessions_dir(&tmp, &project);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    // appended: new transitions and a hotspot on top of the indexed ones
+    let mut content = std::fs::read_to_string(dir.join("tool_results.jsonl")).unwrap();
+    content.push_str(APPENDED_TAIL);
+    std::fs::write(dir.join("tool_results.jsonl"), content).unwrap();
+    // rewritten: its link moves from autodev_session to tool_results
+    let content = std::fs::read_to_string(dir.join("multi_tool.jsonl")).unwrap();
+    std::fs::write(
+        dir.join("multi_tool.jsonl"),
+        content.replace("project/auth.rs", "project/src/login.rs"),
+    )
+    .unwrap();
+    // deleted and added
+    std::fs::remove_file(dir.join("minimal.jsonl")).unwrap();
+    std::fs::copy(
+        dir.join("autodev_session.jsonl"),
+        dir.join("autodev_copy.jsonl"),
+    )
+    .unwrap();
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "1 new, 2 updated (1 appended), 1 unchanged, 1 deleted",
+        ));
+    let incremental = derived_snapshot(&tmp, &project);
+
+    cli_with_home(&tmp)
+        .args(["index", "--full", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(incremental, derived_snapshot(&tmp, &project));
+
+    let links: Vec<&str> = incremental
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["t"] == "link")
+        .map(|r| r["v"].as_str().unwrap())
+        .collect();
+    assert_eq!(links.len(), 2, "{links:?}");
+    assert!(links
+        .iter()
+        .any(|l| l.starts_with("multi_tool~tool_results:")));
+    assert!(!incremental
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|r| r["v"].as_str().unwrap().contains("project/main.rs")));
+}
+
+// --- A13: --jobs caps parse threads without changing what is indexed ---
+#[test]
+fn a13_parallel_parse_matches_single_thread() {
+    let fixtures = [
+        "autodev_session.jsonl",
+        "multi_tool.jsonl",
+        "minimal.jsonl",
+        "tool_results.jsonl",
+        "file_edits.jsonl",
+        "bash_classified.jsonl",
+    ];
+    let (tmp, project) = setup_project(&fixtures);
+
+    cli_with_home(&tmp)
+        .args([
+            "index",
+            "--jobs",
+            "1",
+            "--project",
+            project.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Parsed 6 sessions on 1 threads"))
+        .stderr(predicate::str::contains("sessions/s"));
+    let sequential = (snapshot(&tmp, &project), derived_snapshot(&tmp, &project));
+
+    cli_with_home(&tmp)
+        .args(["index", "--full", "--jobs", "4"])
+        .args(["--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Parsed 6 sessions on 4 threads"));
+    assert_eq!(
+        sequential,
+        (snapshot(&tmp, &project), derived_snapshot(&tmp, &project))
+    );
+
+    cli_with_home(&tmp)
+        .args([
+            "index",
+            "--jobs",
+            "0",
+            "--project",
+            project.to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+}
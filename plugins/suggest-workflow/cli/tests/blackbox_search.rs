@@ -0,0 +1,145 @@
+/// Prompt full-text search: the FTS5 index, its sync with re-indexing, and the search perspective.
+mod helpers;
+
+use helpers::{cli_with_home, db_path, sessions_dir, setup_project};
+
+const FIXTURES: &[&str] = &[
+    "korean_prompts.jsonl",
+    "autodev_session.jsonl",
+    "minimal.jsonl",
+    "multi_tool.jsonl",
+];
+
+fn index_project(tmp: &tempfile::TempDir, project: &std::path::Path) {
+    cli_with_home(tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+fn search(
+    tmp: &tempfile::TempDir,
+    project: &std::path::Path,
+    query: &str,
+) -> Vec<serde_json::Value> {
+    let output = cli_with_home(tmp)
+        .args(["query", "--project", project.to_str().unwrap()])
+        .args([
+            "--perspective",
+            "search",
+            "--param",
+            &format!("query={query}"),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    json.as_array().unwrap().clone()
+}
+
+fn snippets(rows: &[serde_json::Value]) -> Vec<String> {
+    rows.iter()
+        .map(|r| r["snippet"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn korean_terms_are_all_required_and_highlighted() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project);
+
+    let rows = search(&tmp, &project, "로그인 버튼");
+    assert_eq!(snippets(&rows), ["…로그인 화면의 [로그인 버튼] 핸들러…"]);
+    assert_eq!(rows[0]["session_id"], "korean_prompts");
+
+    // Words need not be adjacent, only all present
+    let rows = search(&tmp, &project, "타임아웃 고쳐줘");
+    assert_eq!(snippets(&rows), ["로그인 [타임아웃] 버그를 [고쳐줘]"]);
+}
+
+#[test]
+fn results_are_ranked_by_bm25() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project);
+
+    let rows = search(&tmp, &project, "로그인");
+    assert_eq!(rows.len(), 2, "{rows:?}");
+    // Three mentions outrank one
+    assert!(snippets(&rows)[0].contains("[로그인] 버튼"), "{rows:?}");
+    assert!(rows[0]["score"].as_f64().unwrap() < rows[1]["score"].as_f64().unwrap());
+}
+
+#[test]
+fn short_terms_fall_back_to_a_phrase() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project);
+
+    // "PR" alone is below the trigram minimum; "PR 설명" is searched as one phrase
+    assert_eq!(
+        snippets(&search(&tmp, &project, "PR 설명")),
+        ["[PR 설명]을 정리해줘"]
+    );
+    assert!(search(&tmp, &project, "PR 정리").is_empty());
+
+    cli_with_home(&tmp)
+        .args(["query", "--project", project.to_str().unwrap()])
+        .args(["--perspective", "search", "--param", "query=커밋"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("too short"));
+}
+
+#[test]
+fn reindex_leaves_no_stale_hits() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project);
+    assert_eq!(search(&tmp, &project, "커밋 메시지").len(), 1);
+
+    // Rewrite the session without its commit prompt, then delete another one
+    let target = sessions_dir(&tmp, &project).join("korean_prompts.jsonl");
+    let content = std::fs::read_to_string(&target).unwrap();
+    let kept: Vec<&str> = content.lines().filter(|l| !l.contains("커밋")).collect();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, format!("{}\n", kept.join("\n"))).unwrap();
+    std::fs::remove_file(sessions_dir(&tmp, &project).join("autodev_session.jsonl")).unwrap();
+    index_project(&tmp, &project);
+
+    assert!(search(&tmp, &project, "커밋 메시지").is_empty());
+    assert!(search(&tmp, &project, "timeout").is_empty());
+    assert_eq!(search(&tmp, &project, "로그인").len(), 2);
+}
+
+#[test]
+fn v10_database_backfills_the_index() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project);
+
+    let conn = rusqlite::Connection::open(db_path(&tmp, &project)).unwrap();
+    conn.execute_batch(
+        "DROP TABLE commits;
+         ALTER TABLE prompts DROP COLUMN cwd;
+         ALTER TABLE prompts DROP COLUMN git_branch;
+         ALTER TABLE tool_uses DROP COLUMN cwd;
+         ALTER TABLE tool_uses DROP COLUMN git_branch;
+         DROP INDEX idx_sessions_project;
+         ALTER TABLE sessions DROP COLUMN project;
+         ALTER TABLE session_links DROP COLUMN project;
+         DROP TRIGGER prompts_fts_delete;
+         DROP TABLE prompts_fts;
+         DELETE FROM meta WHERE key = 'fts_tokenizer';
+         UPDATE meta SET value = '10' WHERE key = 'schema_version';",
+    )
+    .unwrap();
+    drop(conn);
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Schema migrated: v10 → v13"))
+        // The v13 step re-parses every session for branches and commits
+        .stderr(predicates::str::contains("4 updated"));
+    assert_eq!(search(&tmp, &project, "로그인").len(), 2);
+}
@@ -0,0 +1,117 @@
+/// Subagents: sidechain attribution, subagent transcript files, and the agent perspectives.
+mod helpers;
+
+use helpers::{cli_with_home, query, sessions_dir, setup_project};
+
+/// `sidechain.jsonl` with its second Task's transcript in `sidechain/subagents/`,
+/// the layout newer versions write.
+fn setup() -> (tempfile::TempDir, std::path::PathBuf) {
+    let (tmp, project) = setup_project(&["sidechain.jsonl"]);
+    let subagents = sessions_dir(&tmp, &project).join("sidechain/subagents");
+    std::fs::create_dir_all(&subagents).unwrap();
+    std::fs::copy(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/subagents/agent-b7.jsonl"),
+        subagents.join("agent-b7.jsonl"),
+    )
+    .unwrap();
+    (tmp, project)
+}
+
+fn index_project(tmp: &tempfile::TempDir, project: &std::path::Path) {
+    cli_with_home(tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+fn transitions(tmp: &tempfile::TempDir, project: &std::path::Path, tool: &str) -> Vec<String> {
+    query(tmp, project, "transitions", &[&format!("tool={tool}")])
+        .iter()
+        .map(|r| r["to_tool"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn sidechain_tools_do_not_break_the_main_flow() {
+    let (tmp, project) = setup();
+    index_project(&tmp, &project);
+
+    // Main thread: Task → Edit → Task → Bash; the Explore run in between
+    // is its own flow: Grep → Read.
+    assert_eq!(transitions(&tmp, &project, "Grep"), ["Read"]);
+    assert!(transitions(&tmp, &project, "Read").is_empty());
+    let after_task = transitions(&tmp, &project, "Task");
+    assert!(after_task.contains(&"Edit".to_string()), "{after_task:?}");
+    assert!(!after_task.contains(&"Grep".to_string()), "{after_task:?}");
+}
+
+#[test]
+fn subagent_runs_are_typed_by_their_task_prompt() {
+    let (tmp, project) = setup();
+    index_project(&tmp, &project);
+
+    let rows = query(&tmp, &project, "subagents", &[]);
+    assert_eq!(rows.len(), 2, "{rows:?}");
+    let explore = rows.iter().find(|r| r["agent_type"] == "Explore").unwrap();
+    assert_eq!(explore["runs"], 1);
+    assert_eq!(explore["tool_uses"], 2);
+    // Transcript file: typed through the parent session's Task call
+    let general = rows
+        .iter()
+        .find(|r| r["agent_type"] == "general-purpose")
+        .unwrap();
+    assert_eq!(general["tool_uses"], 2);
+    assert_eq!(general["sessions"], 1);
+}
+
+#[test]
+fn agent_tools_profiles_each_agent() {
+    let (tmp, project) = setup();
+    index_project(&tmp, &project);
+
+    let rows = query(&tmp, &project, "agent-tools", &[]);
+    let tools_of = |agent: &str| -> Vec<String> {
+        rows.iter()
+            .filter(|r| r["agent"] == agent)
+            .map(|r| r["tool"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(rows[0]["agent"], "main");
+    let mut explore = tools_of("Explore");
+    explore.sort();
+    assert_eq!(explore, ["Grep", "Read"]);
+    assert!(!tools_of("main").contains(&"Grep".to_string()));
+    assert_eq!(tools_of("general-purpose").len(), 1);
+}
+
+#[test]
+fn sidechain_continued_in_appended_tail_keeps_its_agent() {
+    let (tmp, project) = setup_project(&[]);
+    let fixture = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sessions/sidechain.jsonl");
+    let content = std::fs::read_to_string(fixture).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let target = sessions_dir(&tmp, &project).join("sidechain.jsonl");
+
+    // Cut inside the Explore run: its root is indexed first, the rest appended.
+    std::fs::write(&target, format!("{}\n", lines[..4].join("\n"))).unwrap();
+    index_project(&tmp, &project);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, &content).unwrap();
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("1 appended"));
+    let appended = query(&tmp, &project, "agent-tools", &[]);
+
+    cli_with_home(&tmp)
+        .args(["index", "--full", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(appended, query(&tmp, &project, "agent-tools", &[]));
+    assert!(appended
+        .iter()
+        .any(|r| r["agent"] == "Explore" && r["tool"] == "Read"));
+}
@@ -0,0 +1,141 @@
+/// Token usage: per-message indexing, price tables, and the token/cost perspectives.
+mod helpers;
+
+use helpers::{cli_with_home, query, sessions_dir, setup_project};
+
+const FIXTURES: &[&str] = &["token_usage.jsonl", "token_usage_plain.jsonl"];
+
+/// `claude-sonnet-4` is deliberately wrong: the longer `claude-sonnet-4-5` key
+/// must win for `claude-sonnet-4-5-*` models.
+const PRICES: &str = r#"{
+  "models": {
+    "claude-opus-4": {"input": 15, "output": 75, "cacheRead": 1.5, "cacheCreation": 18.75},
+    "claude-sonnet-4": {"input": 100, "output": 100, "cacheRead": 100, "cacheCreation": 100},
+    "claude-sonnet-4-5": {"input": 3, "output": 15, "cacheRead": 0.3, "cacheCreation": 3.75}
+  }
+}"#;
+
+fn index_project(tmp: &tempfile::TempDir, project: &std::path::Path, extra: &[&str]) {
+    cli_with_home(tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .args(extra)
+        .assert()
+        .success();
+}
+
+fn write_prices(tmp: &tempfile::TempDir) -> std::path::PathBuf {
+    let path = tmp.path().join("prices.json");
+    std::fs::write(&path, PRICES).unwrap();
+    path
+}
+
+fn find<'a>(rows: &'a [serde_json::Value], key: &str, value: &str) -> &'a serde_json::Value {
+    rows.iter()
+        .find(|r| r[key] == value)
+        .unwrap_or_else(|| panic!("no row with {key} = {value}: {rows:?}"))
+}
+
+#[test]
+fn split_message_lines_count_once() {
+    let (tmp, project) = setup_project(FIXTURES);
+    index_project(&tmp, &project, &[]);
+
+    let rows = query(&tmp, &project, "session-tokens", &[]);
+    let session = find(&rows, "id", "token_usage");
+    assert_eq!(session["messages"], 2);
+    assert_eq!(session["input_tokens"], 150);
+    assert_eq!(session["cache_read_tokens"], 3000);
+    assert_eq!(session["total_tokens"], 3680);
+    // No price table: tokens only
+    assert!(session["cost_usd"].is_null());
+    assert_eq!(session["unpriced_messages"], 2);
+}
+
+#[test]
+fn prices_match_longest_model_prefix() {
+    let (tmp, project) = setup_project(FIXTURES);
+    let prices = write_prices(&tmp);
+    index_project(&tmp, &project, &["--prices", prices.to_str().unwrap()]);
+
+    let rows = query(&tmp, &project, "model-tokens", &[]);
+    assert_eq!(
+        find(&rows, "model", "claude-opus-4-1-20250805")["cost_usd"],
+        0.0139
+    );
+    assert_eq!(
+        find(&rows, "model", "claude-sonnet-4-5-20250929")["cost_usd"],
+        0.0009
+    );
+    let haiku = find(&rows, "model", "claude-haiku-4-5");
+    assert!(haiku["cost_usd"].is_null());
+    assert_eq!(haiku["unpriced_messages"], 1);
+}
+
+#[test]
+fn default_price_file_is_used() {
+    let (tmp, project) = setup_project(FIXTURES);
+    let dir = tmp.path().join(".claude").join("suggest-workflow");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("prices.json"), PRICES).unwrap();
+    index_project(&tmp, &project, &[]);
+
+    let rows = query(&tmp, &project, "session-tokens", &[]);
+    assert_eq!(find(&rows, "id", "token_usage")["cost_usd"], 0.0148);
+}
+
+#[test]
+fn daily_and_workflow_cost() {
+    let (tmp, project) = setup_project(FIXTURES);
+    let prices = write_prices(&tmp);
+    index_project(&tmp, &project, &["--prices", prices.to_str().unwrap()]);
+
+    let days = query(&tmp, &project, "daily-tokens", &["since=2026-03-01"]);
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0]["total_tokens"], 1620);
+    assert_eq!(days[1]["sessions"], 2);
+
+    let workflows = query(&tmp, &project, "workflow-cost", &["prefix_len=7"]);
+    assert_eq!(workflows[0]["pattern"], "[autodev]");
+    assert_eq!(workflows[0]["cost_usd"], 0.0148);
+    assert_eq!(find(&workflows, "pattern", "explain")["total_tokens"], 15);
+}
+
+#[test]
+fn message_split_across_append_is_one_row() {
+    let (tmp, project) = setup_project(&[]);
+    let fixture = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sessions/token_usage.jsonl");
+    let content = std::fs::read_to_string(fixture).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+
+    let target = sessions_dir(&tmp, &project).join("token_usage.jsonl");
+
+    // Index up to the first line of msg_01, then the rest as an appended tail.
+    std::fs::write(&target, format!("{}\n", lines[..2].join("\n"))).unwrap();
+    index_project(&tmp, &project, &[]);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::fs::write(&target, &content).unwrap();
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("1 appended"));
+
+    let rows = query(&tmp, &project, "session-tokens", &[]);
+    assert_eq!(rows[0]["messages"], 2);
+    assert_eq!(rows[0]["total_tokens"], 3680);
+}
+
+#[test]
+fn invalid_price_file_fails_index() {
+    let (tmp, project) = setup_project(FIXTURES);
+    let prices = tmp.path().join("prices.json");
+    std::fs::write(&prices, r#"{"models": {"claude-opus-4": {"input": 15}}}"#).unwrap();
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .args(["--prices", prices.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid price table"));
+}
@@ -0,0 +1,152 @@
+/// Tool results: linking to tool_uses, failure perspectives, and the migration from v5.
+mod helpers;
+
+use helpers::{cli_with_home, db_path, query, setup_project};
+
+fn index_project(tmp: &tempfile::TempDir, project: &std::path::Path) {
+    cli_with_home(tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+fn sql(tmp: &tempfile::TempDir, project: &std::path::Path, sql: &str) -> Vec<serde_json::Value> {
+    let file = tmp.path().join("q.sql");
+    std::fs::write(&file, sql).unwrap();
+    let output = cli_with_home(tmp)
+        .args([
+            "query",
+            "--project",
+            project.to_str().unwrap(),
+            "--sql-file",
+            file.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    json.as_array().unwrap().clone()
+}
+
+#[test]
+fn results_link_to_their_tool_use_with_duration() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl"]);
+    index_project(&tmp, &project);
+
+    let rows = sql(
+        &tmp,
+        &project,
+        "SELECT u.seq_order, u.classified_name, r.is_error, r.output_size, r.error_text, r.duration_ms \
+         FROM tool_results r JOIN tool_uses u ON u.id = r.tool_use_id ORDER BY u.seq_order",
+    );
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[0]["classified_name"], "Bash:test");
+    assert_eq!(rows[0]["is_error"], 1);
+    assert_eq!(rows[0]["duration_ms"], 3000);
+    assert!(rows[0]["error_text"]
+        .as_str()
+        .unwrap()
+        .contains("cannot find value"));
+    // Successful results keep their size but not their text.
+    assert_eq!(rows[1]["is_error"], 0);
+    assert_eq!(rows[1]["output_size"], "fn login() {}".len());
+    assert!(rows[1]["error_text"].is_null());
+}
+
+#[test]
+fn tool_failures_reports_rate_per_classified_tool() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl", "multi_tool.jsonl"]);
+    index_project(&tmp, &project);
+
+    let rows = query(&tmp, &project, "tool-failures", &[]);
+    let test = rows.iter().find(|r| r["tool"] == "Bash:test").unwrap();
+    assert_eq!(test["results"], 3);
+    assert_eq!(test["failures"], 2);
+    assert_eq!(test["failure_rate"], 0.667);
+    // Tool uses without a recorded result (multi_tool.jsonl) are not counted.
+    let read = rows.iter().find(|r| r["tool"] == "Read").unwrap();
+    assert_eq!(read["results"], 1);
+    assert_eq!(rows[0]["tool"], "Bash:git");
+}
+
+#[test]
+fn failing_commands_groups_by_command() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl"]);
+    index_project(&tmp, &project);
+
+    let rows = query(&tmp, &project, "failing-commands", &[]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["command"], "cargo test login");
+    assert_eq!(rows[0]["failures"], 2);
+    assert_eq!(rows[1]["command"], "git push");
+}
+
+#[test]
+fn retry_loops_counts_retries_and_recoveries() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl"]);
+    index_project(&tmp, &project);
+
+    let rows = query(&tmp, &project, "retry-loops", &[]);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["tool"], "Bash:test");
+    assert_eq!(rows[0]["attempts"], 3);
+    assert_eq!(rows[0]["retries"], 2);
+    assert_eq!(rows[0]["recovered"], 1);
+
+    // git push failed once and was never retried.
+    assert!(query(&tmp, &project, "retry-loops", &["min_retries=0"])
+        .iter()
+        .any(|r| r["tool"] == "Bash:git" && r["retries"] == 0));
+}
+
+#[test]
+fn v5_database_migrates_and_backfills_results() {
+    let (tmp, project) = setup_project(&["tool_results.jsonl"]);
+    index_project(&tmp, &project);
+
+    // Roll the DB back to a v5 layout: no tool_results table, no checkpoints.
+    let conn = rusqlite::Connection::open(db_path(&tmp, &project)).unwrap();
+    conn.execute_batch(
+        "DROP TABLE commits;
+         ALTER TABLE prompts DROP COLUMN cwd;
+         ALTER TABLE prompts DROP COLUMN git_branch;
+         ALTER TABLE tool_uses DROP COLUMN cwd;
+         ALTER TABLE tool_uses DROP COLUMN git_branch;
+         DROP INDEX idx_sessions_project;
+         ALTER TABLE sessions DROP COLUMN project;
+         ALTER TABLE session_links DROP COLUMN project;
+         DROP TRIGGER prompts_fts_delete;
+         DROP TABLE prompts_fts;
+         DROP TABLE tool_results;
+         DROP INDEX idx_tool_uses_block;
+         DROP INDEX idx_session_links_b;
+         DROP VIEW message_costs;
+         DROP TABLE assistant_messages;
+         DROP TABLE model_prices;
+         DROP VIEW subagent_runs;
+         DROP TABLE messages;
+         DROP TABLE subagents;
+         ALTER TABLE tool_uses DROP COLUMN message_uuid;
+         ALTER TABLE tool_uses DROP COLUMN agent_id;
+         ALTER TABLE sessions DROP COLUMN parent_session_id;
+         ALTER TABLE tool_uses DROP COLUMN block_id;
+         ALTER TABLE sessions DROP COLUMN parsed_offset;
+         ALTER TABLE sessions DROP COLUMN last_entry_offset;
+         ALTER TABLE sessions DROP COLUMN last_entry_hash;
+         UPDATE meta SET value = '5' WHERE key = 'schema_version';",
+    )
+    .unwrap();
+    drop(conn);
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Schema migrated: v5 → v13"))
+        .stderr(predicates::str::contains("1 updated"));
+
+    let rows = sql(&tmp, &project, "SELECT COUNT(*) AS n FROM tool_results");
+    assert_eq!(rows[0]["n"], 6);
+}
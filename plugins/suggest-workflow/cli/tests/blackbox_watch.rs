@@ -0,0 +1,215 @@
+/// Watch mode: changed session files are re-indexed while `index --watch` runs,
+/// and queries wait for the index lock instead of reading a pass half-applied.
+mod helpers;
+
+use helpers::{cli_with_home, db_path, query, sessions_dir, setup_project};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A running `index --watch`, killed on drop; its stderr arrives line by line.
+struct Watcher {
+    child: Child,
+    stderr: Receiver<String>,
+}
+
+impl Watcher {
+    #[allow(deprecated)]
+    fn start(tmp: &tempfile::TempDir, project: &std::path::Path, extra: &[&str]) -> Self {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin("suggest-workflow"))
+            .env("HOME", tmp.path())
+            .args(["index", "--project", project.to_str().unwrap(), "--watch"])
+            .args(["--debounce-ms", "200"])
+            .args(extra)
+            .stderr(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let (tx, stderr) = mpsc::channel();
+        let pipe = child.stderr.take().unwrap();
+        std::thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let watcher = Self { child, stderr };
+        watcher.wait_for("Watching ");
+        watcher
+    }
+
+    /// Wait for a stderr line containing `needle` and return it.
+    fn wait_for(&self, needle: &str) -> String {
+        loop {
+            match self.stderr.recv_timeout(TIMEOUT) {
+                Ok(line) if line.contains(needle) => return line,
+                Ok(_) => {}
+                Err(e) => panic!("no stderr line containing {needle:?}: {e}"),
+            }
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn session_ids(tmp: &tempfile::TempDir, project: &std::path::Path) -> Vec<String> {
+    let mut ids: Vec<String> = query(tmp, project, "sessions", &[])
+        .iter()
+        .map(|r| r["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sessions")
+        .join(name)
+}
+
+#[test]
+fn watch_reindexes_only_touched_files() {
+    let (tmp, project) = setup_project(&["minimal.jsonl", "multi_tool.jsonl"]);
+    let dir = sessions_dir(&tmp, &project);
+    let watcher = Watcher::start(&tmp, &project, &[]);
+    assert_eq!(session_ids(&tmp, &project), ["minimal", "multi_tool"]);
+
+    // New file: only it is checked, the untouched sessions are not rescanned
+    std::fs::copy(
+        fixture("tool_results.jsonl"),
+        dir.join("tool_results.jsonl"),
+    )
+    .unwrap();
+    let line = watcher.wait_for("Indexed:");
+    assert!(line.contains("1 new"), "{line}");
+    assert!(line.contains("0 unchanged"), "{line}");
+    assert_eq!(
+        session_ids(&tmp, &project),
+        ["minimal", "multi_tool", "tool_results"]
+    );
+
+    // Appended entry resumes from the checkpoint
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(dir.join("minimal.jsonl"))
+        .unwrap();
+    writeln!(
+        file,
+        r#"{{"type":"user","message":{{"content":"watch mode test prompt"}},"timestamp":"2026-02-12T11:00:00+00:00"}}"#
+    )
+    .unwrap();
+    drop(file);
+    let line = watcher.wait_for("Indexed:");
+    assert!(line.contains("1 updated (1 appended)"), "{line}");
+
+    // Deleted file
+    std::fs::remove_file(dir.join("multi_tool.jsonl")).unwrap();
+    let line = watcher.wait_for("Indexed:");
+    assert!(line.contains("1 deleted"), "{line}");
+    assert_eq!(session_ids(&tmp, &project), ["minimal", "tool_results"]);
+}
+
+#[test]
+fn watch_flushes_a_file_that_keeps_changing() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    let dir = sessions_dir(&tmp, &project);
+    let watcher = Watcher::start(&tmp, &project, &[]);
+
+    // Appends arrive faster than the 200ms debounce for far longer than the
+    // 10x maximum wait; a pass must still run while they continue
+    let path = dir.join("minimal.jsonl");
+    let writing = Duration::from_secs(8);
+    let started = Instant::now();
+    let writer = std::thread::spawn(move || {
+        while started.elapsed() < writing {
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap();
+            writeln!(
+                file,
+                r#"{{"type":"user","message":{{"content":"still typing"}},"timestamp":"2026-02-12T11:00:00+00:00"}}"#
+            )
+            .unwrap();
+            drop(file);
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    });
+    let line = watcher.wait_for("Indexed:");
+    assert!(
+        started.elapsed() < writing,
+        "no pass until the writes stopped: {line}"
+    );
+    assert!(line.contains("appended"), "{line}");
+    writer.join().unwrap();
+}
+
+#[test]
+fn watch_falls_back_to_polling() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    let dir = sessions_dir(&tmp, &project);
+    let watcher = Watcher::start(&tmp, &project, &["--poll"]);
+
+    std::fs::copy(fixture("multi_tool.jsonl"), dir.join("multi_tool.jsonl")).unwrap();
+    let line = watcher.wait_for("Indexed:");
+    assert!(line.contains("1 new"), "{line}");
+    assert_eq!(session_ids(&tmp, &project), ["minimal", "multi_tool"]);
+}
+
+#[test]
+fn watch_requires_the_sessions_directory() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap(), "--watch"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("sessions directory not found"));
+}
+
+#[test]
+fn query_waits_for_the_index_lock() {
+    let (tmp, project) = setup_project(&["minimal.jsonl"]);
+    cli_with_home(&tmp)
+        .args(["index", "--project", project.to_str().unwrap()])
+        .assert()
+        .success();
+
+    // Hold the lock the way an index pass does
+    let lock_path = db_path(&tmp, &project).with_file_name("index.db.lock");
+    let lock = std::fs::File::open(&lock_path).unwrap();
+    lock.lock().unwrap();
+
+    #[allow(deprecated)]
+    let mut query = Command::new(assert_cmd::cargo::cargo_bin("suggest-workflow"))
+        .env("HOME", tmp.path())
+        .args(["query", "--project", project.to_str().unwrap()])
+        .args(["--perspective", "sessions"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(
+        query.try_wait().unwrap().is_none(),
+        "query ran while locked"
+    );
+
+    lock.unlock().unwrap();
+    let output = query.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Waiting for index lock"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+}
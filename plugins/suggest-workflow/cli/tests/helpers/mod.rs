@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use assert_cmd::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 /// Create an isolated test environment with fixture JSONL files.
@@ -17,16 +17,8 @@ pub fn setup_project(fixtures: &[&str]) -> (TempDir, PathBuf) {
     let project_dir = tmp.path().join("project");
     std::fs::create_dir_all(&project_dir).unwrap();
 
-    // Encode the project path the same way the CLI does
-    let canonical = project_dir.canonicalize().unwrap();
-    let normalized = canonical
-        .to_string_lossy()
-        .trim_end_matches('/')
-        .to_string();
-    let encoded = format!("-{}", &normalized[1..].replace('/', "-"));
-
     // Create sessions dir at $tmp/.claude/projects/{encoded}/
-    let sessions_dir = tmp.path().join(".claude").join("projects").join(&encoded);
+    let sessions_dir = sessions_dir(&tmp, &project_dir);
     std::fs::create_dir_all(&sessions_dir).unwrap();
 
     // Copy fixtures
@@ -41,6 +33,57 @@ pub fn setup_project(fixtures: &[&str]) -> (TempDir, PathBuf) {
     (tmp, project_dir)
 }
 
+/// Encode the project path the same way the CLI does: `/a/b` -> `-a-b`.
+pub fn encode_project(project: &Path) -> String {
+    let canonical = project.canonicalize().unwrap();
+    let normalized = canonical
+        .to_string_lossy()
+        .trim_end_matches('/')
+        .to_string();
+    format!("-{}", &normalized[1..].replace('/', "-"))
+}
+
+/// `$tmp/.claude/projects/{encoded}/` — where the project's session files live.
+pub fn sessions_dir(tmp: &TempDir, project: &Path) -> PathBuf {
+    tmp.path()
+        .join(".claude")
+        .join("projects")
+        .join(encode_project(project))
+}
+
+/// `$tmp/.claude/suggest-workflow-index/{encoded}/index.db` — the project's index DB.
+pub fn db_path(tmp: &TempDir, project: &Path) -> PathBuf {
+    tmp.path()
+        .join(".claude")
+        .join("suggest-workflow-index")
+        .join(encode_project(project))
+        .join("index.db")
+}
+
+/// Run a perspective against the project's index and return its rows.
+/// `params` are `key=value` pairs.
+pub fn query(
+    tmp: &TempDir,
+    project: &Path,
+    perspective: &str,
+    params: &[&str],
+) -> Vec<serde_json::Value> {
+    let mut cmd = cli_with_home(tmp);
+    cmd.args([
+        "query",
+        "--project",
+        project.to_str().unwrap(),
+        "--perspective",
+        perspective,
+    ]);
+    for p in params {
+        cmd.args(["--param", p]);
+    }
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    json.as_array().unwrap().clone()
+}
+
 /// Build a CLI command with HOME overridden to the temp directory.
 #[allow(deprecated)]
 pub fn cli_with_home(tmp: &TempDir) -> Command {